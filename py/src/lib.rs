@@ -4,6 +4,18 @@ use pyo3::prelude::*;
 pub mod scope;
 pub mod voltage_spectroscopy;
 
+/// Message of `error` followed by the messages of its sources.
+pub(crate) fn error_message(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        message.push_str(": ");
+        message.push_str(&error.to_string());
+        source = error.source();
+    }
+    message
+}
+
 /// Python exports
 #[pymodule]
 mod jpk_reader_rs {
@@ -11,11 +11,11 @@ pub mod export {
 
     #[pyfunction]
     pub fn load_data(path: PathBuf) -> PyResult<PyDataFrame> {
-        let loader =
-            scope::load_data(&path).map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
+        let loader = scope::load_data(&path)
+            .map_err(|err| PyRuntimeError::new_err(crate::error_message(&err)))?;
         let df = loader
             .collect()
-            .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
+            .map_err(|err| PyRuntimeError::new_err(crate::error_message(&err)))?;
 
         Ok(PyDataFrame(df))
     }
@@ -18,21 +18,25 @@ pub mod export {
     pub fn load_file(path: PathBuf) -> PyResult<PyDataFrame> {
         let mut reader = jpk::FileReader::new(path.clone()).map_err(|err| {
             PyRuntimeError::new_err(format!(
-                "could not load data collection of {path:?}: {err:?}"
+                "could not load data collection of {}: {}",
+                path.display(),
+                crate::error_message(&err)
             ))
         })?;
 
         let mut df = reader.load_data_all().map_err(|err| {
             PyRuntimeError::new_err(format!(
-                "could not load data of {:?}: {err:?}",
-                reader.path()
+                "could not load data of {}: {}",
+                reader.path().display(),
+                crate::error_message(&err)
             ))
         })?;
 
         let (x, y) = reader.position().map_err(|err| {
             PyRuntimeError::new_err(format!(
-                "could not get xy position of {:?}: {err:?}",
-                reader.path()
+                "could not get xy position of {}: {}",
+                reader.path().display(),
+                crate::error_message(&err)
             ))
         })?;
 
@@ -48,8 +52,14 @@ pub mod export {
             length,
         );
 
-        df.with_column(xcol).unwrap();
-        df.with_column(ycol).unwrap();
+        df.with_column(xcol)
+            .and_then(|df| df.with_column(ycol))
+            .map_err(|err| {
+                PyRuntimeError::new_err(format!(
+                    "could not add position: {}",
+                    crate::error_message(&err)
+                ))
+            })?;
 
         Ok(PyDataFrame(df))
     }
@@ -59,9 +69,12 @@ pub mod export {
     #[pyfunction]
     pub fn load_dir(path: PathBuf) -> PyResult<PyDataFrame> {
         let reader = jpk::DirReader::new(path);
-        let df = reader
-            .load_data_all()
-            .map_err(|err| PyRuntimeError::new_err(format!("could not load data: {err:?}")))?;
+        let df = reader.load_data_all().map_err(|err| {
+            PyRuntimeError::new_err(format!(
+                "could not load data: {}",
+                crate::error_message(&err)
+            ))
+        })?;
         Ok(PyDataFrame(df))
     }
 
@@ -0,0 +1,430 @@
+//! Benchmark reading the segment properties of a QI map.
+//!
+//! Reports the time to parse every `segment-header.properties` of the dataset and look up
+//! common keys, along with the allocations made while parsing and the memory retained by the
+//! parsed properties.
+//! Each is reported for [`Properties`] with keys interned as by `DatasetReader`,
+//! and for a baseline storing owned `(key, value)` pairs,
+//! the representation used before values were borrowed from the input.
+//! Files are decompressed before parsing, so decompression is excluded.
+//!
+//! # Usage
+//! `cargo bench --bench properties [-- <path/to/file.jpk-qi-data>]`
+//!
+//! Defaults to `data/qi_data/qi_data-2_0-lg.jpk-qi-data`.
+//! If the file can not be opened, e.g. it is a git-lfs pointer,
+//! a synthetic map of the same size is built from the `qi_data-2_0-xs` properties.
+use jpk_reader::dataset::{
+    properties::{KeyInterner, Properties},
+    v2_0::DatasetReader,
+};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    env, fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+const DEFAULT_DATA_PATH: &str = "../data/qi_data/qi_data-2_0-lg.jpk-qi-data";
+const SYNTHETIC_DATA_DIR: &str = "../data/qi_data/qi_data-2_0-xs";
+/// Pixels per side of the synthetic map.
+const SYNTHETIC_MAP_SIZE: usize = 128;
+const SEGMENT_PROPERTIES_FILE: &str = "segment-header.properties";
+const NUM_POINTS_KEY: &str = "force-segment-header.num-points";
+const CHANNELS_KEY: &str = "channels.list";
+const ITERATIONS: usize = 5;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator::new();
+
+fn main() {
+    let path = env::args()
+        .skip(1)
+        .find(|arg| !arg.starts_with('-'))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_DATA_PATH));
+
+    let data = match fs::read(&path)
+        .map_err(zip::result::ZipError::Io)
+        .and_then(|data| zip::ZipArchive::new(io::Cursor::new(data)))
+    {
+        Ok(archive) => {
+            println!("{path:?}");
+            archive
+        }
+        Err(err) => {
+            println!("could not open {path:?} ({err}), using synthetic map");
+            let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(SYNTHETIC_DATA_DIR);
+            zip::ZipArchive::new(synthetic_archive(&data_path, SYNTHETIC_MAP_SIZE)).unwrap()
+        }
+    };
+    let reader = DatasetReader::new(data).unwrap();
+    let mut archive = reader.archive().clone();
+    let paths = archive
+        .file_names()
+        .filter(|name| name.ends_with(SEGMENT_PROPERTIES_FILE))
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>();
+
+    let start = Instant::now();
+    let files = paths
+        .iter()
+        .map(|path| {
+            let mut file = archive.by_name(path).unwrap();
+            let mut data = Vec::with_capacity(file.size() as usize);
+            io::Read::read_to_end(&mut file, &mut data).unwrap();
+            data
+        })
+        .collect::<Vec<_>>();
+    println!("segment properties files: {}", files.len());
+    println!(
+        "size:        {} KiB",
+        files.iter().map(|file| file.len()).sum::<usize>() / 1024
+    );
+    println!("decompress:  {:?}", start.elapsed());
+
+    let baseline = measure(
+        &files,
+        |file, _| baseline::Properties::new(file),
+        |_| (),
+        baseline::Properties::lookup,
+    );
+    let current = measure(
+        &files,
+        |file, keys| Properties::new_interned(&mut &file[..], keys).unwrap(),
+        |keys| keys,
+        lookup,
+    );
+
+    println!("{:<13}{:>16}{:>16}", "", "baseline", "current");
+    let row = |name: &str, baseline: String, current: String| {
+        println!("{name:<13}{baseline:>16}{current:>16}")
+    };
+    row(
+        "parse:",
+        format!("{:.2?}", baseline.read),
+        format!("{:.2?}", current.read),
+    );
+    row(
+        "lookup:",
+        format!("{:.2?}", baseline.lookup),
+        format!("{:.2?}", current.lookup),
+    );
+    row(
+        "allocations:",
+        baseline.stats.allocations.to_string(),
+        current.stats.allocations.to_string(),
+    );
+    let kib = |size: usize| format!("{} KiB", size / 1024);
+    row(
+        "allocated:",
+        kib(baseline.stats.allocated),
+        kib(current.stats.allocated),
+    );
+    row(
+        "retained:",
+        kib(baseline.stats.retained),
+        kib(current.stats.retained),
+    );
+    row("peak:", kib(baseline.stats.peak), kib(current.stats.peak));
+    println!("(times are the median of {ITERATIONS} iterations)");
+}
+
+/// Parse every file and look up common keys, [`ITERATIONS`] times.
+///
+/// # Arguments
+/// + `parse`: Parse a file, using state shared between all files of an iteration.
+/// + `state`: Create the shared state of an iteration.
+/// + `lookup`: Look up keys of a parsed file.
+fn measure<S, T>(
+    files: &[Vec<u8>],
+    parse: impl Fn(&[u8], &S) -> T,
+    state: impl Fn(KeyInterner) -> S,
+    lookup: impl Fn(&T) -> usize,
+) -> Measurement {
+    let mut durations = Vec::with_capacity(ITERATIONS);
+    let mut stats = Stats::default();
+    for _ in 0..ITERATIONS {
+        ALLOCATOR.reset();
+        let start = Instant::now();
+        let shared = state(KeyInterner::new());
+        let mut properties = Vec::with_capacity(files.len());
+        for file in files.iter() {
+            properties.push(parse(file, &shared));
+        }
+        let read = start.elapsed();
+        stats = ALLOCATOR.stats();
+
+        let start = Instant::now();
+        let points = properties.iter().map(&lookup).sum::<usize>();
+        let lookup = start.elapsed();
+        assert!(points > 0);
+
+        durations.push((read, lookup));
+        drop(properties);
+        drop(shared);
+    }
+
+    let (read, lookup) = median(durations);
+    Measurement {
+        read,
+        lookup,
+        stats,
+    }
+}
+
+struct Measurement {
+    read: Duration,
+    lookup: Duration,
+    stats: Stats,
+}
+
+/// Look up keys typically read from segment properties.
+fn lookup(properties: &Properties) -> usize {
+    let num_points = properties
+        .get(NUM_POINTS_KEY)
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let channels = properties
+        .get(CHANNELS_KEY)
+        .map(|value| value.split_ascii_whitespace().count())
+        .unwrap_or(0);
+    num_points * channels
+}
+
+/// Build a QI map archive with `size` x `size` indices,
+/// using the dataset, shared data, and index `0` properties from the directory at `data_path`.
+fn synthetic_archive(data_path: &Path, size: usize) -> io::Cursor<Vec<u8>> {
+    let mut archive = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    for path in ["header.properties", "shared-data/header.properties"] {
+        archive.start_file(path, options).unwrap();
+        io::Write::write_all(&mut archive, &fs::read(data_path.join(path)).unwrap()).unwrap();
+    }
+
+    let index_header = fs::read(data_path.join("index/0/header.properties")).unwrap();
+    let segment_headers = (0..2)
+        .map(|segment| {
+            fs::read(data_path.join(format!(
+                "index/0/segments/{segment}/{SEGMENT_PROPERTIES_FILE}"
+            )))
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+    for index in 0..size * size {
+        archive
+            .start_file(format!("index/{index}/header.properties"), options)
+            .unwrap();
+        io::Write::write_all(&mut archive, &index_header).unwrap();
+        for (segment, header) in segment_headers.iter().enumerate() {
+            archive
+                .start_file(
+                    format!("index/{index}/segments/{segment}/{SEGMENT_PROPERTIES_FILE}"),
+                    options,
+                )
+                .unwrap();
+            io::Write::write_all(&mut archive, header).unwrap();
+        }
+    }
+
+    let mut buffer = archive.finish().unwrap();
+    buffer.set_position(0);
+    buffer
+}
+
+fn median(mut durations: Vec<(Duration, Duration)>) -> (Duration, Duration) {
+    let mid = durations.len() / 2;
+    durations.sort_by_key(|(read, _)| *read);
+    let read = durations[mid].0;
+    durations.sort_by_key(|(_, lookup)| *lookup);
+    (read, durations[mid].1)
+}
+
+#[derive(Default)]
+struct Stats {
+    allocations: usize,
+    allocated: usize,
+    retained: usize,
+    peak: usize,
+}
+
+/// Allocator tracking the memory allocated since the last reset.
+struct CountingAllocator {
+    allocations: AtomicUsize,
+    allocated: AtomicUsize,
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl CountingAllocator {
+    const fn new() -> Self {
+        Self {
+            allocations: AtomicUsize::new(0),
+            allocated: AtomicUsize::new(0),
+            current: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        }
+    }
+
+    fn reset(&self) {
+        self.allocations.store(0, Ordering::SeqCst);
+        self.allocated.store(0, Ordering::SeqCst);
+        self.current.store(0, Ordering::SeqCst);
+        self.peak.store(0, Ordering::SeqCst);
+    }
+
+    fn stats(&self) -> Stats {
+        Stats {
+            allocations: self.allocations.load(Ordering::SeqCst),
+            allocated: self.allocated.load(Ordering::SeqCst),
+            retained: self.current.load(Ordering::SeqCst),
+            peak: self.peak.load(Ordering::SeqCst),
+        }
+    }
+
+    fn add(&self, size: usize) {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        self.allocated.fetch_add(size, Ordering::Relaxed);
+        let current = self.current.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn remove(&self, size: usize) {
+        // memory allocated before a reset may be freed after it
+        let _ = self
+            .current
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                Some(current.saturating_sub(size))
+            });
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.add(layout.size());
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.remove(layout.size());
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        self.remove(layout.size());
+        self.add(new_size);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+/// Properties stored as owned `(key, value)` pairs, sorted by key.
+mod baseline {
+    pub struct Properties {
+        inner: Vec<(String, String)>,
+    }
+
+    impl Properties {
+        pub fn new(input: &[u8]) -> Self {
+            // properties files are ISO 8859-1
+            let input = input.iter().map(|&byte| byte as char).collect::<String>();
+            let mut properties = Vec::new();
+            let mut lines = input.lines();
+            while let Some(line) = lines.next() {
+                let mut line = line.trim_start().to_string();
+                if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+                    continue;
+                }
+
+                while continues(&line) {
+                    line.pop();
+                    match lines.next() {
+                        Some(next) => line.push_str(next.trim_start()),
+                        None => break,
+                    }
+                }
+
+                properties.push(key_value(&line));
+            }
+
+            // retain the last occurrence of duplicate keys
+            properties.reverse();
+            properties.sort_by(|(a, _), (b, _)| a.cmp(b));
+            properties.dedup_by(|(a, _), (b, _)| a == b);
+            Self { inner: properties }
+        }
+
+        pub fn get(&self, key: &str) -> Option<&String> {
+            self.inner
+                .binary_search_by_key(&key, |(key, _)| key)
+                .ok()
+                .map(|idx| &self.inner[idx].1)
+        }
+
+        pub fn lookup(&self) -> usize {
+            let num_points = self
+                .get(super::NUM_POINTS_KEY)
+                .and_then(|value| value.parse::<usize>().ok())
+                .unwrap_or(0);
+            let channels = self
+                .get(super::CHANNELS_KEY)
+                .map(|value| value.split_ascii_whitespace().count())
+                .unwrap_or(0);
+            num_points * channels
+        }
+    }
+
+    /// Whether the line ends with an odd number of `\`.
+    fn continues(line: &str) -> bool {
+        line.chars().rev().take_while(|&char| char == '\\').count() % 2 == 1
+    }
+
+    fn key_value(line: &str) -> (String, String) {
+        let mut escaped = false;
+        let end = line
+            .char_indices()
+            .find(|&(_, char)| {
+                let separator = !escaped && matches!(char, '=' | ':' | ' ' | '\t' | '\x0c');
+                escaped = !escaped && char == '\\';
+                separator
+            })
+            .map(|(idx, _)| idx)
+            .unwrap_or(line.len());
+
+        let value = line[end..].trim_start_matches([' ', '\t', '\x0c']);
+        let value = value
+            .strip_prefix(['=', ':'])
+            .unwrap_or(value)
+            .trim_start_matches([' ', '\t', '\x0c']);
+        (unescape(&line[..end]), unescape(value))
+    }
+
+    fn unescape(input: &str) -> String {
+        let mut output = String::with_capacity(input.len());
+        let mut chars = input.chars();
+        while let Some(char) = chars.next() {
+            if char != '\\' {
+                output.push(char);
+                continue;
+            }
+
+            match chars.next() {
+                Some('t') => output.push('\t'),
+                Some('n') => output.push('\n'),
+                Some('r') => output.push('\r'),
+                Some('f') => output.push('\x0c'),
+                Some('u') => {
+                    let code = chars.by_ref().take(4).collect::<String>();
+                    let char = u32::from_str_radix(&code, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .unwrap();
+                    output.push(char);
+                }
+                Some(char) => output.push(char),
+                None => {}
+            }
+        }
+        output
+    }
+}
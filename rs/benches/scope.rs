@@ -0,0 +1,112 @@
+//! Benchmark loading scope files.
+//!
+//! Reports the time to collect all columns, a single column, and the first rows of a file,
+//! for files with single and with repeated separators between values.
+//!
+//! # Usage
+//! `cargo bench --bench scope [-- <path/to/file.out>]`
+//!
+//! Without a path, synthetic delimited and whitespace separated files of
+//! [`SYNTHETIC_ROWS`] rows are written to the target directory.
+use jpk_reader::scope::ScopeFile;
+use polars::prelude::*;
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+const SYNTHETIC_ROWS: usize = 2_000_000;
+const HEAD_ROWS: u32 = 1_000;
+const ITERATIONS: usize = 5;
+
+fn main() {
+    let paths = match env::args().skip(1).find(|arg| !arg.starts_with('-')) {
+        Some(path) => vec![PathBuf::from(path)],
+        None => {
+            let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+            let delimited = dir.join("scope-delimited.out");
+            let whitespace = dir.join("scope-whitespace.out");
+            write_synthetic(&delimited, " ").unwrap();
+            write_synthetic(&whitespace, "  ").unwrap();
+            vec![delimited, whitespace]
+        }
+    };
+
+    for path in paths {
+        println!("{path:?}");
+        println!(
+            "size:     {} MiB",
+            fs::metadata(&path).unwrap().len() / (1024 * 1024)
+        );
+
+        let file = ScopeFile::open(&path).unwrap();
+        let columns = file.metadata().columns();
+        let column = columns
+            .last()
+            .map(|column| column.name().clone())
+            .unwrap_or_default();
+        println!("columns:  {}", columns.len());
+
+        let all = median(|| file.load_data().unwrap().collect().unwrap().height());
+        let single = median(|| {
+            file.load_data()
+                .unwrap()
+                .select([col(column.as_str())])
+                .collect()
+                .unwrap()
+                .height()
+        });
+        let head = median(|| {
+            file.load_data()
+                .unwrap()
+                .slice(0, HEAD_ROWS)
+                .collect()
+                .unwrap()
+                .height()
+        });
+
+        println!("all:      {all:?} (median of {ITERATIONS})");
+        println!("column:   {single:?} (median of {ITERATIONS})");
+        println!("head:     {head:?} (median of {ITERATIONS})");
+    }
+}
+
+/// Write a scope file with time, current, and deflection columns.
+///
+/// # Arguments
+/// + `separator`: Separator between values.
+fn write_synthetic(path: &Path, separator: &str) -> io::Result<()> {
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    writeln!(file, "# columns: t current vDeflection")?;
+    writeln!(
+        file,
+        "# fancyNames: \"Time\" \"Current\" \"Vertical Deflection\""
+    )?;
+    writeln!(file, "# units: s A V")?;
+    for row in 0..SYNTHETIC_ROWS {
+        let t = row as f64 * 1e-6;
+        writeln!(
+            file,
+            "{t:e}{separator}{:e}{separator}{:e}",
+            (t * 1e3).sin() * 1e-9,
+            (t * 7e2).cos() * 0.5,
+        )?;
+    }
+    file.flush()
+}
+
+/// Median duration of running `f`.
+/// Each run must produce rows.
+fn median(f: impl Fn() -> usize) -> Duration {
+    let mut durations = (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            assert!(f() > 0);
+            start.elapsed()
+        })
+        .collect::<Vec<_>>();
+    durations.sort();
+    durations[ITERATIONS / 2]
+}
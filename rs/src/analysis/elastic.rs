@@ -0,0 +1,676 @@
+//! Elastic model fitting of force curves.
+//!
+//! Models relate the force on the cantilever to the indentation of the sample
+//! past the contact point of a [`ProcessedCurve`].
+//! The Young's modulus is fit to the contact region of the extend segment.
+use super::force_curve::{self, FitRange, ForceCurve, ProcessedCurve};
+use crate::{
+    dataset::properties::{Properties, PropertyTree, error::Property as PropertyError},
+    qi_map::{
+        Data, Pixel,
+        v2_0::{PositionPattern, PositionPatternType},
+    },
+};
+use rayon::prelude::*;
+use std::f64::consts::PI;
+
+type Value = f64;
+
+/// Bilodeau's coefficient for a four sided pyramid.
+const PYRAMID_COEFFICIENT: Value = 0.7453;
+
+/// Iterations used to solve for the contact radius and the JKR modulus.
+const SOLVER_ITERATIONS: usize = 100;
+
+/// Relative step used to estimate the sensitivity of the JKR force to the modulus.
+const JKR_STEP: Value = 1e-6;
+
+/// Geometry of the indenting tip.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Tip {
+    /// Paraboloid with the given radius of curvature, in meters.
+    Paraboloid { radius: Value },
+
+    /// Sphere with the given radius, in meters.
+    Sphere { radius: Value },
+
+    /// Cone with the given half opening angle, in radians.
+    Cone { half_angle: Value },
+
+    /// Four sided pyramid with the given half angle to the faces, in radians.
+    Pyramid { half_angle: Value },
+}
+
+impl Tip {
+    /// `{prefix}.environment.cantilever-calibration-info`
+    pub const KEY: &str = "environment.cantilever-calibration-info";
+
+    /// `{prefix}.environment.cantilever-calibration-info.tip-radius`
+    const RADIUS_KEY: &str = "tip-radius";
+
+    /// `{prefix}.environment.cantilever-calibration-info.tip-half-angle`
+    const HALF_ANGLE_KEY: &str = "tip-half-angle";
+}
+
+impl Tip {
+    /// Tip recorded in the cantilever calibration.
+    /// A defined radius gives a [`Tip::Paraboloid`], otherwise a defined half angle gives a [`Tip::Cone`].
+    ///
+    /// # Arguments
+    /// + `properties`: The `{prefix}.environment.cantilever-calibration-info` tree.
+    ///
+    /// # Errors
+    /// + [`PropertyError::InvalidValue`]: If neither the radius nor the half angle is defined.
+    pub fn from_properties(properties: &PropertyTree) -> Result<Self, PropertyError> {
+        if let Some(radius) = properties.get_f64_non_nan(Self::RADIUS_KEY)? {
+            return Ok(Self::Paraboloid { radius });
+        }
+        if let Some(half_angle) = properties.get_f64_non_nan(Self::HALF_ANGLE_KEY)? {
+            return Ok(Self::Cone { half_angle });
+        }
+
+        Err(PropertyError::InvalidValue(
+            properties.key(Self::RADIUS_KEY),
+        ))
+    }
+}
+
+impl Tip {
+    /// Radius of curvature of a paraboloid or spherical tip.
+    pub fn radius(&self) -> Option<Value> {
+        match self {
+            Self::Paraboloid { radius } | Self::Sphere { radius } => Some(*radius),
+            Self::Cone { .. } | Self::Pyramid { .. } => None,
+        }
+    }
+}
+
+/// Contact model.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Model {
+    /// Hertz contact, valid for indentations small compared to the tip.
+    /// Spheres are approximated as paraboloids,
+    /// cones follow Sneddon and pyramids Bilodeau.
+    Hertz,
+
+    /// Sneddon contact, as [`Model::Hertz`] but exact for spherical tips.
+    Sneddon,
+
+    /// Derjaguin–Muller–Toporov contact,
+    /// Hertz contact offset by the adhesion force.
+    /// Requires a paraboloid or spherical tip.
+    Dmt,
+
+    /// Johnson–Kendall–Roberts contact,
+    /// for soft and strongly adhesive samples.
+    /// Requires a paraboloid or spherical tip.
+    Jkr,
+}
+
+/// Settings of a fit.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FitSettings {
+    pub model: Model,
+    pub tip: Tip,
+
+    /// Poisson's ratio of the sample.
+    pub poisson_ratio: Value,
+
+    /// Range used to fit the baseline of each curve.
+    pub baseline: FitRange,
+
+    /// Largest indentation to fit, in meters.
+    /// All points in contact are fit if `None`.
+    pub max_indentation: Option<Value>,
+}
+
+impl FitSettings {
+    /// Fit with a Poisson's ratio of 0.5, the default baseline range, and all points in contact.
+    pub fn new(model: Model, tip: Tip) -> Self {
+        Self {
+            model,
+            tip,
+            poisson_ratio: 0.5,
+            baseline: FitRange::default(),
+            max_indentation: None,
+        }
+    }
+}
+
+/// Result of a fit.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Fit {
+    modulus: Value,
+    uncertainty: Value,
+    residual: Value,
+    adhesion: Value,
+    points: usize,
+}
+
+impl Fit {
+    /// Young's modulus, in pascals.
+    pub fn modulus(&self) -> Value {
+        self.modulus
+    }
+
+    /// Standard error of the modulus, in pascals.
+    pub fn uncertainty(&self) -> Value {
+        self.uncertainty
+    }
+
+    /// Root mean square of the force residuals, in newtons.
+    pub fn residual(&self) -> Value {
+        self.residual
+    }
+
+    /// Adhesion force used by adhesive models, in newtons.
+    /// `0` for non-adhesive models.
+    pub fn adhesion(&self) -> Value {
+        self.adhesion
+    }
+
+    /// Number of points fit.
+    pub fn points(&self) -> usize {
+        self.points
+    }
+}
+
+/// Fit the contact region of the extend segment of a processed curve.
+///
+/// # Errors
+/// + [`error::Fit::UnsupportedTip`]: If the model requires a tip radius the tip does not have.
+/// + [`error::Fit::InsufficientData`]: If less than two points are in contact.
+pub fn fit(curve: &ProcessedCurve, settings: &FitSettings) -> Result<Fit, error::Fit> {
+    let adhesion = match settings.model {
+        Model::Hertz | Model::Sneddon => 0.0,
+        Model::Dmt | Model::Jkr => {
+            if settings.tip.radius().is_none() {
+                return Err(error::Fit::UnsupportedTip);
+            }
+
+            // the pull-off force is the most negative force of the retract segment
+            let adhesion = curve
+                .force(curve.retract())
+                .into_iter()
+                .filter(|force| !force.is_nan())
+                .fold(0.0, Value::min);
+            -adhesion
+        }
+    };
+
+    let indentation = curve.indentation(curve.extend());
+    let force = curve.force(curve.extend());
+    let (indentation, force): (Vec<_>, Vec<_>) = std::iter::zip(indentation, force)
+        .skip(curve.contact_point().index())
+        .filter(|(indentation, force)| {
+            *indentation >= 0.0
+                && settings
+                    .max_indentation
+                    .is_none_or(|max_indentation| *indentation <= max_indentation)
+                && !force.is_nan()
+        })
+        .unzip();
+    if indentation.len() < 2 {
+        return Err(error::Fit::InsufficientData);
+    }
+
+    let reduction = 1.0 / (1.0 - settings.poisson_ratio.powi(2));
+    match settings.model {
+        Model::Hertz | Model::Sneddon | Model::Dmt => {
+            let exact = settings.model == Model::Sneddon;
+            let basis = indentation
+                .iter()
+                .map(|indentation| reduction * basis(&settings.tip, *indentation, exact))
+                .collect::<Vec<_>>();
+            Ok(fit_linear(&basis, &force, adhesion))
+        }
+        Model::Jkr => {
+            let radius = settings.tip.radius().expect("radius checked by model");
+            Ok(fit_jkr(&indentation, &force, radius, reduction, adhesion))
+        }
+    }
+}
+
+/// Force per unit reduced modulus, `F / E*`, of a non-adhesive contact.
+///
+/// # Arguments
+/// + `exact`: Use the exact Sneddon solution for spherical tips.
+fn basis(tip: &Tip, indentation: Value, exact: bool) -> Value {
+    match *tip {
+        Tip::Sphere { radius } if exact => {
+            let contact_radius = sphere_contact_radius(radius, indentation);
+            let log = ((radius + contact_radius) / (radius - contact_radius)).ln();
+            0.5 * ((contact_radius.powi(2) + radius.powi(2)) * log - 2.0 * contact_radius * radius)
+        }
+        Tip::Paraboloid { radius } | Tip::Sphere { radius } => {
+            4.0 / 3.0 * radius.sqrt() * indentation.powf(1.5)
+        }
+        Tip::Cone { half_angle } => 2.0 / PI * half_angle.tan() * indentation.powi(2),
+        Tip::Pyramid { half_angle } => PYRAMID_COEFFICIENT * half_angle.tan() * indentation.powi(2),
+    }
+}
+
+/// Contact radius of a sphere solving `δ = a / 2 ln((R + a) / (R - a))`.
+fn sphere_contact_radius(radius: Value, indentation: Value) -> Value {
+    let indentation_at = |a: Value| 0.5 * a * ((radius + a) / (radius - a)).ln();
+    bisect(0.0, radius, |a| indentation_at(a) < indentation)
+}
+
+/// Least squares fit of `F = E b - F_ad`.
+fn fit_linear(basis: &[Value], force: &[Value], adhesion: Value) -> Fit {
+    let (numerator, denominator) = std::iter::zip(basis, force)
+        .fold((0.0, 0.0), |(numerator, denominator), (b, f)| {
+            (numerator + b * (f + adhesion), denominator + b * b)
+        });
+    let modulus = numerator / denominator;
+    let ssr = std::iter::zip(basis, force)
+        .map(|(b, f)| (f + adhesion - modulus * b).powi(2))
+        .sum::<Value>();
+
+    let points = force.len();
+    Fit {
+        modulus,
+        uncertainty: (ssr / (points - 1) as Value / denominator).sqrt(),
+        residual: (ssr / points as Value).sqrt(),
+        adhesion,
+        points,
+    }
+}
+
+/// Fit of the JKR model by minimizing the force residuals over the logarithm of the modulus.
+///
+/// # Arguments
+/// + `reduction`: Ratio of the reduced to the Young's modulus.
+fn fit_jkr(
+    indentation: &[Value],
+    force: &[Value],
+    radius: Value,
+    reduction: Value,
+    adhesion: Value,
+) -> Fit {
+    let predict = |modulus: Value| {
+        let stiffness = 4.0 / 3.0 * reduction * modulus;
+        indentation
+            .iter()
+            .map(|indentation| jkr_force(*indentation, radius, stiffness, adhesion))
+            .collect::<Vec<_>>()
+    };
+    let ssr = |modulus: Value| {
+        std::iter::zip(predict(modulus), force)
+            .map(|(predicted, force)| (force - predicted).powi(2))
+            .sum::<Value>()
+    };
+
+    // bracket around the DMT estimate, which neglects the adhesive neck
+    let basis = indentation
+        .iter()
+        .map(|indentation| reduction * 4.0 / 3.0 * radius.sqrt() * indentation.powf(1.5))
+        .collect::<Vec<_>>();
+    let estimate = fit_linear(&basis, force, adhesion)
+        .modulus
+        .abs()
+        .max(Value::MIN_POSITIVE);
+    let (mut lower, mut upper) = ((estimate / 1e3).ln(), (estimate * 1e3).ln());
+    let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+    for _ in 0..SOLVER_ITERATIONS {
+        let a = upper - ratio * (upper - lower);
+        let b = lower + ratio * (upper - lower);
+        if ssr(a.exp()) < ssr(b.exp()) {
+            upper = b;
+        } else {
+            lower = a;
+        }
+    }
+    let modulus = ((lower + upper) / 2.0).exp();
+
+    let ssr = ssr(modulus);
+    let high = predict(modulus * (1.0 + JKR_STEP));
+    let low = predict(modulus * (1.0 - JKR_STEP));
+    let sensitivity = std::iter::zip(high, low)
+        .map(|(high, low)| ((high - low) / (2.0 * JKR_STEP * modulus)).powi(2))
+        .sum::<Value>();
+
+    let points = force.len();
+    Fit {
+        modulus,
+        uncertainty: (ssr / (points - 1) as Value / sensitivity).sqrt(),
+        residual: (ssr / points as Value).sqrt(),
+        adhesion,
+        points,
+    }
+}
+
+/// Force of a JKR contact.
+///
+/// # Arguments
+/// + `stiffness`: `K = 4/3 E*`.
+/// + `adhesion`: Pull-off force, `3/2 π w R`.
+fn jkr_force(indentation: Value, radius: Value, stiffness: Value, adhesion: Value) -> Value {
+    // δ(a) = a² / R - 4/3 √(F_ad a / (R K)) is increasing past its minimum
+    let indentation_at =
+        |a: Value| a.powi(2) / radius - 4.0 / 3.0 * (adhesion * a / (radius * stiffness)).sqrt();
+    let lower = (radius * adhesion / (9.0 * stiffness)).cbrt();
+    let mut upper = lower.max(radius * Value::EPSILON) * 2.0;
+    while indentation_at(upper) < indentation {
+        upper *= 2.0;
+    }
+
+    let a = bisect(lower, upper, |a| indentation_at(a) < indentation);
+    let a3 = a.powi(3);
+    stiffness * a3 / radius - 2.0 * (adhesion * stiffness * a3 / radius).sqrt()
+}
+
+/// Bisect `[lower, upper]` for the boundary of `below`,
+/// which must hold at `lower` and not at `upper`.
+fn bisect(mut lower: Value, mut upper: Value, below: impl Fn(Value) -> bool) -> Value {
+    for _ in 0..SOLVER_ITERATIONS {
+        let mid = (lower + upper) / 2.0;
+        if below(mid) {
+            lower = mid;
+        } else {
+            upper = mid;
+        }
+    }
+    (lower + upper) / 2.0
+}
+
+/// Fits of each pixel of a map.
+pub struct ModulusMap {
+    i_length: usize,
+    j_length: usize,
+
+    /// Fit of each pixel, in row major order.
+    /// `None` if the pixel was not queried.
+    pixels: Vec<Option<Result<Fit, error::Fit>>>,
+}
+
+impl ModulusMap {
+    /// Number of pixels along the `i` axis.
+    pub fn i_length(&self) -> usize {
+        self.i_length
+    }
+
+    /// Number of pixels along the `j` axis.
+    pub fn j_length(&self) -> usize {
+        self.j_length
+    }
+
+    /// Fit of a pixel.
+    ///
+    /// # Returns
+    /// `None` if the pixel is out of bounds or was not queried.
+    pub fn get(&self, pixel: &Pixel) -> Option<&Result<Fit, error::Fit>> {
+        let (i, j) = (pixel.i() as usize, pixel.j() as usize);
+        if i >= self.i_length || j >= self.j_length {
+            return None;
+        }
+
+        self.pixels[j * self.i_length + i].as_ref()
+    }
+
+    /// Young's modulus of each pixel, indexed by `[j][i]`.
+    /// `NaN` where the pixel was not fit.
+    pub fn modulus(&self) -> Vec<Vec<Value>> {
+        self.map(Fit::modulus)
+    }
+
+    /// Uncertainty of the modulus of each pixel, indexed by `[j][i]`.
+    /// `NaN` where the pixel was not fit.
+    pub fn uncertainty(&self) -> Vec<Vec<Value>> {
+        self.map(Fit::uncertainty)
+    }
+
+    /// Residual of each pixel, indexed by `[j][i]`.
+    /// `NaN` where the pixel was not fit.
+    pub fn residual(&self) -> Vec<Vec<Value>> {
+        self.map(Fit::residual)
+    }
+
+    fn map(&self, value: impl Fn(&Fit) -> Value) -> Vec<Vec<Value>> {
+        self.pixels
+            .chunks(self.i_length.max(1))
+            .map(|row| {
+                row.iter()
+                    .map(|fit| match fit {
+                        Some(Ok(fit)) => value(fit),
+                        Some(Err(_)) | None => Value::NAN,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Fit every curve of a map in parallel.
+///
+/// # Arguments
+/// + `data`: Data of the curves to fit, as queried by [`super::force_curve::data_query`].
+/// + `position_pattern`: Position pattern of the map, used to lay out the pixels.
+/// + `shared`: Shared data properties of the map, used to read the
+///   [`spring_constant`](force_curve::spring_constant).
+/// + `spring_constant`: Spring constant of the cantilever, in N/m,
+///   overriding the one recorded in `shared`.
+///
+/// # Returns
+/// Fit of each pixel in `data`.
+/// Curves that could not be fit record their error.
+pub fn fit_map(
+    data: &Data,
+    position_pattern: &PositionPattern,
+    shared: &Properties,
+    spring_constant: Option<Value>,
+    settings: &FitSettings,
+) -> Result<ModulusMap, error::Map> {
+    let spring_constant = force_curve::spring_constant_or(shared, spring_constant)?;
+    let (i_length, j_length) = match position_pattern.kind() {
+        PositionPatternType::Grid(grid) => (grid.i_length() as usize, grid.j_length() as usize),
+    };
+
+    let fits = super::data_indices(data)
+        .into_par_iter()
+        .map(|index| {
+            let fit = ForceCurve::from_data_with(data, index, spring_constant)
+                .and_then(|curve| curve.process(&settings.baseline))
+                .map_err(error::Fit::from)
+                .and_then(|curve| fit(&curve, settings));
+            (index, fit)
+        })
+        .collect::<Vec<_>>();
+
+    let mut pixels = (0..i_length * j_length).map(|_| None).collect::<Vec<_>>();
+    for (index, fit) in fits {
+        let pixel = position_pattern
+            .index_to_pixel(index)
+            .ok_or(error::Map::InvalidIndex(index))?;
+        pixels[pixel.j() as usize * i_length + pixel.i() as usize] = Some(fit);
+    }
+
+    Ok(ModulusMap {
+        i_length,
+        j_length,
+        pixels,
+    })
+}
+
+pub mod error {
+    use crate::{analysis::force_curve::error::ForceCurve, dataset::v2_0::IndexType};
+    use std::fmt;
+
+    #[derive(derive_more::From, Debug)]
+    pub enum Fit {
+        /// The curve could not be processed.
+        ForceCurve(ForceCurve),
+
+        /// The model requires a paraboloid or spherical tip.
+        UnsupportedTip,
+
+        /// Less than two points are in contact.
+        InsufficientData,
+    }
+
+    impl fmt::Display for Fit {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::ForceCurve(_) => write!(f, "could not process curve"),
+                Self::UnsupportedTip => write!(f, "model requires a paraboloid or spherical tip"),
+                Self::InsufficientData => write!(f, "not enough points in contact to fit"),
+            }
+        }
+    }
+
+    impl std::error::Error for Fit {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::ForceCurve(err) => Some(err),
+                Self::UnsupportedTip | Self::InsufficientData => None,
+            }
+        }
+    }
+
+    #[derive(derive_more::From, Debug)]
+    pub enum Map {
+        /// The spring constant could not be read.
+        SpringConstant(ForceCurve),
+
+        /// The index is not part of the position pattern.
+        #[from(ignore)]
+        InvalidIndex(IndexType),
+    }
+
+    impl fmt::Display for Map {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::SpringConstant(_) => write!(f, "could not read spring constant"),
+                Self::InvalidIndex(index) => {
+                    write!(f, "index {index} is not part of the position pattern")
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for Map {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::SpringConstant(err) => Some(err),
+                Self::InvalidIndex(_) => None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analysis::synthetic;
+
+    const SPRING_CONSTANT: Value = 1.0;
+    const MODULUS: Value = 1e6;
+    const RADIUS: Value = 1e-6;
+    const CONTACT: Value = 5e-7;
+
+    /// Processed curve of a sample following `force(indentation)`,
+    /// with the height decreasing towards the sample.
+    /// The tip pulls off the sample with a force of `pull_off` when fully retracted.
+    fn processed_curve(force: impl Fn(Value) -> Value, pull_off: Value) -> ProcessedCurve {
+        // the deflection reduces the indentation, solve for their balance
+        let extend = synthetic::extend(200, 5e-9, -1.0, |height| {
+            let approach = CONTACT - height;
+            if approach <= 0.0 {
+                return 0.0;
+            }
+            let balance =
+                |deflection: Value| deflection * SPRING_CONSTANT < force(approach - deflection);
+            bisect(0.0, approach, balance)
+        });
+        let retract = synthetic::retract(&extend, Some((200, -pull_off / SPRING_CONSTANT)));
+
+        ForceCurve::new(extend, retract, SPRING_CONSTANT)
+            .process(&FitRange::default())
+            .unwrap()
+    }
+
+    fn assert_modulus(fit: &Fit, tolerance: Value) {
+        let error = (fit.modulus() - MODULUS).abs() / MODULUS;
+        assert!(error < tolerance, "modulus {} != {MODULUS}", fit.modulus());
+    }
+
+    #[test]
+    fn fit_hertz() {
+        let reduced = MODULUS / (1.0 - 0.25);
+        let tips = [
+            Tip::Paraboloid { radius: RADIUS },
+            Tip::Cone { half_angle: 0.3 },
+            Tip::Pyramid { half_angle: 0.3 },
+        ];
+        for tip in tips {
+            let curve =
+                processed_curve(|indentation| reduced * basis(&tip, indentation, false), 0.0);
+            let fit = fit(&curve, &FitSettings::new(Model::Hertz, tip)).unwrap();
+            assert_modulus(&fit, 1e-3);
+            assert!(fit.uncertainty() < MODULUS * 1e-3);
+            assert!(fit.points() > 50);
+        }
+    }
+
+    #[test]
+    fn fit_sneddon_sphere() {
+        let reduced = MODULUS / (1.0 - 0.25);
+        let tip = Tip::Sphere { radius: RADIUS };
+        let curve = processed_curve(|indentation| reduced * basis(&tip, indentation, true), 0.0);
+        let fit_exact = fit(&curve, &FitSettings::new(Model::Sneddon, tip)).unwrap();
+        assert_modulus(&fit_exact, 1e-3);
+
+        let fit_hertz = fit(&curve, &FitSettings::new(Model::Hertz, tip)).unwrap();
+        assert!(fit_hertz.residual() > fit_exact.residual());
+    }
+
+    #[test]
+    fn fit_adhesive() {
+        const ADHESION: Value = 2e-8;
+        let stiffness = 4.0 / 3.0 * MODULUS / (1.0 - 0.25);
+        let tip = Tip::Paraboloid { radius: RADIUS };
+
+        let dmt = |indentation: Value| stiffness * RADIUS.sqrt() * indentation.powf(1.5) - ADHESION;
+        let curve = processed_curve(|indentation| dmt(indentation).max(0.0), ADHESION);
+        let settings = FitSettings {
+            max_indentation: Some(4e-7),
+            ..FitSettings::new(Model::Dmt, tip)
+        };
+        let result = fit(&curve, &settings).unwrap();
+        assert!((result.adhesion() - ADHESION).abs() < 1e-15);
+        assert_modulus(&result, 1e-2);
+
+        let jkr = |indentation: Value| jkr_force(indentation, RADIUS, stiffness, ADHESION);
+        let curve = processed_curve(|indentation| jkr(indentation).max(0.0), ADHESION);
+        let settings = FitSettings {
+            max_indentation: Some(4e-7),
+            ..FitSettings::new(Model::Jkr, tip)
+        };
+        let result = fit(&curve, &settings).unwrap();
+        assert_modulus(&result, 1e-2);
+        assert!(result.uncertainty().is_finite());
+
+        let settings = FitSettings::new(Model::Jkr, Tip::Cone { half_angle: 0.3 });
+        assert!(matches!(
+            fit(&curve, &settings),
+            Err(error::Fit::UnsupportedTip)
+        ));
+    }
+
+    #[test]
+    fn jkr_force_without_adhesion() {
+        let stiffness = 4.0 / 3.0 * MODULUS;
+        let indentation: Value = 1e-7;
+        let hertz = stiffness * RADIUS.sqrt() * indentation.powf(1.5);
+        let jkr = jkr_force(indentation, RADIUS, stiffness, 0.0);
+        assert!((jkr - hertz).abs() / hertz < 1e-9);
+    }
+
+    #[test]
+    fn fit_error_source() {
+        let err = error::Fit::from(force_curve::error::ForceCurve::NoContact);
+        assert_eq!(err.to_string(), "could not process curve");
+        let source = std::error::Error::source(&err).unwrap();
+        assert_eq!(source.to_string(), "could not find a contact point");
+    }
+}
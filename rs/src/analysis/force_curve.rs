@@ -0,0 +1,731 @@
+//! Baseline correction, contact point detection and indentation of force curves.
+//!
+//! A force curve consists of an extend segment, approaching the sample,
+//! followed by a retract segment, withdrawing from it.
+//! Each segment holds the measured height of the cantilever base
+//! and the deflection of the cantilever, both in meters.
+//! The tip–sample separation is the height minus the deflection,
+//! with the deflection taken along the direction of the height towards the sample.
+//! The direction is determined from the extend segment,
+//! so heights that decrease towards the sample, as `measuredHeight` does, are supported.
+use crate::{
+    dataset::{
+        properties::Properties,
+        v2_0::{IndexType, SegmentType, lcd_info::LcdInfo, properties::SharedData},
+    },
+    qi_map::{ChannelQuery, Data, DataIndex, DataQuery, IndexQuery, SegmentQuery},
+};
+use std::{collections::HashMap, ops::Range};
+
+type Value = f64;
+
+/// Channel of the measured height of the cantilever base.
+pub const HEIGHT_CHANNEL: &str = "measuredHeight";
+
+/// Channel of the vertical deflection of the cantilever.
+pub const DEFLECTION_CHANNEL: &str = "vDeflection";
+
+/// Segment approaching the sample.
+pub const EXTEND_SEGMENT: SegmentType = 0;
+
+/// Segment withdrawing from the sample.
+pub const RETRACT_SEGMENT: SegmentType = 1;
+
+/// Calibration slot of the deflection in meters.
+const DISTANCE_SLOT: &str = "distance";
+
+/// Calibration slot of the deflection in newtons.
+/// Its multiplier is the spring constant.
+const FORCE_SLOT: &str = "force";
+
+const DISTANCE_UNIT: &str = "m";
+const FORCE_UNIT: &str = "N";
+
+/// Query of the channels of the force curves at `index`,
+/// with the deflection in meters.
+pub fn data_query(index: IndexQuery) -> DataQuery {
+    DataQuery {
+        index,
+        segment: SegmentQuery::Indices(vec![EXTEND_SEGMENT, RETRACT_SEGMENT]),
+        channel: ChannelQuery::include([HEIGHT_CHANNEL, DEFLECTION_CHANNEL]),
+        calibration_slots: HashMap::from([(
+            DEFLECTION_CHANNEL.to_string(),
+            DISTANCE_SLOT.to_string(),
+        )]),
+    }
+}
+
+/// Spring constant of the cantilever, in N/m,
+/// from the `force` calibration of the [`DEFLECTION_CHANNEL`].
+/// Calibrations overridden on the reader are included.
+///
+/// # Arguments
+/// + `shared`: Shared data properties of the map,
+///   as queried by [`MetadataQuery::SharedData`](crate::qi_map::MetadataQuery::SharedData).
+///
+/// # Errors
+/// + [`error::ForceCurve::MissingCalibration`]: If the deflection does not have a `force` calibration.
+pub fn spring_constant(shared: &Properties) -> Result<Value, error::ForceCurve> {
+    let lcd_infos = shared.subtree(SharedData::LCD_INFO_KEY);
+    for index in lcd_infos.children() {
+        let info = LcdInfo::from_properties(&lcd_infos.subtree(index))
+            .map_err(error::ForceCurve::Property)?;
+        if info.channel_info().name == DEFLECTION_CHANNEL {
+            return info
+                .calibration(FORCE_SLOT)
+                .map(|scaling| scaling.multiplier())
+                .ok_or(error::ForceCurve::MissingCalibration);
+        }
+    }
+
+    Err(error::ForceCurve::MissingCalibration)
+}
+
+/// # Returns
+/// `spring_constant` if given, otherwise the [`spring_constant`] recorded in `shared`.
+pub(super) fn spring_constant_or(
+    shared: &Properties,
+    spring_constant: Option<Value>,
+) -> Result<Value, error::ForceCurve> {
+    match spring_constant {
+        Some(spring_constant) => Ok(spring_constant),
+        None => self::spring_constant(shared),
+    }
+}
+
+/// Range of the extend segment used to fit the baseline,
+/// as fractions of the segment measured from its start, far from the sample.
+///
+/// The headers do not record a fit range.
+/// Their `baseline-adjust-settings` only state whether the instrument
+/// adjusted the baseline during acquisition.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FitRange {
+    start: f64,
+    end: f64,
+}
+
+impl FitRange {
+    /// # Returns
+    /// `None` if the range is not within `[0, 1]` or `start` is not less than `end`.
+    pub fn new(start: f64, end: f64) -> Option<Self> {
+        ((0.0..1.0).contains(&start) && start < end && end <= 1.0).then_some(Self { start, end })
+    }
+
+    pub fn start(&self) -> f64 {
+        self.start
+    }
+
+    pub fn end(&self) -> f64 {
+        self.end
+    }
+
+    /// Indices of a segment with `len` points within the range.
+    pub fn indices(&self, len: usize) -> Range<usize> {
+        let start = (self.start * len as f64).floor() as usize;
+        let end = (self.end * len as f64).ceil() as usize;
+        start.min(len)..end.min(len)
+    }
+}
+
+impl Default for FitRange {
+    /// First 30% of the extend segment.
+    fn default() -> Self {
+        Self {
+            start: 0.0,
+            end: 0.3,
+        }
+    }
+}
+
+/// Height and deflection of a segment, in meters.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Segment {
+    height: Vec<Value>,
+    deflection: Vec<Value>,
+}
+
+impl Segment {
+    pub fn new(height: Vec<Value>, deflection: Vec<Value>) -> Result<Self, error::ForceCurve> {
+        if height.len() != deflection.len() {
+            return Err(error::ForceCurve::InvalidDataLength);
+        }
+
+        Ok(Self { height, deflection })
+    }
+}
+
+impl Segment {
+    pub fn height(&self) -> &Vec<Value> {
+        &self.height
+    }
+
+    pub fn deflection(&self) -> &Vec<Value> {
+        &self.deflection
+    }
+
+    /// Number of data points.
+    pub fn len(&self) -> usize {
+        self.height.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.height.is_empty()
+    }
+
+    /// Direction of the height towards the sample,
+    /// `1` if the height increases over the segment and `-1` otherwise.
+    fn direction(&self) -> Value {
+        match (self.height.first(), self.height.last()) {
+            (Some(start), Some(end)) if end > start => 1.0,
+            _ => -1.0,
+        }
+    }
+
+    /// Tip–sample separation, the height minus the deflection along the height.
+    ///
+    /// # Arguments
+    /// + `direction`: Direction of the height towards the sample, `1` or `-1`.
+    fn separation(&self, direction: Value) -> Vec<Value> {
+        std::iter::zip(&self.height, &self.deflection)
+            .map(|(height, deflection)| height - direction * deflection)
+            .collect()
+    }
+}
+
+/// Linear baseline of the deflection as a function of the height.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Baseline {
+    offset: Value,
+    slope: Value,
+}
+
+impl Baseline {
+    pub fn new(offset: Value, slope: Value) -> Self {
+        Self { offset, slope }
+    }
+
+    /// Least squares fit of the deflection against the height within `range`.
+    ///
+    /// # Errors
+    /// + [`error::ForceCurve::InsufficientData`]: If `range` contains less than two points.
+    pub fn fit(segment: &Segment, range: &FitRange) -> Result<Self, error::ForceCurve> {
+        let indices = range.indices(segment.len());
+        if indices.len() < 2 {
+            return Err(error::ForceCurve::InsufficientData);
+        }
+
+        let height = &segment.height[indices.clone()];
+        let deflection = &segment.deflection[indices];
+        let n = height.len() as Value;
+        let height_mean = height.iter().sum::<Value>() / n;
+        let deflection_mean = deflection.iter().sum::<Value>() / n;
+        let (covariance, variance) = std::iter::zip(height, deflection).fold(
+            (0.0, 0.0),
+            |(covariance, variance), (height, deflection)| {
+                let dh = height - height_mean;
+                (
+                    covariance + dh * (deflection - deflection_mean),
+                    variance + dh * dh,
+                )
+            },
+        );
+
+        // constant height, only the offset can be determined
+        let slope = if variance > 0.0 {
+            covariance / variance
+        } else {
+            0.0
+        };
+        Ok(Self {
+            offset: deflection_mean - slope * height_mean,
+            slope,
+        })
+    }
+}
+
+impl Baseline {
+    pub fn offset(&self) -> Value {
+        self.offset
+    }
+
+    pub fn slope(&self) -> Value {
+        self.slope
+    }
+
+    /// Baseline deflection at `height`.
+    pub fn evaluate(&self, height: Value) -> Value {
+        self.offset + self.slope * height
+    }
+
+    /// Subtract the baseline from the deflection of `segment`.
+    pub fn subtract(&self, segment: &Segment) -> Segment {
+        let deflection = std::iter::zip(&segment.height, &segment.deflection)
+            .map(|(height, deflection)| deflection - self.evaluate(*height))
+            .collect();
+        Segment {
+            height: segment.height.clone(),
+            deflection,
+        }
+    }
+}
+
+/// Point of the extend segment where the tip contacts the sample.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ContactPoint {
+    index: usize,
+    separation: Value,
+}
+
+impl ContactPoint {
+    /// Detect the contact point of a baseline corrected extend segment.
+    /// Walks back from the maximum deflection to the last point
+    /// whose deflection is not positive.
+    ///
+    /// # Errors
+    /// + [`error::ForceCurve::NoContact`]: If the deflection never rises above,
+    ///   or never returns to, the baseline.
+    pub fn detect(extend: &Segment) -> Result<Self, error::ForceCurve> {
+        let peak = max_index(&extend.deflection).ok_or(error::ForceCurve::NoContact)?;
+        if extend.deflection[peak] <= 0.0 {
+            return Err(error::ForceCurve::NoContact);
+        }
+
+        let index = extend.deflection[..=peak]
+            .iter()
+            .rposition(|deflection| *deflection <= 0.0)
+            .ok_or(error::ForceCurve::NoContact)?;
+        Ok(Self {
+            index,
+            separation: extend.height[index] - extend.direction() * extend.deflection[index],
+        })
+    }
+}
+
+impl ContactPoint {
+    /// Index of the contact point in the extend segment.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Tip–sample separation at contact.
+    pub fn separation(&self) -> Value {
+        self.separation
+    }
+}
+
+/// Extend and retract segments of a force curve.
+#[derive(Clone, Debug)]
+pub struct ForceCurve {
+    extend: Segment,
+    retract: Segment,
+    spring_constant: Value,
+}
+
+impl ForceCurve {
+    /// # Arguments
+    /// + `spring_constant`: Spring constant of the cantilever, in N/m,
+    ///   e.g. from [`spring_constant`].
+    pub fn new(extend: Segment, retract: Segment, spring_constant: Value) -> Self {
+        Self {
+            extend,
+            retract,
+            spring_constant,
+        }
+    }
+
+    /// Create a force curve from the data of a QI or force map.
+    ///
+    /// # Arguments
+    /// + `data`: Data containing the [`HEIGHT_CHANNEL`] and [`DEFLECTION_CHANNEL`]
+    ///   of the [`EXTEND_SEGMENT`] and [`RETRACT_SEGMENT`] at `index`.
+    ///   The height must be in meters, the deflection in meters or newtons.
+    /// + `index`: Index of the curve.
+    /// + `shared`: Shared data properties of the map, used to read the [`spring_constant`].
+    /// + `spring_constant`: Spring constant of the cantilever, in N/m,
+    ///   overriding the one recorded in `shared`.
+    pub fn from_data(
+        data: &Data,
+        index: IndexType,
+        shared: &Properties,
+        spring_constant: Option<Value>,
+    ) -> Result<Self, error::ForceCurve> {
+        let spring_constant = spring_constant_or(shared, spring_constant)?;
+        Self::from_data_with(data, index, spring_constant)
+    }
+
+    /// Create a force curve from the data of a QI or force map.
+    /// See [`Self::from_data`].
+    ///
+    /// # Arguments
+    /// + `spring_constant`: Spring constant of the cantilever, in N/m.
+    ///   Used to convert a deflection in newtons to meters.
+    pub(super) fn from_data_with(
+        data: &Data,
+        index: IndexType,
+        spring_constant: Value,
+    ) -> Result<Self, error::ForceCurve> {
+        let segment = |segment| -> Result<Segment, error::ForceCurve> {
+            let height = channel_data(data, index, segment, HEIGHT_CHANNEL)?;
+            let height = match height {
+                (height, DISTANCE_UNIT) => height.clone(),
+                (_, unit) => return Err(invalid_unit(HEIGHT_CHANNEL, unit)),
+            };
+
+            let deflection = channel_data(data, index, segment, DEFLECTION_CHANNEL)?;
+            let deflection = match deflection {
+                (deflection, DISTANCE_UNIT) => deflection.clone(),
+                (deflection, FORCE_UNIT) => deflection
+                    .iter()
+                    .map(|force| force / spring_constant)
+                    .collect(),
+                (_, unit) => return Err(invalid_unit(DEFLECTION_CHANNEL, unit)),
+            };
+
+            Segment::new(height, deflection)
+        };
+
+        Ok(Self {
+            extend: segment(EXTEND_SEGMENT)?,
+            retract: segment(RETRACT_SEGMENT)?,
+            spring_constant,
+        })
+    }
+}
+
+impl ForceCurve {
+    pub fn extend(&self) -> &Segment {
+        &self.extend
+    }
+
+    pub fn retract(&self) -> &Segment {
+        &self.retract
+    }
+
+    /// Spring constant of the cantilever, in N/m.
+    pub fn spring_constant(&self) -> Value {
+        self.spring_constant
+    }
+
+    /// Fit the baseline of the extend segment.
+    pub fn fit_baseline(&self, range: &FitRange) -> Result<Baseline, error::ForceCurve> {
+        Baseline::fit(&self.extend, range)
+    }
+
+    /// Subtract the baseline fit within `range` from both segments
+    /// and detect the contact point.
+    pub fn process(&self, range: &FitRange) -> Result<ProcessedCurve, error::ForceCurve> {
+        let baseline = self.fit_baseline(range)?;
+        let extend = baseline.subtract(&self.extend);
+        let retract = baseline.subtract(&self.retract);
+        let contact_point = ContactPoint::detect(&extend)?;
+        let direction = extend.direction();
+        Ok(ProcessedCurve {
+            baseline,
+            contact_point,
+            extend,
+            retract,
+            spring_constant: self.spring_constant,
+            direction,
+        })
+    }
+}
+
+/// Baseline corrected force curve with its contact point.
+#[derive(Clone, Debug)]
+pub struct ProcessedCurve {
+    baseline: Baseline,
+    contact_point: ContactPoint,
+    extend: Segment,
+    retract: Segment,
+    spring_constant: Value,
+
+    /// Direction of the height towards the sample.
+    direction: Value,
+}
+
+impl ProcessedCurve {
+    pub fn baseline(&self) -> &Baseline {
+        &self.baseline
+    }
+
+    pub fn contact_point(&self) -> &ContactPoint {
+        &self.contact_point
+    }
+
+    /// Baseline corrected extend segment.
+    pub fn extend(&self) -> &Segment {
+        &self.extend
+    }
+
+    /// Baseline corrected retract segment.
+    pub fn retract(&self) -> &Segment {
+        &self.retract
+    }
+
+    /// Spring constant of the cantilever, in N/m.
+    pub fn spring_constant(&self) -> Value {
+        self.spring_constant
+    }
+
+    /// Tip–sample separation of a segment.
+    pub fn separation(&self, segment: &Segment) -> Vec<Value> {
+        segment.separation(self.direction)
+    }
+
+    /// Indentation of the sample, relative to the contact point.
+    /// Positive when the tip is in contact with the sample,
+    /// regardless of the direction of the height.
+    pub fn indentation(&self, segment: &Segment) -> Vec<Value> {
+        segment
+            .separation(self.direction)
+            .into_iter()
+            .map(|separation| (separation - self.contact_point.separation) * self.direction)
+            .collect()
+    }
+
+    /// Force on the cantilever, in newtons.
+    pub fn force(&self, segment: &Segment) -> Vec<Value> {
+        segment
+            .deflection
+            .iter()
+            .map(|deflection| deflection * self.spring_constant)
+            .collect()
+    }
+}
+
+/// # Returns
+/// Data and unit of a channel.
+fn channel_data<'a>(
+    data: &'a Data,
+    index: IndexType,
+    segment: SegmentType,
+    channel: &str,
+) -> Result<(&'a Vec<Value>, &'a str), error::ForceCurve> {
+    let idx = DataIndex::new(index, segment, channel);
+    let values = data
+        .get(&idx)
+        .ok_or_else(|| error::ForceCurve::MissingChannel {
+            segment,
+            channel: channel.to_string(),
+        })?;
+    let unit = data.unit(&idx).map(String::as_str).unwrap_or_default();
+    Ok((values, unit))
+}
+
+fn invalid_unit(channel: &str, unit: &str) -> error::ForceCurve {
+    error::ForceCurve::InvalidUnit {
+        channel: channel.to_string(),
+        unit: unit.to_string(),
+    }
+}
+
+/// Index of the maximum value, ignoring `NaN`s.
+fn max_index(values: &[Value]) -> Option<usize> {
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| !value.is_nan())
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(idx, _)| idx)
+}
+
+pub mod error {
+    use crate::dataset::{properties::error::Property, v2_0::SegmentType};
+    use std::fmt;
+
+    #[derive(Debug)]
+    pub enum ForceCurve {
+        /// A channel is not present in the data.
+        MissingChannel {
+            segment: SegmentType,
+            channel: String,
+        },
+
+        /// A channel is not in a supported unit.
+        InvalidUnit { channel: String, unit: String },
+
+        /// The height and deflection do not have the same number of points.
+        InvalidDataLength,
+
+        /// The baseline fit range does not contain enough points.
+        InsufficientData,
+
+        /// A contact point could not be found.
+        NoContact,
+
+        /// The deflection does not have a `force` calibration to read the spring constant from.
+        MissingCalibration,
+
+        /// The calibration of a channel could not be read.
+        Property(Property),
+    }
+
+    impl fmt::Display for ForceCurve {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::MissingChannel { segment, channel } => {
+                    write!(f, "channel `{channel}` of segment {segment} is missing")
+                }
+                Self::InvalidUnit { channel, unit } => {
+                    write!(f, "channel `{channel}` has unsupported unit `{unit}`")
+                }
+                Self::InvalidDataLength => {
+                    write!(f, "height and deflection have different lengths")
+                }
+                Self::InsufficientData => write!(f, "not enough points to fit the baseline"),
+                Self::NoContact => write!(f, "could not find a contact point"),
+                Self::MissingCalibration => {
+                    write!(f, "deflection does not have a force calibration")
+                }
+                Self::Property(_) => write!(f, "could not read channel calibration"),
+            }
+        }
+    }
+
+    impl std::error::Error for ForceCurve {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::Property(err) => Some(err),
+                Self::MissingChannel { .. }
+                | Self::InvalidUnit { .. }
+                | Self::InvalidDataLength
+                | Self::InsufficientData
+                | Self::NoContact
+                | Self::MissingCalibration => None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analysis::synthetic;
+
+    const SPRING_CONSTANT: f64 = 0.5;
+    const STEP: f64 = 1e-8;
+    const CONTACT_STEP: f64 = 40.0;
+    const STIFFNESS: f64 = 0.25;
+
+    /// Extend segment approaching from 1 um to 0, in the direction of `direction`,
+    /// with a linear response past contact.
+    fn extend(baseline: &Baseline, direction: f64) -> Segment {
+        synthetic::extend(100, STEP, direction, |height| {
+            let response = (CONTACT_STEP * STEP - height).max(0.0) * STIFFNESS;
+            response + baseline.evaluate(height)
+        })
+    }
+
+    #[test]
+    fn fit_range_indices() {
+        assert_eq!(FitRange::default().indices(100), 0..30);
+        assert_eq!(FitRange::new(0.5, 1.0).unwrap().indices(5), 2..5);
+        assert!(FitRange::new(0.5, 0.5).is_none());
+        assert!(FitRange::new(-0.1, 0.5).is_none());
+        assert!(FitRange::new(0.0, 1.1).is_none());
+    }
+
+    #[test]
+    fn baseline_fit() {
+        let expected = Baseline::new(2e-9, 0.01);
+        let baseline = Baseline::fit(&extend(&expected, -1.0), &FitRange::default()).unwrap();
+        assert!((baseline.offset() - expected.offset()).abs() < 1e-15);
+        assert!((baseline.slope() - expected.slope()).abs() < 1e-9);
+
+        let short = Segment::new(vec![0.0], vec![0.0]).unwrap();
+        assert!(matches!(
+            Baseline::fit(&short, &FitRange::default()),
+            Err(error::ForceCurve::InsufficientData)
+        ));
+    }
+
+    #[test]
+    fn process_force_curve() {
+        let expected = (CONTACT_STEP * (1.0 - STIFFNESS)) * STEP;
+        for direction in [-1.0, 1.0] {
+            let extend = extend(&Baseline::new(0.0, 0.0), direction);
+            let retract = synthetic::retract(&extend, None);
+            let curve = ForceCurve::new(extend, retract, SPRING_CONSTANT);
+            let processed = curve.process(&FitRange::default()).unwrap();
+
+            let contact = processed.contact_point();
+            assert_eq!(contact.index(), 60);
+            assert!((contact.separation() + direction * CONTACT_STEP * STEP).abs() < 1e-15);
+
+            let indentation = processed.indentation(processed.extend());
+            assert!(
+                indentation[..60]
+                    .iter()
+                    .all(|indentation| *indentation < 0.0)
+            );
+            assert!((indentation[100] - expected).abs() < 1e-15);
+
+            let force = processed.force(processed.extend());
+            let expected_force = CONTACT_STEP * STEP * STIFFNESS * SPRING_CONSTANT;
+            assert!((force[100] - expected_force).abs() < 1e-15);
+
+            let indentation = processed.indentation(processed.retract());
+            assert!((indentation[0] - expected).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn process_no_contact() {
+        let height = (0..100).map(|idx| idx as f64 * STEP).collect::<Vec<_>>();
+        let segment = Segment::new(height, vec![0.0; 100]).unwrap();
+        let curve = ForceCurve::new(segment.clone(), segment, SPRING_CONSTANT);
+        assert!(matches!(
+            curve.process(&FitRange::default()),
+            Err(error::ForceCurve::NoContact)
+        ));
+    }
+
+    #[test]
+    fn from_data_converts_force() {
+        let indices = [EXTEND_SEGMENT, RETRACT_SEGMENT]
+            .into_iter()
+            .flat_map(|segment| {
+                [HEIGHT_CHANNEL, DEFLECTION_CHANNEL]
+                    .map(|channel| DataIndex::new(0, segment, channel))
+            })
+            .collect::<Vec<_>>();
+        let units = ["m", "N", "m", "N"].map(String::from).to_vec();
+        let data = vec![
+            vec![1.0, 2.0],
+            vec![0.5, 1.0],
+            vec![2.0, 1.0],
+            vec![1.0, 0.5],
+        ];
+        let data = Data::new(indices, data, units).unwrap();
+
+        let shared = Properties::parse("").unwrap();
+        let curve = ForceCurve::from_data(&data, 0, &shared, Some(SPRING_CONSTANT)).unwrap();
+        assert_eq!(curve.extend().deflection(), &vec![1.0, 2.0]);
+        assert_eq!(curve.retract().deflection(), &vec![2.0, 1.0]);
+        assert!(matches!(
+            ForceCurve::from_data(&data, 1, &shared, Some(SPRING_CONSTANT)),
+            Err(error::ForceCurve::MissingChannel { segment: 0, .. })
+        ));
+
+        let indices = vec![DataIndex::new(0, EXTEND_SEGMENT, HEIGHT_CHANNEL)];
+        let data = Data::new(indices, vec![vec![0.0]], vec!["V".to_string()]).unwrap();
+        assert!(matches!(
+            ForceCurve::from_data(&data, 0, &shared, Some(SPRING_CONSTANT)),
+            Err(error::ForceCurve::InvalidUnit { .. })
+        ));
+        assert!(matches!(
+            ForceCurve::from_data(&data, 0, &shared, None),
+            Err(error::ForceCurve::MissingCalibration)
+        ));
+    }
+
+    #[test]
+    fn spring_constant_from_calibration() {
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../data/qi_data/qi_data-2_0-xs/shared-data/header.properties");
+        let shared = Properties::new(&mut std::fs::File::open(path).unwrap()).unwrap();
+        assert_eq!(spring_constant(&shared).unwrap(), 2.8307938999982456);
+        assert_eq!(spring_constant_or(&shared, Some(0.1)).unwrap(), 0.1);
+    }
+}
@@ -0,0 +1,353 @@
+//! Adhesion, dissipation and slope of force curves.
+//!
+//! Energies are integrated over the indentation of a [`ProcessedCurve`],
+//! so the work done by the cantilever on the sample is positive.
+use super::{
+    force_curve::{self, FitRange, ForceCurve, ProcessedCurve, Segment},
+    trapezoid,
+};
+use crate::{
+    dataset::properties::Properties,
+    qi_map::{Data, v2_0::PositionPattern},
+};
+use polars::prelude as pl;
+use rayon::prelude::*;
+
+type Value = f64;
+
+const I_COLUMN: &str = "i";
+const J_COLUMN: &str = "j";
+const ADHESION_COLUMN: &str = "adhesion";
+const WORK_OF_ADHESION_COLUMN: &str = "work_of_adhesion";
+const DISSIPATION_COLUMN: &str = "dissipation";
+const SLOPE_COLUMN: &str = "slope";
+const ERROR_COLUMN: &str = "error";
+
+/// Settings of the extraction.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Settings {
+    /// Range used to fit the baseline of each curve.
+    pub baseline: FitRange,
+
+    /// Range of the extend segment used to fit the slope.
+    pub slope: FitRange,
+}
+
+impl Default for Settings {
+    /// Default baseline range, and the slope of the last 20% of the extend segment.
+    fn default() -> Self {
+        Self {
+            baseline: FitRange::default(),
+            slope: FitRange::new(0.8, 1.0).expect("range should be valid"),
+        }
+    }
+}
+
+/// Scalar properties of a force curve.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Mechanics {
+    adhesion: Value,
+    work_of_adhesion: Value,
+    dissipation: Value,
+    slope: Value,
+}
+
+impl Mechanics {
+    /// # Arguments
+    /// + `slope`: Range of the extend segment to fit the slope in.
+    ///
+    /// # Errors
+    /// + [`error::Mechanics::InsufficientData`]: If the slope range contains less than two points.
+    pub fn from_curve(curve: &ProcessedCurve, slope: &FitRange) -> Result<Self, error::Mechanics> {
+        let retract_force = curve.force(curve.retract());
+        let retract_indentation = curve.indentation(curve.retract());
+        let adhesion = -retract_force
+            .iter()
+            .copied()
+            .filter(|force| !force.is_nan())
+            .fold(0.0, Value::min);
+
+        // attractive part of the retract segment, withdrawing lowers the indentation
+        let work_of_adhesion = trapezoid(
+            &retract_indentation,
+            &retract_force
+                .iter()
+                .map(|force| force.min(0.0))
+                .collect::<Vec<_>>(),
+        );
+
+        let extend_force = curve.force(curve.extend());
+        let extend_indentation = curve.indentation(curve.extend());
+        let dissipation = trapezoid(&extend_indentation, &extend_force)
+            + trapezoid(&retract_indentation, &retract_force);
+
+        Ok(Self {
+            adhesion,
+            work_of_adhesion,
+            dissipation,
+            slope: fit_slope(curve, curve.extend(), slope)?,
+        })
+    }
+}
+
+impl Mechanics {
+    /// Pull-off force, the depth of the minimum of the retract force, in newtons.
+    pub fn adhesion(&self) -> Value {
+        self.adhesion
+    }
+
+    /// Area of the attractive part of the retract segment, in joules.
+    pub fn work_of_adhesion(&self) -> Value {
+        self.work_of_adhesion
+    }
+
+    /// Area enclosed by the extend and retract segments, in joules.
+    pub fn dissipation(&self) -> Value {
+        self.dissipation
+    }
+
+    /// Slope of the force against the approach of the cantilever base, in N/m.
+    pub fn slope(&self) -> Value {
+        self.slope
+    }
+}
+
+/// Extract the properties of every curve of a map in parallel.
+///
+/// # Arguments
+/// + `data`: Data of the curves, as queried by [`super::force_curve::data_query`].
+/// + `position_pattern`: Position pattern of the map.
+/// + `shared`: Shared data properties of the map, used to read the
+///   [`spring_constant`](force_curve::spring_constant).
+/// + `spring_constant`: Spring constant of the cantilever, in N/m,
+///   overriding the one recorded in `shared`.
+///
+/// # Returns
+/// Data frame with `i` and `j` pixel columns, ordered by index,
+/// `adhesion`, `work_of_adhesion`, `dissipation` and `slope` columns,
+/// and an `error` column.
+/// Curves that could not be processed record their error, and their properties are null.
+pub fn mechanics_map(
+    data: &Data,
+    position_pattern: &PositionPattern,
+    shared: &Properties,
+    spring_constant: Option<Value>,
+    settings: &Settings,
+) -> Result<pl::DataFrame, error::Map> {
+    let spring_constant = force_curve::spring_constant_or(shared, spring_constant)?;
+    let indices = super::data_indices(data);
+    let mechanics = indices
+        .par_iter()
+        .map(|index| {
+            ForceCurve::from_data_with(data, *index, spring_constant)
+                .and_then(|curve| curve.process(&settings.baseline))
+                .map_err(error::Mechanics::from)
+                .and_then(|curve| Mechanics::from_curve(&curve, &settings.slope))
+        })
+        .collect::<Vec<_>>();
+
+    let (i, j) = indices
+        .iter()
+        .map(|index| {
+            let pixel = position_pattern
+                .index_to_pixel(*index)
+                .ok_or(error::Map::InvalidIndex(*index))?;
+            Ok((pixel.i(), pixel.j()))
+        })
+        .collect::<Result<(Vec<_>, Vec<_>), error::Map>>()?;
+
+    let column = |name: &str, value: fn(&Mechanics) -> Value| {
+        let values = mechanics
+            .iter()
+            .map(|mechanics| mechanics.as_ref().ok().map(value))
+            .collect::<Vec<_>>();
+        pl::Column::new(name.into(), values)
+    };
+    let errors = mechanics
+        .iter()
+        .map(|mechanics| {
+            mechanics
+                .as_ref()
+                .err()
+                .map(|err| super::error_message(err))
+        })
+        .collect::<Vec<_>>();
+    let df = pl::DataFrame::new(vec![
+        pl::Column::new(I_COLUMN.into(), i),
+        pl::Column::new(J_COLUMN.into(), j),
+        column(ADHESION_COLUMN, Mechanics::adhesion),
+        column(WORK_OF_ADHESION_COLUMN, Mechanics::work_of_adhesion),
+        column(DISSIPATION_COLUMN, Mechanics::dissipation),
+        column(SLOPE_COLUMN, Mechanics::slope),
+        pl::Column::new(ERROR_COLUMN.into(), errors),
+    ])?;
+    Ok(df)
+}
+
+/// Least squares slope of the force against the approach, `indentation + deflection`,
+/// within `range` of `segment`.
+fn fit_slope(
+    curve: &ProcessedCurve,
+    segment: &Segment,
+    range: &FitRange,
+) -> Result<Value, error::Mechanics> {
+    let indices = range.indices(segment.len());
+    if indices.len() < 2 {
+        return Err(error::Mechanics::InsufficientData);
+    }
+
+    let approach = std::iter::zip(curve.indentation(segment), segment.deflection())
+        .map(|(indentation, deflection)| indentation + deflection)
+        .collect::<Vec<_>>();
+    let force = curve.force(segment);
+    let approach = &approach[indices.clone()];
+    let force = &force[indices];
+
+    let n = approach.len() as Value;
+    let approach_mean = approach.iter().sum::<Value>() / n;
+    let force_mean = force.iter().sum::<Value>() / n;
+    let (covariance, variance) = std::iter::zip(approach, force).fold(
+        (0.0, 0.0),
+        |(covariance, variance), (approach, force)| {
+            let da = approach - approach_mean;
+            (covariance + da * (force - force_mean), variance + da * da)
+        },
+    );
+    if variance == 0.0 {
+        return Err(error::Mechanics::InsufficientData);
+    }
+
+    Ok(covariance / variance)
+}
+
+pub mod error {
+    use crate::{analysis::force_curve::error::ForceCurve, dataset::v2_0::IndexType};
+    use std::fmt;
+
+    #[derive(derive_more::From, Debug)]
+    pub enum Mechanics {
+        /// The curve could not be processed.
+        ForceCurve(ForceCurve),
+
+        /// The slope range does not contain enough distinct points.
+        InsufficientData,
+    }
+
+    impl fmt::Display for Mechanics {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::ForceCurve(_) => write!(f, "could not process curve"),
+                Self::InsufficientData => write!(f, "not enough points to fit the slope"),
+            }
+        }
+    }
+
+    impl std::error::Error for Mechanics {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::ForceCurve(err) => Some(err),
+                Self::InsufficientData => None,
+            }
+        }
+    }
+
+    #[derive(derive_more::From, Debug)]
+    pub enum Map {
+        /// The spring constant could not be read.
+        SpringConstant(ForceCurve),
+
+        /// The index is not part of the position pattern.
+        #[from(ignore)]
+        InvalidIndex(IndexType),
+
+        Polars(polars::error::PolarsError),
+    }
+
+    impl fmt::Display for Map {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::SpringConstant(_) => write!(f, "could not read spring constant"),
+                Self::InvalidIndex(index) => {
+                    write!(f, "index {index} is not part of the position pattern")
+                }
+                Self::Polars(_) => write!(f, "could not build data frame"),
+            }
+        }
+    }
+
+    impl std::error::Error for Map {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::SpringConstant(err) => Some(err),
+                Self::InvalidIndex(_) => None,
+                Self::Polars(err) => Some(err),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analysis::synthetic;
+
+    const SPRING_CONSTANT: Value = 2.0;
+    const STEP: Value = 1e-8;
+
+    /// Curve with the height decreasing towards the sample, in contact for the last 50 points,
+    /// where a stiff sample deflects the cantilever by half the approach.
+    /// The retract segment follows the extend segment back,
+    /// with an attractive force of `pull_off` at contact.
+    fn processed_curve(pull_off: Value) -> ProcessedCurve {
+        let extend = synthetic::extend(100, STEP, -1.0, |height| {
+            (50.0 * STEP - height).max(0.0) / 2.0
+        });
+        let retract = synthetic::retract(&extend, Some((50, -pull_off / SPRING_CONSTANT)));
+
+        ForceCurve::new(extend, retract, SPRING_CONSTANT)
+            .process(&FitRange::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn mechanics_from_curve() {
+        const PULL_OFF: Value = 1e-8;
+        let curve = processed_curve(PULL_OFF);
+        let mechanics = Mechanics::from_curve(&curve, &Settings::default().slope).unwrap();
+        assert!((mechanics.adhesion() - PULL_OFF).abs() < 1e-20);
+        assert!((mechanics.slope() - SPRING_CONSTANT / 2.0).abs() < 1e-9);
+
+        // the pull-off point lies half a step in contact, one and a half steps from the next point
+        let work = PULL_OFF * 1.5 * STEP / 2.0;
+        assert!((mechanics.work_of_adhesion() - work).abs() < 1e-25);
+
+        // the pull-off point also skips the last half step of unloading of the extend segment
+        let unloading = 0.5 * STEP * (SPRING_CONSTANT * 0.5 * STEP) / 2.0;
+        assert!((mechanics.dissipation() - (work + unloading)).abs() < 1e-25);
+
+        let range = FitRange::new(0.995, 1.0).unwrap();
+        assert!(matches!(
+            Mechanics::from_curve(&curve, &range),
+            Err(error::Mechanics::InsufficientData)
+        ));
+    }
+
+    #[test]
+    fn mechanics_without_adhesion() {
+        let curve = processed_curve(0.0);
+        let mechanics = Mechanics::from_curve(&curve, &Settings::default().slope).unwrap();
+        assert_eq!(mechanics.adhesion(), 0.0);
+        assert_eq!(mechanics.work_of_adhesion(), 0.0);
+        assert!(mechanics.dissipation().abs() < 1e-25);
+    }
+
+    #[test]
+    fn mechanics_error_message() {
+        let err = error::Mechanics::from(force_curve::error::ForceCurve::NoContact);
+        assert_eq!(err.to_string(), "could not process curve");
+        assert_eq!(
+            crate::analysis::error_message(&err),
+            "could not process curve: could not find a contact point"
+        );
+    }
+}
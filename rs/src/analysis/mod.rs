@@ -0,0 +1,101 @@
+//! Post-processing of loaded data.
+use crate::{dataset::v2_0::IndexType, qi_map::Data};
+
+pub mod elastic;
+pub mod force_curve;
+pub mod mechanics;
+#[cfg(feature = "voltage_spectroscopy")]
+pub mod voltage_spectroscopy;
+
+/// Indices of the curves in `data`, in order.
+fn data_indices(data: &Data) -> Vec<IndexType> {
+    let mut indices = data
+        .indices()
+        .iter()
+        .map(|idx| idx.index)
+        .collect::<Vec<_>>();
+    indices.dedup();
+    indices
+}
+
+/// Message of `error` followed by the messages of its sources.
+fn error_message(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        message.push_str(": ");
+        message.push_str(&error.to_string());
+        source = error.source();
+    }
+    message
+}
+
+/// Trapezoidal integral of `y` over `x`, following the order of the points.
+fn trapezoid(x: &[f64], y: &[f64]) -> f64 {
+    std::iter::zip(x.windows(2), y.windows(2))
+        .map(|(x, y)| (x[1] - x[0]) * (y[0] + y[1]) / 2.0)
+        .filter(|area| !area.is_nan())
+        .sum()
+}
+
+/// Synthetic force curves for tests.
+#[cfg(test)]
+mod synthetic {
+    use super::force_curve::Segment;
+
+    /// Extend segment approaching the sample from `steps * step` to `0`.
+    ///
+    /// # Arguments
+    /// + `direction`: Direction of the height towards the sample, `-1` if it decreases.
+    /// + `deflection`: Deflection of the cantilever at a distance from the sample.
+    pub fn extend(
+        steps: usize,
+        step: f64,
+        direction: f64,
+        deflection: impl Fn(f64) -> f64,
+    ) -> Segment {
+        let distance = (0..=steps)
+            .map(|idx| (steps - idx) as f64 * step)
+            .collect::<Vec<_>>();
+        let deflection = distance
+            .iter()
+            .map(|distance| deflection(*distance))
+            .collect();
+        let height = distance
+            .into_iter()
+            .map(|distance| -direction * distance)
+            .collect();
+        Segment::new(height, deflection).unwrap()
+    }
+
+    /// Retract segment following `extend` back.
+    ///
+    /// # Arguments
+    /// + `pull_off`: Index of the retract segment where the tip pulls off the sample,
+    /// and the deflection there.
+    pub fn retract(extend: &Segment, pull_off: Option<(usize, f64)>) -> Segment {
+        let mut deflection = extend
+            .deflection()
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Vec<_>>();
+        if let Some((index, pull_off)) = pull_off {
+            deflection[index] = pull_off;
+        }
+        Segment::new(extend.height().iter().rev().copied().collect(), deflection).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn trapezoid_follows_order() {
+        let x = [0.0, 1.0, 2.0];
+        let y = [0.0, 1.0, 2.0];
+        assert_eq!(trapezoid(&x, &y), 2.0);
+        assert_eq!(trapezoid(&[2.0, 1.0, 0.0], &[2.0, 1.0, 0.0]), -2.0);
+    }
+}
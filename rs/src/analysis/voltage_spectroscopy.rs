@@ -0,0 +1,886 @@
+//! Differential conductance and hysteresis of voltage spectroscopy ramps.
+//!
+//! A triangle voltage spectroscopy sweeps the bias between two voltages in consecutive segments.
+//! A forward ramp increases the bias, a reverse ramp decreases it.
+//! Consecutive forward and reverse ramps over the same voltage range form a [`Sweep`].
+use super::{error_message, trapezoid};
+use crate::{
+    dataset::{
+        properties::{PropertyTree, error::Property as PropertyError},
+        v2_0::SegmentType,
+    },
+    voltage_spectroscopy::v2_0::{DirReader, FileReader, Reader},
+};
+use polars::prelude as pl;
+use rayon::prelude::*;
+use std::{fs, io, path::Path};
+
+type Value = f64;
+
+/// Channel of the applied bias.
+pub const BIAS_CHANNEL: &str = "cafmBias";
+
+/// Channel of the measured current.
+pub const CURRENT_CHANNEL: &str = "cafmCurrent";
+
+const BIAS_UNIT: &str = "V";
+const CURRENT_UNIT: &str = "A";
+
+/// Relative tolerance of the voltages of paired ramps.
+const VOLTAGE_TOLERANCE: Value = 1e-9;
+
+const PATH_COLUMN: &str = "path";
+const X_COLUMN: &str = "x";
+const Y_COLUMN: &str = "y";
+const FORWARD_SEGMENT_COLUMN: &str = "forward_segment";
+const REVERSE_SEGMENT_COLUMN: &str = "reverse_segment";
+const ZERO_BIAS_CONDUCTANCE_COLUMN: &str = "zero_bias_conductance";
+const RECTIFICATION_RATIO_COLUMN: &str = "rectification_ratio";
+const HYSTERESIS_AREA_COLUMN: &str = "hysteresis_area";
+const ERROR_COLUMN: &str = "error";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    /// The bias increases.
+    Forward,
+
+    /// The bias decreases.
+    Reverse,
+}
+
+/// Voltage range of a segment.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RampSettings {
+    start_voltage: Value,
+    end_voltage: Value,
+}
+
+impl RampSettings {
+    /// `{settings}.segment.{segment}.start-voltage`
+    const START_VOLTAGE_KEY: &str = "start-voltage";
+
+    /// `{settings}.segment.{segment}.end-voltage`
+    const END_VOLTAGE_KEY: &str = "end-voltage";
+}
+
+impl RampSettings {
+    pub fn new(start_voltage: Value, end_voltage: Value) -> Self {
+        Self {
+            start_voltage,
+            end_voltage,
+        }
+    }
+
+    /// # Arguments
+    /// + `properties`: The `{settings}.segment.{segment}` tree.
+    pub fn from_properties(properties: &PropertyTree) -> Result<Self, PropertyError> {
+        let start_voltage = properties.get_f64(Self::START_VOLTAGE_KEY)?;
+        let end_voltage = properties.get_f64(Self::END_VOLTAGE_KEY)?;
+        Ok(Self {
+            start_voltage,
+            end_voltage,
+        })
+    }
+}
+
+impl RampSettings {
+    pub fn start_voltage(&self) -> Value {
+        self.start_voltage
+    }
+
+    pub fn end_voltage(&self) -> Value {
+        self.end_voltage
+    }
+
+    /// # Returns
+    /// `None` if the bias is constant.
+    pub fn direction(&self) -> Option<Direction> {
+        if self.end_voltage > self.start_voltage {
+            Some(Direction::Forward)
+        } else if self.end_voltage < self.start_voltage {
+            Some(Direction::Reverse)
+        } else {
+            None
+        }
+    }
+
+    /// Whether `other` sweeps the same voltage range in the opposite direction.
+    fn is_reverse_of(&self, other: &Self) -> bool {
+        let tolerance = (self.end_voltage - self.start_voltage).abs() * VOLTAGE_TOLERANCE;
+        self.direction().is_some()
+            && (self.start_voltage - other.end_voltage).abs() <= tolerance
+            && (self.end_voltage - other.start_voltage).abs() <= tolerance
+    }
+}
+
+/// Segments of a triangle voltage spectroscopy.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TriangleSettings {
+    segments: Vec<RampSettings>,
+}
+
+impl TriangleSettings {
+    /// `voltage-spectroscopy-segment-series.header.voltage-spectroscopy-settings`
+    pub const KEY: &str =
+        "voltage-spectroscopy-segment-series.header.voltage-spectroscopy-settings";
+
+    /// `{settings}.type`
+    const TYPE_KEY: &str = "type";
+
+    const TYPE: &str = "triangle-voltage-spectroscopy-settings";
+
+    /// `{settings}.segments.size`
+    const SEGMENTS_COUNT_KEY: &str = "segments.size";
+
+    /// `{settings}.segment`
+    const SEGMENT_KEY: &str = "segment";
+}
+
+impl TriangleSettings {
+    pub fn new(segments: Vec<RampSettings>) -> Self {
+        Self { segments }
+    }
+
+    /// # Arguments
+    /// + `properties`: The `voltage-spectroscopy-segment-series.header.voltage-spectroscopy-settings` tree.
+    ///
+    /// # Errors
+    /// + [`PropertyError::InvalidValue`]: If the settings are not triangle settings.
+    pub fn from_properties(properties: &PropertyTree) -> Result<Self, PropertyError> {
+        properties.get_with(Self::TYPE_KEY, |kind| (kind == Self::TYPE).then_some(()))?;
+        let count = properties.parse::<SegmentType>(Self::SEGMENTS_COUNT_KEY)?;
+        let segment = properties.subtree(Self::SEGMENT_KEY);
+        let segments = (0..count)
+            .map(|idx| RampSettings::from_properties(&segment.subtree(idx.to_string())))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { segments })
+    }
+}
+
+impl TriangleSettings {
+    /// Settings of each segment, in order.
+    pub fn segments(&self) -> &Vec<RampSettings> {
+        &self.segments
+    }
+
+    /// Pair consecutive segments sweeping the same voltage range in opposite directions.
+    /// Each segment is part of at most one pair, segments without a partner are skipped.
+    pub fn pairs(&self) -> Vec<RampPair> {
+        let mut pairs = Vec::new();
+        let mut idx = 0;
+        while idx + 1 < self.segments.len() {
+            let (first, second) = (&self.segments[idx], &self.segments[idx + 1]);
+            if !first.is_reverse_of(second) {
+                idx += 1;
+                continue;
+            }
+
+            let (first_idx, second_idx) = (idx as SegmentType, (idx + 1) as SegmentType);
+            let pair = match first.direction() {
+                Some(Direction::Forward) => RampPair::new(first_idx, second_idx),
+                Some(Direction::Reverse) => RampPair::new(second_idx, first_idx),
+                None => unreachable!("paired ramps have a direction"),
+            };
+            pairs.push(pair);
+            idx += 2;
+        }
+
+        pairs
+    }
+}
+
+/// Segments of a forward and its reverse ramp.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RampPair {
+    forward: SegmentType,
+    reverse: SegmentType,
+}
+
+impl RampPair {
+    pub fn new(forward: SegmentType, reverse: SegmentType) -> Self {
+        Self { forward, reverse }
+    }
+
+    pub fn forward(&self) -> SegmentType {
+        self.forward
+    }
+
+    pub fn reverse(&self) -> SegmentType {
+        self.reverse
+    }
+}
+
+/// Bias, in volts, and current, in amperes, of a segment.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Ramp {
+    bias: Vec<Value>,
+    current: Vec<Value>,
+}
+
+impl Ramp {
+    /// # Errors
+    /// + [`error::Sweep::InvalidDataLength`]: If `bias` and `current` have different lengths.
+    pub fn new(bias: Vec<Value>, current: Vec<Value>) -> Result<Self, error::Sweep> {
+        if bias.len() != current.len() {
+            return Err(error::Sweep::InvalidDataLength);
+        }
+        Ok(Self { bias, current })
+    }
+
+    /// Read the ramp of `segment`.
+    pub fn from_reader<R>(reader: &mut Reader<R>, segment: SegmentType) -> Result<Self, error::File>
+    where
+        R: io::Read + io::Seek,
+    {
+        let mut channel = |channel: &str, unit: &str| {
+            let data = reader
+                .channel_data(segment, channel, None)
+                .map_err(|error| error::File::ChannelData { segment, error })?;
+            if data.unit() != unit {
+                return Err(error::File::InvalidUnit {
+                    channel: channel.to_string(),
+                    unit: data.unit().clone(),
+                });
+            }
+            Ok(data.into_data())
+        };
+
+        let bias = channel(BIAS_CHANNEL, BIAS_UNIT)?;
+        let current = channel(CURRENT_CHANNEL, CURRENT_UNIT)?;
+        Self::new(bias, current).map_err(|_| error::File::InvalidDataLength(segment))
+    }
+}
+
+impl Ramp {
+    pub fn bias(&self) -> &Vec<Value> {
+        &self.bias
+    }
+
+    pub fn current(&self) -> &Vec<Value> {
+        &self.current
+    }
+
+    pub fn len(&self) -> usize {
+        self.bias.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bias.is_empty()
+    }
+
+    /// Differential conductance, dI/dV, in siemens.
+    /// Each point is the slope of a least squares line through the `window` points centered on it,
+    /// truncated at the ends of the ramp.
+    /// `NaN` where the bias is constant within the window.
+    pub fn conductance(&self, window: usize) -> Vec<Value> {
+        self.local_fits(window)
+            .into_iter()
+            .map(|(_, slope)| slope)
+            .collect()
+    }
+
+    /// Current smoothed by the same least squares lines as [`Self::conductance`].
+    pub fn smoothed_current(&self, window: usize) -> Vec<Value> {
+        self.local_fits(window)
+            .into_iter()
+            .map(|(current, _)| current)
+            .collect()
+    }
+
+    /// Least squares line through the window centered on each point.
+    ///
+    /// # Returns
+    /// Value and slope of the line at each point.
+    fn local_fits(&self, window: usize) -> Vec<(Value, Value)> {
+        let half_width = window / 2;
+        (0..self.len())
+            .map(|idx| {
+                let start = idx.saturating_sub(half_width);
+                let end = (idx + half_width + 1).min(self.len());
+                let bias = &self.bias[start..end];
+                let current = &self.current[start..end];
+
+                let n = bias.len() as Value;
+                let bias_mean = bias.iter().sum::<Value>() / n;
+                let current_mean = current.iter().sum::<Value>() / n;
+                let (covariance, variance) = std::iter::zip(bias, current).fold(
+                    (0.0, 0.0),
+                    |(covariance, variance), (bias, current)| {
+                        let db = bias - bias_mean;
+                        (
+                            covariance + db * (current - current_mean),
+                            variance + db * db,
+                        )
+                    },
+                );
+                if variance == 0.0 {
+                    return (current_mean, Value::NAN);
+                }
+
+                let slope = covariance / variance;
+                let value = current_mean + slope * (self.bias[idx] - bias_mean);
+                (value, slope)
+            })
+            .collect()
+    }
+
+    /// Range of the bias.
+    fn bias_range(&self) -> (Value, Value) {
+        self.bias.iter().fold(
+            (Value::INFINITY, Value::NEG_INFINITY),
+            |(min, max), bias| (min.min(*bias), max.max(*bias)),
+        )
+    }
+}
+
+/// Settings of the analysis.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Settings {
+    /// Number of points of the window used to smooth the current and its derivative.
+    pub window: usize,
+
+    /// Bias at which the rectification ratio is evaluated, in volts.
+    /// If `None` the largest bias reached in both polarities by both ramps is used,
+    /// which requires both ramps to sweep both polarities.
+    pub rectification_bias: Option<Value>,
+}
+
+impl Default for Settings {
+    /// Window of 51 points, and the rectification ratio at the largest common bias.
+    fn default() -> Self {
+        Self {
+            window: 51,
+            rectification_bias: None,
+        }
+    }
+}
+
+/// Forward and reverse ramp of a triangle voltage spectroscopy.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Sweep {
+    pair: RampPair,
+    forward: Ramp,
+    reverse: Ramp,
+}
+
+impl Sweep {
+    pub fn new(pair: RampPair, forward: Ramp, reverse: Ramp) -> Self {
+        Self {
+            pair,
+            forward,
+            reverse,
+        }
+    }
+
+    /// Read the sweeps of a file, paired by its [`TriangleSettings`].
+    pub fn from_reader<R>(reader: &mut Reader<R>) -> Result<Vec<Self>, error::File>
+    where
+        R: io::Read + io::Seek,
+    {
+        let settings = TriangleSettings::from_properties(
+            &reader.dataset_properties().subtree(TriangleSettings::KEY),
+        )?;
+        settings
+            .pairs()
+            .into_iter()
+            .map(|pair| {
+                let forward = Ramp::from_reader(reader, pair.forward())?;
+                let reverse = Ramp::from_reader(reader, pair.reverse())?;
+                Ok(Self::new(pair, forward, reverse))
+            })
+            .collect()
+    }
+}
+
+impl Sweep {
+    pub fn pair(&self) -> &RampPair {
+        &self.pair
+    }
+
+    pub fn forward(&self) -> &Ramp {
+        &self.forward
+    }
+
+    pub fn reverse(&self) -> &Ramp {
+        &self.reverse
+    }
+
+    /// # Errors
+    /// + [`error::Sweep::InsufficientData`]: If a ramp has less than two points.
+    /// + [`error::Sweep::BiasOutOfRange`]: If zero or the rectification bias is not swept by both ramps.
+    /// + [`error::Sweep::SinglePolarity`]: If no rectification bias is set
+    ///   and a ramp does not sweep both polarities.
+    pub fn analyze(&self, settings: &Settings) -> Result<Transport, error::Sweep> {
+        if self.forward.len() < 2 || self.reverse.len() < 2 {
+            return Err(error::Sweep::InsufficientData);
+        }
+
+        let ramps = [&self.forward, &self.reverse];
+        let at_bias = |bias: Value, values: &[Vec<Value>; 2]| {
+            let values = std::iter::zip(ramps, values)
+                .map(|(ramp, values)| interpolate(ramp.bias(), values, bias))
+                .collect::<Option<Vec<_>>>()
+                .ok_or(error::Sweep::BiasOutOfRange(bias))?;
+            Ok(values.iter().sum::<Value>() / values.len() as Value)
+        };
+
+        let conductance = ramps.map(|ramp| ramp.conductance(settings.window));
+        let zero_bias_conductance = at_bias(0.0, &conductance)?;
+
+        let rectification_bias = match settings.rectification_bias {
+            Some(bias) => bias,
+            None => {
+                let bias = ramps
+                    .iter()
+                    .map(|ramp| {
+                        let (min, max) = ramp.bias_range();
+                        (-min).min(max)
+                    })
+                    .fold(Value::INFINITY, Value::min);
+                if bias <= 0.0 {
+                    return Err(error::Sweep::SinglePolarity);
+                }
+                bias
+            }
+        };
+        let current = ramps.map(|ramp| ramp.smoothed_current(settings.window));
+        let positive = at_bias(rectification_bias.abs(), &current)?;
+        let negative = at_bias(-rectification_bias.abs(), &current)?;
+
+        // the reverse ramp integrates in decreasing bias, closing the loop
+        let hysteresis_area = trapezoid(self.forward.bias(), self.forward.current())
+            + trapezoid(self.reverse.bias(), self.reverse.current());
+
+        Ok(Transport {
+            zero_bias_conductance,
+            rectification_ratio: (positive / negative).abs(),
+            hysteresis_area,
+        })
+    }
+}
+
+/// Transport properties of a sweep.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Transport {
+    zero_bias_conductance: Value,
+    rectification_ratio: Value,
+    hysteresis_area: Value,
+}
+
+impl Transport {
+    /// Differential conductance at zero bias, averaged over both ramps, in siemens.
+    pub fn zero_bias_conductance(&self) -> Value {
+        self.zero_bias_conductance
+    }
+
+    /// Magnitude of the ratio of the smoothed current at positive and negative rectification bias,
+    /// averaged over both ramps.
+    pub fn rectification_ratio(&self) -> Value {
+        self.rectification_ratio
+    }
+
+    /// Area between the forward and reverse current, in watts.
+    /// Positive if the forward current exceeds the reverse current.
+    pub fn hysteresis_area(&self) -> Value {
+        self.hysteresis_area
+    }
+}
+
+/// Analyze the sweeps of every file of a collection in parallel.
+///
+/// # Returns
+/// Data frame with `path`, `x` and `y` columns of each file,
+/// `forward_segment` and `reverse_segment` columns of each sweep,
+/// `zero_bias_conductance`, `rectification_ratio` and `hysteresis_area` columns,
+/// and an `error` column, ordered by path and sweep.
+/// Sweeps that could not be analyzed record their error, and their properties are null.
+/// Files that could not be read record their error in a single row,
+/// with null position, segment and transport columns.
+///
+/// # Errors
+/// + If the directory could not be read.
+/// + If the data frame could not be built.
+pub fn analyze_dir(
+    dir: &DirReader,
+    settings: &Settings,
+) -> Result<pl::DataFrame, error::Collection> {
+    let mut files = dir.files()?;
+    files.sort();
+    let files = files
+        .into_par_iter()
+        .map(|path| {
+            let sweeps = analyze_file(&path, settings);
+            (path, sweeps)
+        })
+        .collect::<Vec<_>>();
+
+    let rows = files
+        .iter()
+        .flat_map(|(path, sweeps)| -> Vec<Row<'_>> {
+            match sweeps {
+                Ok((xy, sweeps)) => sweeps
+                    .iter()
+                    .map(|(pair, transport)| {
+                        let transport = transport
+                            .as_ref()
+                            .copied()
+                            .map_err(|err| error_message(err));
+                        (path.as_path(), Some(*xy), Some(*pair), transport)
+                    })
+                    .collect(),
+                Err(err) => vec![(path.as_path(), None, None, Err(error_message(err)))],
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let column = |name: &str, value: fn(&Transport) -> Value| {
+        let values = rows
+            .iter()
+            .map(|(_, _, _, transport)| transport.as_ref().ok().map(value))
+            .collect::<Vec<_>>();
+        pl::Column::new(name.into(), values)
+    };
+    let errors = rows
+        .iter()
+        .map(|(_, _, _, transport)| transport.as_ref().err().map(String::as_str))
+        .collect::<Vec<_>>();
+    let df = pl::DataFrame::new(vec![
+        pl::Column::new(
+            PATH_COLUMN.into(),
+            rows.iter()
+                .map(|(path, ..)| path.to_string_lossy().to_string())
+                .collect::<Vec<_>>(),
+        ),
+        pl::Column::new(
+            X_COLUMN.into(),
+            rows.iter()
+                .map(|(_, xy, ..)| xy.map(|xy| xy.0))
+                .collect::<Vec<_>>(),
+        ),
+        pl::Column::new(
+            Y_COLUMN.into(),
+            rows.iter()
+                .map(|(_, xy, ..)| xy.map(|xy| xy.1))
+                .collect::<Vec<_>>(),
+        ),
+        pl::Column::new(
+            FORWARD_SEGMENT_COLUMN.into(),
+            rows.iter()
+                .map(|(_, _, pair, _)| pair.map(|pair| pair.forward()))
+                .collect::<Vec<_>>(),
+        ),
+        pl::Column::new(
+            REVERSE_SEGMENT_COLUMN.into(),
+            rows.iter()
+                .map(|(_, _, pair, _)| pair.map(|pair| pair.reverse()))
+                .collect::<Vec<_>>(),
+        ),
+        column(
+            ZERO_BIAS_CONDUCTANCE_COLUMN,
+            Transport::zero_bias_conductance,
+        ),
+        column(RECTIFICATION_RATIO_COLUMN, Transport::rectification_ratio),
+        column(HYSTERESIS_AREA_COLUMN, Transport::hysteresis_area),
+        pl::Column::new(ERROR_COLUMN.into(), errors),
+    ])?;
+    Ok(df)
+}
+
+/// Row of [`analyze_dir`]: path, position, sweep, and transport or error message.
+type Row<'a> = (
+    &'a Path,
+    Option<(f64, f64)>,
+    Option<RampPair>,
+    Result<Transport, String>,
+);
+
+/// Position and analyzed sweeps of a file.
+type FileSweeps = ((f64, f64), Vec<(RampPair, Result<Transport, error::Sweep>)>);
+
+/// Position and analyzed sweeps of the file at `path`.
+fn analyze_file(path: &Path, settings: &Settings) -> Result<FileSweeps, error::Collection> {
+    let mut reader =
+        FileReader::new(path.to_path_buf()).map_err(|error| error::Collection::Dataset {
+            path: path.to_path_buf(),
+            error,
+        })?;
+    let xy = reader.position().map_err(|error| error::Collection::File {
+        path: path.to_path_buf(),
+        error: error.into(),
+    })?;
+    let sweeps =
+        Sweep::from_reader::<fs::File>(&mut reader).map_err(|error| error::Collection::File {
+            path: path.to_path_buf(),
+            error,
+        })?;
+    let sweeps = sweeps
+        .iter()
+        .map(|sweep| (*sweep.pair(), sweep.analyze(settings)))
+        .collect();
+    Ok((xy, sweeps))
+}
+
+/// Linearly interpolate `values` at the first crossing of `x` by `positions`.
+///
+/// # Returns
+/// `None` if `positions` does not reach `x`.
+fn interpolate(positions: &[Value], values: &[Value], x: Value) -> Option<Value> {
+    std::iter::zip(positions.windows(2), values.windows(2)).find_map(|(position, value)| {
+        let (p0, p1) = (position[0], position[1]);
+        if p0 == x {
+            return Some(value[0]);
+        }
+        if p1 == x {
+            return Some(value[1]);
+        }
+        if (p0 - x) * (p1 - x) < 0.0 {
+            let t = (x - p0) / (p1 - p0);
+            return Some(value[0] + t * (value[1] - value[0]));
+        }
+        None
+    })
+}
+
+pub mod error {
+    use crate::dataset::{self, properties::error::Property, v2_0::SegmentType};
+    use std::{fmt, io, path::PathBuf};
+
+    #[derive(Debug)]
+    pub enum Sweep {
+        /// The bias and current of a ramp have different lengths.
+        InvalidDataLength,
+
+        /// A ramp does not contain enough points.
+        InsufficientData,
+
+        /// The bias is not swept by both ramps.
+        BiasOutOfRange(f64),
+
+        /// A ramp does not sweep both polarities, so no rectification bias is common to both.
+        SinglePolarity,
+    }
+
+    impl fmt::Display for Sweep {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::InvalidDataLength => {
+                    write!(f, "bias and current have different lengths")
+                }
+                Self::InsufficientData => write!(f, "not enough points in the ramps"),
+                Self::BiasOutOfRange(bias) => {
+                    write!(f, "bias {bias} V is not swept by both ramps")
+                }
+                Self::SinglePolarity => {
+                    write!(f, "ramps do not sweep both polarities")
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for Sweep {}
+
+    #[derive(derive_more::From, Debug)]
+    pub enum File {
+        /// The voltage spectroscopy settings could not be read.
+        Property(Property),
+
+        #[from(ignore)]
+        ChannelData {
+            segment: SegmentType,
+            error: dataset::v2_0::error::ChannelData,
+        },
+
+        /// The channel is not in the expected unit.
+        #[from(ignore)]
+        InvalidUnit { channel: String, unit: String },
+
+        /// The bias and current of the segment have different lengths.
+        #[from(ignore)]
+        InvalidDataLength(SegmentType),
+    }
+
+    impl fmt::Display for File {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Property(_) => write!(f, "could not read voltage spectroscopy settings"),
+                Self::ChannelData { segment, .. } => {
+                    write!(f, "could not read channel data of segment {segment}")
+                }
+                Self::InvalidUnit { channel, unit } => {
+                    write!(f, "channel `{channel}` has unexpected unit `{unit}`")
+                }
+                Self::InvalidDataLength(segment) => write!(
+                    f,
+                    "bias and current of segment {segment} have different lengths"
+                ),
+            }
+        }
+    }
+
+    impl std::error::Error for File {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::Property(err) => Some(err),
+                Self::ChannelData { error, .. } => Some(error),
+                Self::InvalidUnit { .. } | Self::InvalidDataLength(_) => None,
+            }
+        }
+    }
+
+    #[derive(derive_more::From, Debug)]
+    pub enum Collection {
+        /// The directory could not be read.
+        Io(io::Error),
+
+        Dataset {
+            path: PathBuf,
+            error: dataset::DatasetError,
+        },
+
+        File {
+            path: PathBuf,
+            error: File,
+        },
+
+        Polars(polars::error::PolarsError),
+    }
+
+    impl fmt::Display for Collection {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Io(_) => write!(f, "could not read directory"),
+                Self::Dataset { path, .. } => {
+                    write!(f, "could not read dataset `{}`", path.display())
+                }
+                Self::File { path, .. } => write!(f, "could not analyze `{}`", path.display()),
+                Self::Polars(_) => write!(f, "could not build data frame"),
+            }
+        }
+    }
+
+    impl std::error::Error for Collection {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::Io(err) => Some(err),
+                Self::Dataset { error, .. } => Some(error),
+                Self::File { error, .. } => Some(error),
+                Self::Polars(err) => Some(err),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Ramp of `num_points` from `start` to `end` volts through `current`.
+    fn ramp(start: Value, end: Value, num_points: usize, current: impl Fn(Value) -> Value) -> Ramp {
+        let bias = (0..num_points)
+            .map(|idx| start + (end - start) * idx as Value / (num_points - 1) as Value)
+            .collect::<Vec<_>>();
+        let current = bias.iter().copied().map(current).collect();
+        Ramp::new(bias, current).unwrap()
+    }
+
+    #[test]
+    fn triangle_settings_pairs() {
+        let settings = TriangleSettings::new(vec![
+            RampSettings::new(0.02, -0.02),
+            RampSettings::new(-0.02, 0.02),
+            RampSettings::new(0.0, 0.0),
+            RampSettings::new(-0.1, 0.1),
+            RampSettings::new(0.1, -0.1),
+            RampSettings::new(0.1, -0.2),
+        ]);
+        assert_eq!(
+            settings.pairs(),
+            vec![RampPair::new(1, 0), RampPair::new(3, 4)]
+        );
+    }
+
+    #[test]
+    fn ramp_conductance() {
+        // quadratic current, dI/dV = 2 V + 1
+        let ramp = ramp(-1.0, 1.0, 101, |bias| bias * bias + bias);
+        let conductance = ramp.conductance(5);
+        // truncated windows at the ends are not centered on their point
+        for (bias, conductance) in std::iter::zip(ramp.bias(), &conductance).skip(2).take(97) {
+            assert!((conductance - (2.0 * bias + 1.0)).abs() < 1e-9);
+        }
+
+        let smoothed = ramp.smoothed_current(1);
+        assert_eq!(&smoothed, ramp.current());
+        assert!(ramp.conductance(1).iter().all(|slope| slope.is_nan()));
+    }
+
+    #[test]
+    fn sweep_analyze() {
+        const CONDUCTANCE: Value = 2e-9;
+        const OFFSET: Value = 1e-11;
+
+        // rectifying forward ramp, reverse ramp shifted down
+        let current = |bias: Value| CONDUCTANCE * bias + if bias > 0.0 { bias * bias } else { 0.0 };
+        let forward = ramp(-1.0, 1.0, 201, |bias| current(bias) + OFFSET);
+        let reverse = ramp(1.0, -1.0, 201, |bias| current(bias) - OFFSET);
+        let sweep = Sweep::new(RampPair::new(0, 1), forward, reverse);
+
+        let settings = Settings {
+            window: 3,
+            rectification_bias: None,
+        };
+        let transport = sweep.analyze(&settings).unwrap();
+        // the window is symmetric about zero, so the quadratic term is halved
+        let zero_bias = CONDUCTANCE + 0.01 / 2.0;
+        assert!((transport.zero_bias_conductance() - zero_bias).abs() < 1e-12);
+        let ratio = (CONDUCTANCE + 1.0) / CONDUCTANCE;
+        assert!((transport.rectification_ratio() - ratio).abs() / ratio < 1e-3);
+        assert!((transport.hysteresis_area() - 2.0 * 2.0 * OFFSET).abs() < 1e-15);
+
+        let settings = Settings {
+            window: 3,
+            rectification_bias: Some(2.0),
+        };
+        assert!(matches!(
+            sweep.analyze(&settings),
+            Err(error::Sweep::BiasOutOfRange(_))
+        ));
+
+        // zero is reached, but only positive bias is swept
+        let forward = ramp(0.0, 1.0, 101, current);
+        let reverse = ramp(1.0, 0.0, 101, current);
+        let sweep = Sweep::new(RampPair::new(0, 1), forward, reverse);
+        let settings = Settings {
+            window: 3,
+            rectification_bias: None,
+        };
+        assert!(matches!(
+            sweep.analyze(&settings),
+            Err(error::Sweep::SinglePolarity)
+        ));
+    }
+
+    #[test]
+    fn interpolate_crossing() {
+        let positions = [1.0, 0.5, -0.5];
+        let values = [3.0, 2.0, 0.0];
+        assert_eq!(interpolate(&positions, &values, 0.0), Some(1.0));
+        assert_eq!(interpolate(&positions, &values, 1.0), Some(3.0));
+        assert_eq!(interpolate(&positions, &values, -0.5), Some(0.0));
+        assert_eq!(interpolate(&positions, &values, 2.0), None);
+    }
+
+    #[test]
+    fn file_error_source() {
+        let err = error::File::from(PropertyError::NotFound("voltage-spectroscopy".to_string()));
+        assert_eq!(
+            err.to_string(),
+            "could not read voltage spectroscopy settings"
+        );
+        let source = std::error::Error::source(&err).unwrap();
+        assert_eq!(
+            source.to_string(),
+            "property `voltage-spectroscopy` not found"
+        );
+    }
+}
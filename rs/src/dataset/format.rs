@@ -0,0 +1,313 @@
+//! File format version and features of dataset archives.
+use super::properties::{PropertyTree, error::Property as PropertyError};
+use std::fmt;
+
+/// JPK file format version of a dataset archive.
+/// Version 1.x archives use a different layout and are not supported.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FormatVersion {
+    // 2.0
+    V2_0,
+}
+
+impl FormatVersion {
+    pub fn from_str(v: impl AsRef<str>) -> Option<FormatVersion> {
+        match v.as_ref() {
+            "2.0" => Some(Self::V2_0),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::V2_0 => "2.0",
+        }
+    }
+
+    /// Features of the files the reader for the version is validated against.
+    /// Feature bits are not documented, so other features are not rejected,
+    /// see [`FileFormat::unknown_features`].
+    pub fn known_features(&self) -> Features {
+        match self {
+            Self::V2_0 => Features::from_hex("1c2010401801524043c3b").expect("valid features"),
+        }
+    }
+
+    /// Features known to change the layout of the version,
+    /// so files using them can not be read by the reader for the version.
+    /// None are known for 2.0.
+    pub fn unsupported_features(&self) -> Features {
+        match self {
+            Self::V2_0 => Features::default(),
+        }
+    }
+}
+
+/// Set of file format features, from the `file-format-features` bitmask.
+/// Each feature is identified by its bit index.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct Features {
+    /// Little endian words of the bitmask.
+    words: Vec<u64>,
+}
+
+impl Features {
+    /// Bits per word.
+    const WORD_BITS: u32 = u64::BITS;
+    /// Hex digits per word.
+    const WORD_DIGITS: usize = 16;
+
+    /// Parse a hex encoded bitmask.
+    pub fn from_hex(input: impl AsRef<str>) -> Option<Self> {
+        let input = input.as_ref().trim();
+        if input.is_empty() || !input.is_ascii() {
+            return None;
+        }
+
+        let mut words = Vec::with_capacity(input.len().div_ceil(Self::WORD_DIGITS));
+        let mut end = input.len();
+        while end > 0 {
+            let start = end.saturating_sub(Self::WORD_DIGITS);
+            words.push(u64::from_str_radix(&input[start..end], 16).ok()?);
+            end = start;
+        }
+
+        let mut features = Self { words };
+        features.trim();
+        Some(features)
+    }
+
+    pub fn contains(&self, feature: u32) -> bool {
+        let word = (feature / Self::WORD_BITS) as usize;
+        self.words
+            .get(word)
+            .is_some_and(|word| word & (1 << (feature % Self::WORD_BITS)) != 0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Bit indices of the features, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.words.iter().enumerate().flat_map(|(idx, word)| {
+            (0..Self::WORD_BITS)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| idx as u32 * Self::WORD_BITS + bit)
+        })
+    }
+
+    /// Features in `self` that are not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let words = self
+            .words
+            .iter()
+            .enumerate()
+            .map(|(idx, word)| word & !other.words.get(idx).copied().unwrap_or(0))
+            .collect();
+        let mut features = Self { words };
+        features.trim();
+        features
+    }
+
+    /// Remove leading zero words.
+    fn trim(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+}
+
+/// File format of a dataset archive.
+#[derive(Clone, Debug)]
+pub struct FileFormat {
+    version: String,
+    features: Features,
+}
+
+impl FileFormat {
+    /// `file-format-version`
+    pub const VERSION_KEY: &str = "file-format-version";
+    /// `file-format-features`
+    pub const FEATURES_KEY: &str = "file-format-features";
+}
+
+impl FileFormat {
+    /// # Arguments
+    /// + `properties`: Dataset properties.
+    ///   Missing features are treated as no features.
+    pub fn from_properties(properties: &PropertyTree) -> Result<Self, PropertyError> {
+        let version = properties.get_str(Self::VERSION_KEY)?;
+        let features = match properties.get_str(Self::FEATURES_KEY) {
+            Ok(features) => Features::from_hex(features)
+                .ok_or_else(|| PropertyError::InvalidValue(properties.key(Self::FEATURES_KEY)))?,
+            Err(PropertyError::NotFound(_)) => Features::default(),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Self {
+            version: version.to_string(),
+            features,
+        })
+    }
+}
+
+impl FileFormat {
+    /// Value of `file-format-version`.
+    pub fn version(&self) -> &String {
+        &self.version
+    }
+
+    pub fn features(&self) -> &Features {
+        &self.features
+    }
+
+    /// Get the version to dispatch the reader on.
+    /// Readers are only available for version 2.0,
+    /// older 1.x layouts are rejected.
+    ///
+    /// # Errors
+    /// + If no reader is available for the version.
+    /// + If the file uses features known to change the layout of the version.
+    pub fn supported_version(&self) -> Result<FormatVersion, UnsupportedFormat> {
+        let version = match FormatVersion::from_str(&self.version) {
+            Some(version @ FormatVersion::V2_0) => version,
+            None => return Err(UnsupportedFormat::Version(self.version.clone())),
+        };
+
+        let unsupported = version.unsupported_features();
+        let features = self
+            .features
+            .iter()
+            .filter(|feature| unsupported.contains(*feature))
+            .collect::<Vec<_>>();
+        if !features.is_empty() {
+            return Err(UnsupportedFormat::Features { version, features });
+        }
+
+        Ok(version)
+    }
+
+    /// Features of the file the reader for its version is not validated against.
+    /// These do not reject the file, but data may be misread if they change the layout.
+    ///
+    /// # Returns
+    /// `None` if no reader is available for the version.
+    pub fn unknown_features(&self) -> Option<Features> {
+        let version = FormatVersion::from_str(&self.version)?;
+        Some(self.features.difference(&version.known_features()))
+    }
+}
+
+/// The file format of a dataset can not be read.
+#[derive(Clone, Debug)]
+pub enum UnsupportedFormat {
+    /// No reader is available for the version.
+    Version(String),
+
+    /// The file uses features known to change the layout of the version.
+    Features {
+        version: FormatVersion,
+        features: Vec<u32>,
+    },
+}
+
+impl fmt::Display for UnsupportedFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Version(version) => {
+                write!(f, "file format version `{version}` is not supported")
+            }
+            Self::Features { version, features } => {
+                let features = features
+                    .iter()
+                    .map(|feature| feature.to_string())
+                    .collect::<Vec<_>>();
+                let plural = if features.len() == 1 { "" } else { "s" };
+                write!(
+                    f,
+                    "unsupported feature{plural} {} of file format version `{}`",
+                    features.join(", "),
+                    version.as_str()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for UnsupportedFormat {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn features_from_hex() {
+        let features = Features::from_hex("100000400000104002013").unwrap();
+        assert_eq!(
+            features.iter().collect::<Vec<_>>(),
+            vec![0, 1, 4, 13, 26, 32, 58, 80]
+        );
+        assert!(features.contains(80));
+        assert!(!features.contains(81));
+        assert!(!features.contains(1000));
+
+        assert_eq!(Features::from_hex("0").unwrap(), Features::default());
+        assert!(Features::from_hex("").is_none());
+        assert!(Features::from_hex("xyz").is_none());
+    }
+
+    #[test]
+    fn features_difference() {
+        let a = Features::from_hex("1000000000000000000f").unwrap();
+        let b = Features::from_hex("3").unwrap();
+        assert_eq!(a.difference(&b).iter().collect::<Vec<_>>(), vec![2, 3, 76]);
+        assert!(b.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn file_format_supported_version() {
+        let format = FileFormat {
+            version: "2.0".to_string(),
+            features: Features::from_hex("1c2010401801424041c3b").unwrap(),
+        };
+        assert_eq!(format.supported_version().unwrap(), FormatVersion::V2_0);
+        assert!(format.unknown_features().unwrap().is_empty());
+
+        let format = FileFormat {
+            version: "2.0".to_string(),
+            features: Features::from_hex("1100000400000104002013").unwrap(),
+        };
+        assert_eq!(format.supported_version().unwrap(), FormatVersion::V2_0);
+        assert_eq!(
+            format
+                .unknown_features()
+                .unwrap()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![84]
+        );
+
+        let format = FileFormat {
+            version: "1.0".to_string(),
+            features: Features::default(),
+        };
+        assert!(matches!(
+            format.supported_version(),
+            Err(UnsupportedFormat::Version(_))
+        ));
+        assert!(format.unknown_features().is_none());
+    }
+
+    #[test]
+    fn unsupported_format_features_display() {
+        let err = UnsupportedFormat::Features {
+            version: FormatVersion::V2_0,
+            features: vec![2],
+        };
+        assert_eq!(
+            err.to_string(),
+            "unsupported feature 2 of file format version `2.0`"
+        );
+    }
+}
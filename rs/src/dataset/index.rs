@@ -0,0 +1,102 @@
+//! Index and pixel queries of map datasets.
+use super::v2_0::IndexType;
+
+pub enum IndexQuery {
+    All,
+    Index(IndexType),
+    PixelRect(PixelRect),
+    Pixel(Pixel),
+}
+
+pub struct PixelRect {
+    start: Pixel,
+    end: Pixel,
+}
+
+impl PixelRect {
+    pub fn new(start: Pixel, end: Pixel) -> Self {
+        let Pixel { i: xa, j: ya } = start;
+        let Pixel { i: xb, j: yb } = end;
+        let (x0, x1) = if xa < xb { (xa, xb) } else { (xb, xa) };
+        let (y0, y1) = if ya < yb { (ya, yb) } else { (yb, ya) };
+
+        let start = Pixel { i: x0, j: y0 };
+        let end = Pixel { i: x1, j: y1 };
+        Self { start, end }
+    }
+
+    pub fn rows(&self) -> IndexType {
+        self.end.j - self.start.j + 1
+    }
+
+    pub fn cols(&self) -> IndexType {
+        self.end.i - self.start.i + 1
+    }
+
+    pub fn iter(&self) -> PixelRectIter<'_> {
+        PixelRectIter::new(self)
+    }
+}
+
+pub struct PixelRectIter<'a> {
+    inner: &'a PixelRect,
+    i: IndexType,
+    j: IndexType,
+}
+
+impl<'a> PixelRectIter<'a> {
+    pub fn new(inner: &'a PixelRect) -> Self {
+        Self {
+            inner,
+            i: inner.start.i,
+            j: inner.start.j,
+        }
+    }
+}
+
+impl<'a> std::iter::Iterator for PixelRectIter<'a> {
+    type Item = Pixel;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.j > self.inner.end.j {
+            return None;
+        }
+
+        let pixel = Pixel {
+            i: self.i,
+            j: self.j,
+        };
+
+        self.i += 1;
+        if self.i > self.inner.end.i {
+            self.i = self.inner.start.i;
+            self.j += 1;
+        }
+
+        Some(pixel)
+    }
+}
+
+/// Ordered by `(i, j)`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pixel {
+    i: IndexType,
+    j: IndexType,
+}
+
+impl Pixel {
+    pub fn new(i: IndexType, j: IndexType) -> Self {
+        Self { i, j }
+    }
+
+    pub fn i(&self) -> IndexType {
+        self.i
+    }
+
+    pub fn j(&self) -> IndexType {
+        self.j
+    }
+
+    pub fn to_index(&self, cols: IndexType) -> IndexType {
+        self.j * cols + self.i
+    }
+}
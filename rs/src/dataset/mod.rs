@@ -1,27 +1,100 @@
 //! Reader for generic JPK datasets.
-use std::{fmt, path::PathBuf};
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+pub mod format;
+pub mod index;
+pub mod preview;
 pub mod properties;
 pub mod v2_0;
 
+pub use format::{FileFormat, FormatVersion};
+
 const DATASET_PROPERTIES_FILE_PATH: &str = "header.properties";
 
 #[derive(derive_more::From, Debug)]
 pub enum DatasetError {
+    /// The file at the given path could not be opened as an archive.
+    OpenFile {
+        path: PathBuf,
+        error: zip::result::ZipError,
+    },
+
     #[from]
     OpenArchive(zip::result::ZipError),
+
+    /// The archive entry at the given path could not be read.
     Zip {
         path: PathBuf,
         error: zip::result::ZipError,
     },
-    InvalidFormat {
+
+    /// The archive entry at the given path had an invalid format.
+    InvalidFormat { path: PathBuf, cause: String },
+
+    /// A property of the archive entry at the given path was missing or invalid.
+    Property {
         path: PathBuf,
-        cause: String,
+        error: properties::error::Property,
     },
+
+    /// The file format version or features of the dataset can not be read.
+    #[from]
+    FileFormatNotSupported(format::UnsupportedFormat),
+}
+
+impl DatasetError {
+    /// Error reading the properties file at `path`.
+    pub(crate) fn properties(path: impl Into<PathBuf>, error: properties::error::Parse) -> Self {
+        match error {
+            properties::error::Parse::Io(error) => Self::Zip {
+                path: path.into(),
+                error: zip::result::ZipError::Io(error),
+            },
+            error @ properties::error::Parse::InvalidUnicodeEscape { .. } => Self::InvalidFormat {
+                path: path.into(),
+                cause: error.to_string(),
+            },
+        }
+    }
 }
 
 impl fmt::Display for DatasetError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // TODO: Clean up error messages.
-        write!(f, "{self:?}")
+        match self {
+            Self::OpenFile { path, .. } => write!(f, "could not open `{}`", path.display()),
+            Self::OpenArchive(_) => write!(f, "could not open archive"),
+            Self::Zip { path, .. } => write!(f, "could not read `{}`", path.display()),
+            Self::InvalidFormat { path, cause } => {
+                write!(f, "invalid format of `{}`: {cause}", path.display())
+            }
+            Self::Property { path, .. } => write!(f, "invalid property in `{}`", path.display()),
+            Self::FileFormatNotSupported(error) => write!(f, "{error}"),
+        }
     }
 }
+
+impl std::error::Error for DatasetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::OpenFile { error, .. } | Self::OpenArchive(error) | Self::Zip { error, .. } => {
+                Some(error)
+            }
+            Self::InvalidFormat { .. } => None,
+            Self::Property { error, .. } => Some(error),
+            Self::FileFormatNotSupported(error) => error.source(),
+        }
+    }
+}
+
+/// Open the file at `path` as a zip archive.
+pub(crate) fn open_archive(
+    path: impl AsRef<Path>,
+) -> Result<zip::ZipArchive<fs::File>, zip::result::ZipError> {
+    let file = fs::File::open(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => zip::result::ZipError::FileNotFound,
+        _ => zip::result::ZipError::Io(err),
+    })?;
+    zip::ZipArchive::new(file)
+}
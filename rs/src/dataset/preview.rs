@@ -0,0 +1,333 @@
+//! Preview images stored in dataset archives.
+use super::DatasetError;
+use std::{
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// File stem of the archive thumbnail.
+const THUMBNAIL_FILE_STEM: &str = "thumbnail";
+
+/// Extension of JPK images in archives, whose first IFD holds a thumbnail.
+const JPK_IMAGE_EXTENSION: &str = "jpk-qi-image";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Bmp,
+}
+
+impl ImageFormat {
+    pub fn from_extension(ext: impl AsRef<str>) -> Option<Self> {
+        match ext.as_ref().to_ascii_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "gif" => Some(Self::Gif),
+            "bmp" => Some(Self::Bmp),
+            _ => None,
+        }
+    }
+
+    /// Identify the format from the magic bytes of an image.
+    pub fn from_signature(data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if data.starts_with(&[0xff, 0xd8]) {
+            Some(Self::Jpeg)
+        } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+            Some(Self::Gif)
+        } else if data.starts_with(b"BM") {
+            Some(Self::Bmp)
+        } else {
+            None
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Gif => "image/gif",
+            Self::Bmp => "image/bmp",
+        }
+    }
+}
+
+/// Encoded preview image.
+/// Thumbnails of JPK images are re-encoded as bitmaps.
+#[derive(Clone, Debug)]
+pub struct Preview {
+    path: PathBuf,
+    format: ImageFormat,
+    dimensions: Option<(u32, u32)>,
+    data: Vec<u8>,
+}
+
+impl Preview {
+    /// # Arguments
+    /// + `path`: Path of the image in the archive.
+    /// + `data`: Encoded image.
+    ///
+    /// # Returns
+    /// `None` if `data` is not a supported image format.
+    pub fn new(path: impl Into<PathBuf>, data: Vec<u8>) -> Option<Self> {
+        let format = ImageFormat::from_signature(&data)?;
+        let dimensions = dimensions(format, &data);
+        Some(Self {
+            path: path.into(),
+            format,
+            dimensions,
+            data,
+        })
+    }
+
+    /// Path of the image in the archive.
+    /// For the thumbnail of a JPK image, the path of the image file.
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn format(&self) -> ImageFormat {
+        self.format
+    }
+
+    /// `(width, height)` in pixels, decoded from the image header.
+    /// `None` if the header could not be decoded.
+    pub fn dimensions(&self) -> Option<(u32, u32)> {
+        self.dimensions
+    }
+
+    /// Encoded image.
+    pub fn data(&self) -> &Vec<u8> {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// Paths of preview images in an archive.
+/// Ordered by depth, then by name.
+pub(crate) fn preview_paths<R>(archive: &zip::ZipArchive<R>) -> Vec<PathBuf>
+where
+    R: io::Read + io::Seek,
+{
+    let mut paths = archive
+        .file_names()
+        .map(PathBuf::from)
+        .filter(|path| {
+            let ext = path.extension().and_then(|ext| ext.to_str());
+            ext.and_then(ImageFormat::from_extension).is_some() || is_jpk_image(path)
+        })
+        .collect::<Vec<_>>();
+    paths.sort_by(|a, b| {
+        let depth = |path: &PathBuf| path.components().count();
+        depth(a).cmp(&depth(b)).then_with(|| a.cmp(b))
+    });
+    paths
+}
+
+/// Read all preview images in an archive.
+/// Files that are not a supported image are skipped.
+pub(crate) fn read_previews<R>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<Vec<Preview>, DatasetError>
+where
+    R: io::Read + io::Seek,
+{
+    let mut previews = Vec::new();
+    for path in preview_paths(archive) {
+        if let Some(preview) = read_preview(archive, path)? {
+            previews.push(preview);
+        }
+    }
+    Ok(previews)
+}
+
+/// Read the thumbnail of an archive.
+/// A file named `thumbnail` is preferred,
+/// falling back to the first preview image.
+pub(crate) fn read_thumbnail<R>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<Option<Preview>, DatasetError>
+where
+    R: io::Read + io::Seek,
+{
+    let mut paths = preview_paths(archive);
+    if let Some(idx) = paths.iter().position(|path| {
+        path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem.eq_ignore_ascii_case(THUMBNAIL_FILE_STEM))
+    }) {
+        let path = paths.remove(idx);
+        paths.insert(0, path);
+    }
+
+    for path in paths {
+        if let Some(preview) = read_preview(archive, path)? {
+            return Ok(Some(preview));
+        }
+    }
+    Ok(None)
+}
+
+fn read_preview<R>(
+    archive: &mut zip::ZipArchive<R>,
+    path: PathBuf,
+) -> Result<Option<Preview>, DatasetError>
+where
+    R: io::Read + io::Seek,
+{
+    let zip_error = |path: &PathBuf, error| DatasetError::Zip {
+        path: path.clone(),
+        error,
+    };
+
+    let mut file = archive
+        .by_path(&path)
+        .map_err(|error| zip_error(&path, error))?;
+    // the size in the archive is not trusted to allocate the buffer
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)
+        .map_err(|error| zip_error(&path, zip::result::ZipError::Io(error)))?;
+
+    #[cfg(feature = "jpk_image")]
+    if is_jpk_image(&path) {
+        return Ok(crate::jpk_image::read_thumbnail(&data, path));
+    }
+    Ok(Preview::new(path, data))
+}
+
+/// # Returns
+/// If `path` is a JPK image whose thumbnail can be read.
+fn is_jpk_image(path: &Path) -> bool {
+    cfg!(feature = "jpk_image")
+        && path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(JPK_IMAGE_EXTENSION))
+}
+
+/// Encode 8 bit RGB pixels, in rows from the top, as a 24 bit bitmap.
+#[cfg_attr(not(feature = "jpk_image"), allow(dead_code))]
+pub(crate) fn encode_bmp(width: u32, height: u32, rgb: &[[u8; 3]]) -> Vec<u8> {
+    const HEADER_SIZE: u32 = 14 + 40;
+
+    // rows are padded to a multiple of 4 bytes
+    let row_size = (width * 3).div_ceil(4) * 4;
+    let image_size = row_size * height;
+    let mut data = Vec::with_capacity((HEADER_SIZE + image_size) as usize);
+    data.extend_from_slice(b"BM");
+    data.extend_from_slice(&(HEADER_SIZE + image_size).to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&HEADER_SIZE.to_le_bytes());
+    data.extend_from_slice(&40_u32.to_le_bytes());
+    data.extend_from_slice(&(width as i32).to_le_bytes());
+    // negative height for rows from the top
+    data.extend_from_slice(&(-(height as i32)).to_le_bytes());
+    data.extend_from_slice(&1_u16.to_le_bytes());
+    data.extend_from_slice(&24_u16.to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&image_size.to_le_bytes());
+    data.extend_from_slice(&[0; 16]);
+    for row in rgb.chunks_exact(width as usize) {
+        for [red, green, blue] in row {
+            data.extend_from_slice(&[*blue, *green, *red]);
+        }
+        data.resize(data.len() + (row_size - width * 3) as usize, 0);
+    }
+    data
+}
+
+/// Decode the `(width, height)` of an image from its header.
+fn dimensions(format: ImageFormat, data: &[u8]) -> Option<(u32, u32)> {
+    let be_u16 = |offset: usize| {
+        data.get(offset..offset + 2)
+            .map(|bytes| u16::from_be_bytes(bytes.try_into().expect("slice has correct length")))
+    };
+    let le_u16 = |offset: usize| {
+        data.get(offset..offset + 2)
+            .map(|bytes| u16::from_le_bytes(bytes.try_into().expect("slice has correct length")))
+    };
+    let be_u32 = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|bytes| u32::from_be_bytes(bytes.try_into().expect("slice has correct length")))
+    };
+    let le_i32 = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|bytes| i32::from_le_bytes(bytes.try_into().expect("slice has correct length")))
+    };
+
+    match format {
+        // IHDR is the first chunk.
+        ImageFormat::Png => Some((be_u32(16)?, be_u32(20)?)),
+        ImageFormat::Gif => Some((le_u16(6)? as u32, le_u16(8)? as u32)),
+        ImageFormat::Bmp => {
+            // Height is negative for top-down bitmaps.
+            let width = le_i32(18)?;
+            let height = le_i32(22)?;
+            Some((width.unsigned_abs(), height.unsigned_abs()))
+        }
+        ImageFormat::Jpeg => {
+            let mut offset = 2;
+            loop {
+                if *data.get(offset)? != 0xff {
+                    return None;
+                }
+                let marker = *data.get(offset + 1)?;
+                match marker {
+                    // Padding.
+                    0xff => offset += 1,
+                    // Markers without a payload.
+                    0x01 | 0xd0..=0xd7 => offset += 2,
+                    // Start of frame, excluding DHT, JPG and DAC.
+                    0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+                        let height = be_u16(offset + 5)?;
+                        let width = be_u16(offset + 7)?;
+                        return Some((width as u32, height as u32));
+                    }
+                    _ => offset += 2 + be_u16(offset + 2)? as usize,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn preview_dimensions() {
+        let mut gif = b"GIF89a".to_vec();
+        gif.extend_from_slice(&[3, 0, 2, 0]);
+        let preview = Preview::new("a.gif", gif).unwrap();
+        assert_eq!(preview.format(), ImageFormat::Gif);
+        assert_eq!(preview.dimensions(), Some((3, 2)));
+
+        // SOI, APP0 with empty payload, SOF0.
+        let jpeg = vec![
+            0xff, 0xd8, 0xff, 0xe0, 0x00, 0x02, 0xff, 0xc0, 0x00, 0x0b, 0x08, 0x00, 0x05, 0x00,
+            0x07,
+        ];
+        let preview = Preview::new("a.jpg", jpeg).unwrap();
+        assert_eq!(preview.format(), ImageFormat::Jpeg);
+        assert_eq!(preview.dimensions(), Some((7, 5)));
+
+        assert!(Preview::new("a.png", b"not an image".to_vec()).is_none());
+    }
+
+    #[test]
+    fn encode_bmp_rows() {
+        let rgb = [[1, 2, 3], [4, 5, 6], [7, 8, 9], [10, 11, 12]];
+        let bmp = encode_bmp(1, 4, &rgb);
+        let preview = Preview::new("a.bmp", bmp).unwrap();
+        assert_eq!(preview.format(), ImageFormat::Bmp);
+        assert_eq!(preview.dimensions(), Some((1, 4)));
+        // each row of 3 bytes is padded to 4, in blue, green, red order
+        assert_eq!(preview.data().len(), 54 + 4 * 4);
+        assert_eq!(&preview.data()[54..62], &[3, 2, 1, 0, 6, 5, 4, 0]);
+    }
+}
@@ -1,65 +1,329 @@
 //! Dataset properties.
-use std::io::{self};
+//!
+//! Properties are stored as Java `.properties` files.
+//!
+//! # Notes
+//! + Properties share the decoded input.
+//!   Values without escapes borrow from it, values with escapes are resolved on first access.
+//! + Keys can be interned with a [`KeyInterner`],
+//!   so keys repeated across files, e.g. segment headers, are only stored once.
+//!
+//! # See also
+//! [`java.util.Properties#load`](https://docs.oracle.com/javase/8/docs/api/java/util/Properties.html#load-java.io.Reader-)
+use std::{
+    collections::HashSet,
+    fmt,
+    hash::{BuildHasher, RandomState},
+    io,
+    sync::{Arc, Mutex, OnceLock},
+};
 
-#[derive(Debug)]
-pub struct Properties {
-    /// Sorted list of properties.
-    inner: Vec<(String, String)>,
+/// Interned property keys, shared between [`Properties`].
+///
+/// # Notes
+/// + Keys are split into shards by hash, each with its own lock,
+///   to reduce contention when properties are parsed from multiple threads.
+/// + Keys are freed once the interner and all properties using them are dropped.
+#[derive(Default)]
+pub struct KeyInterner {
+    hasher: RandomState,
+    shards: [Mutex<HashSet<Arc<str>>>; Self::SHARDS],
 }
 
-impl Properties {
-    pub fn new(reader: &mut impl io::Read) -> Result<Self, error::InvalidFormat> {
-        let mut properties = std::collections::HashMap::new();
-        let mut input = String::new();
-        reader.read_to_string(&mut input).unwrap();
-        for line in input.split_terminator("\n") {
-            if line.starts_with("#") {
-                continue;
-            }
-            let Some((key, value)) = line.split_once("=") else {
-                return Err(error::InvalidFormat);
-            };
+impl KeyInterner {
+    const SHARDS: usize = 16;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-            properties.insert(key.to_string(), value.to_string());
+    /// Shared copy of `key`.
+    fn intern(&self, key: &str) -> Arc<str> {
+        let shard = self.hasher.hash_one(key) as usize % Self::SHARDS;
+        let mut keys = self.shards[shard]
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        match keys.get(key) {
+            Some(key) => key.clone(),
+            None => {
+                let key = Arc::<str>::from(key);
+                keys.insert(key.clone());
+                key
+            }
         }
+    }
+
+    /// Number of interned keys.
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|keys| keys.lock().unwrap_or_else(|err| err.into_inner()).len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Clone)]
+pub struct Properties {
+    /// Decoded input.
+    source: Arc<str>,
+
+    /// Entries sorted by key.
+    entries: Vec<Entry>,
 
-        let mut properties = properties.into_iter().collect::<Vec<_>>();
-        properties.sort_unstable_by_key(|(key, _)| key.clone());
-        Ok(Self { inner: properties })
+    /// Values containing escapes, resolved on first access.
+    /// Indexed by [`Entry::escaped`].
+    resolved: Vec<OnceLock<Box<str>>>,
+
+    /// Time stamp from the header comment.
+    timestamp: Option<Timestamp>,
+}
+
+#[derive(Clone)]
+struct Entry {
+    /// Key, shared with other properties if interned.
+    key: Arc<str>,
+
+    /// Span of the raw value in the source.
+    start: u32,
+    end: u32,
+
+    /// Index of the resolved value if the raw value contains escapes or line continuations.
+    /// Escapes are validated while parsing.
+    escaped: Option<u32>,
+}
+
+impl Properties {
+    pub fn new(reader: &mut impl io::Read) -> Result<Self, error::Parse> {
+        let input = read_input(reader)?;
+        Self::from_source(input.into(), |_| true, None)
+    }
+
+    /// Read properties, sharing keys with other properties using the same `keys`.
+    pub fn new_interned(
+        reader: &mut impl io::Read,
+        keys: &KeyInterner,
+    ) -> Result<Self, error::Parse> {
+        let input = read_input(reader)?;
+        Self::from_source(input.into(), |_| true, Some(keys))
     }
 
     /// Only extract keys matching those in `keys`.
     pub fn extract(
         reader: &mut impl io::Read,
-        keys: &Vec<impl AsRef<str>>,
-    ) -> Result<Self, error::InvalidFormat> {
+        keys: &[impl AsRef<str>],
+    ) -> Result<Self, error::Parse> {
         let keys = keys.iter().map(|key| key.as_ref()).collect::<Vec<_>>();
-        let mut properties = std::collections::HashMap::with_capacity(keys.len());
-        let mut input = String::new();
-        reader.read_to_string(&mut input).unwrap();
-        for line in input.split_terminator("\n") {
-            if line.starts_with("#") {
-                continue;
-            }
-            let Some((key, value)) = line.split_once("=") else {
-                return Err(error::InvalidFormat);
-            };
+        let input = read_input(reader)?;
+        Self::from_source(input.into(), |key| keys.contains(&key), None)
+    }
+
+    /// Parse properties from a string.
+    pub fn parse(input: &str) -> Result<Self, error::Parse> {
+        Self::from_source(input.into(), |_| true, None)
+    }
+
+    /// Index the properties of `source`, only retaining keys for which `filter` returns `true`.
+    ///
+    /// # Arguments
+    /// + `keys`: Interner to share keys with, if any.
+    ///
+    /// # Errors
+    /// + [`error::Parse::Io`] with [`io::ErrorKind::InvalidData`] if `source` is larger than 4 GiB.
+    fn from_source(
+        source: Arc<str>,
+        filter: impl Fn(&str) -> bool,
+        keys: Option<&KeyInterner>,
+    ) -> Result<Self, error::Parse> {
+        if u32::try_from(source.len()).is_err() {
+            return Err(
+                io::Error::new(io::ErrorKind::InvalidData, "properties larger than 4 GiB").into(),
+            );
+        }
+
+        let mut properties = Vec::new();
+        let mut timestamp = None;
+        for line in parse::LogicalLines::new(&source) {
+            match line.kind {
+                parse::LineKind::Comment(comment) => {
+                    if properties.is_empty() && timestamp.is_none() {
+                        timestamp = Timestamp::from_str(comment.trim());
+                    }
+                }
 
-            if keys.contains(&key) {
-                properties.insert(key.to_string(), value.to_string());
+                parse::LineKind::Property(span) => {
+                    let (key, value, escaped) = parse::key_value(&source, span, line.number)?;
+                    let retain = match &key {
+                        parse::Key::Span(span) => filter(&source[span.clone()]),
+                        parse::Key::Escaped(key) => filter(key),
+                    };
+                    if retain {
+                        properties.push((key, value, escaped));
+                    }
+                }
             }
         }
 
-        let mut properties = properties.into_iter().collect::<Vec<_>>();
-        properties.sort_unstable_by_key(|(key, _)| key.clone());
-        Ok(Self { inner: properties })
+        let mut resolved = Vec::new();
+        let mut entries = properties
+            .into_iter()
+            .map(|(key, value, escaped)| {
+                let key = match &key {
+                    parse::Key::Span(span) => &source[span.clone()],
+                    parse::Key::Escaped(key) => key.as_str(),
+                };
+                let key = match keys {
+                    Some(keys) => keys.intern(key),
+                    None => Arc::from(key),
+                };
+
+                let escaped = escaped.then(|| {
+                    resolved.push(OnceLock::new());
+                    (resolved.len() - 1) as u32
+                });
+                Entry {
+                    key,
+                    start: value.start as u32,
+                    end: value.end as u32,
+                    escaped,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // retain the last occurrence of duplicate keys
+        entries.reverse();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        entries.dedup_by(|a, b| a.key == b.key);
+        // `collect` reuses the allocation of the parsed lines
+        entries.shrink_to_fit();
+
+        Ok(Self {
+            source,
+            entries,
+            resolved,
+            timestamp,
+        })
     }
 
-    pub fn get(&self, key: impl AsRef<str>) -> Option<&String> {
-        self.inner
-            .binary_search_by_key(&key.as_ref(), |(key, _)| key)
+    pub fn get(&self, key: impl AsRef<str>) -> Option<&str> {
+        self.position(key.as_ref())
+            .map(|idx| self.resolve(&self.entries[idx]))
+    }
+
+    /// Index of the entry with the given key.
+    fn position(&self, key: &str) -> Option<usize> {
+        self.entries
+            .binary_search_by(|entry| (*entry.key).cmp(key))
             .ok()
-            .map(|idx| &self.inner[idx].1)
+    }
+
+    /// Value of an entry.
+    fn resolve(&self, entry: &Entry) -> &str {
+        let raw = &self.source[entry.start as usize..entry.end as usize];
+        match entry.escaped {
+            None => raw,
+            Some(idx) => self.resolved[idx as usize].get_or_init(|| parse::unescape(raw).into()),
+        }
+    }
+
+    /// Hierarchical view of the properties.
+    pub fn tree(&self) -> PropertyTree<'_> {
+        PropertyTree::new(self)
+    }
+
+    /// Hierarchical view of the properties under `path`.
+    /// Shorthand for `self.tree().subtree(path)`.
+    pub fn subtree(&self, path: impl AsRef<str>) -> PropertyTree<'_> {
+        self.tree().subtree(path)
+    }
+
+    /// Time stamp from the header comment, if present.
+    pub fn timestamp(&self) -> Option<&Timestamp> {
+        self.timestamp.as_ref()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over `(key, value)` pairs, sorted by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|entry| (&*entry.key, self.resolve(entry)))
+    }
+
+    /// Write the properties in `.properties` format.
+    /// If present, the time stamp is written as the header comment.
+    pub fn write(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        if let Some(timestamp) = self.timestamp.as_ref() {
+            writeln!(writer, "#{timestamp}")?;
+        }
+
+        for (key, value) in self.iter() {
+            writeln!(
+                writer,
+                "{}={}",
+                parse::escape(key, true),
+                parse::escape(value, false)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Copy of the properties with `values` set, replacing existing keys.
+    pub fn with_values<K, V>(&self, values: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut output = Vec::with_capacity(self.source.len());
+        self.write(&mut output)
+            .expect("writing to a vec can not fail");
+        for (key, value) in values {
+            // later occurrences of a key take precedence
+            output.extend_from_slice(
+                format!(
+                    "{}={}\n",
+                    parse::escape(key.as_ref(), true),
+                    parse::escape(value.as_ref(), false)
+                )
+                .as_bytes(),
+            );
+        }
+
+        let source = String::from_utf8(output).expect("escaped properties are ascii");
+        Self::parse(&source).expect("written properties can be parsed")
+    }
+}
+
+impl fmt::Debug for Properties {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Properties")
+            .field("timestamp", &self.timestamp)
+            .field("properties", &DebugMap(self))
+            .finish()
+    }
+}
+
+struct DebugMap<'a>(&'a Properties);
+impl fmt::Debug for DebugMap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.0.iter()).finish()
+    }
+}
+
+impl PartialEq for Properties {
+    fn eq(&self, other: &Self) -> bool {
+        self.timestamp == other.timestamp && self.iter().eq(other.iter())
     }
 }
 
@@ -68,14 +332,818 @@ impl IntoIterator for Properties {
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.inner.into_iter()
+        self.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+/// Hierarchical view of [`Properties`], where keys are paths separated by `.`.
+///
+/// # Notes
+/// + Keys passed to a tree are relative to its path.
+/// + Errors contain the full key.
+///
+/// # Examples
+/// let segment = properties.subtree("force-segment-header");
+/// let num_points = segment.parse::<usize>("num-points")?;
+/// let channels = segment.list("channels")?;
+#[derive(Clone)]
+pub struct PropertyTree<'a> {
+    properties: &'a Properties,
+
+    /// Full path of the tree, without a trailing `.`.
+    /// Empty for the root.
+    path: String,
+
+    /// Value of the key equal to the path.
+    value: Option<&'a str>,
+
+    /// Sorted entries whose keys start with the path.
+    entries: &'a [Entry],
+}
+
+impl fmt::Debug for PropertyTree<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PropertyTree")
+            .field("path", &self.path)
+            .field("value", &self.value)
+            .field("len", &self.entries.len())
+            .finish()
+    }
+}
+
+impl<'a> PropertyTree<'a> {
+    const SEPARATOR: char = '.';
+    const COUNT_KEY: &'static str = "count";
+    const LIST_KEY: &'static str = "list";
+
+    fn new(properties: &'a Properties) -> Self {
+        Self {
+            properties,
+            path: String::new(),
+            value: None,
+            entries: &properties.entries,
+        }
+    }
+
+    /// Full path of the tree.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Full key of a key relative to the tree.
+    pub fn key(&self, key: impl AsRef<str>) -> String {
+        let key = key.as_ref();
+        if self.path.is_empty() {
+            key.to_string()
+        } else if key.is_empty() {
+            self.path.clone()
+        } else {
+            format!("{}{}{key}", self.path, Self::SEPARATOR)
+        }
+    }
+
+    /// Value of the key equal to the tree's path, if it exists.
+    pub fn value(&self) -> Option<&'a str> {
+        self.value
+    }
+
+    /// # Returns
+    /// `true` if the tree has no value and no descendants.
+    pub fn is_empty(&self) -> bool {
+        self.value.is_none() && self.entries.is_empty()
+    }
+
+    /// Number of descendant properties.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Tree under the relative `path`.
+    /// The tree is empty if no keys exist under `path`.
+    pub fn subtree(&self, path: impl AsRef<str>) -> PropertyTree<'a> {
+        let path = self.key(path);
+        let prefix = format!("{path}{}", Self::SEPARATOR);
+        let start = self
+            .entries
+            .partition_point(|entry| *entry.key < *prefix.as_str());
+        let len = self.entries[start..].partition_point(|entry| entry.key.starts_with(&prefix));
+        let value = self.find(&path);
+
+        PropertyTree {
+            properties: self.properties,
+            path,
+            value,
+            entries: &self.entries[start..start + len],
+        }
+    }
+
+    /// Names of the immediate children, sorted.
+    pub fn children(&self) -> Vec<&'a str> {
+        let prefix_len = if self.path.is_empty() {
+            0
+        } else {
+            self.path.len() + 1
+        };
+
+        let mut children = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let key = &entry.key[prefix_len..];
+                key.split_once(Self::SEPARATOR)
+                    .map(|(child, _)| child)
+                    .unwrap_or(key)
+            })
+            .collect::<Vec<_>>();
+        children.sort_unstable();
+        children.dedup();
+        children
+    }
+
+    /// Iterate over the immediate child trees.
+    pub fn child_trees(&self) -> impl Iterator<Item = PropertyTree<'a>> + '_ {
+        self.children().into_iter().map(|child| self.subtree(child))
+    }
+
+    /// Iterate over `(key, value)` pairs of all descendants, with keys relative to the tree.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        let prefix_len = if self.path.is_empty() {
+            0
+        } else {
+            self.path.len() + 1
+        };
+        self.entries
+            .iter()
+            .map(move |entry| (&entry.key[prefix_len..], self.properties.resolve(entry)))
+    }
+
+    /// Value of the relative `key`, if it exists.
+    pub fn get(&self, key: impl AsRef<str>) -> Option<&'a str> {
+        let key = key.as_ref();
+        if key.is_empty() {
+            return self.value;
+        }
+
+        self.find(&self.key(key))
+    }
+
+    /// Value of the full `key`, if it is one of the tree's entries.
+    fn find(&self, key: &str) -> Option<&'a str> {
+        let properties = self.properties;
+        self.entries
+            .binary_search_by(|entry| (*entry.key).cmp(key))
+            .ok()
+            .map(|idx| properties.resolve(&self.entries[idx]))
+    }
+
+    /// Value of the relative `key`.
+    pub fn get_str(&self, key: impl AsRef<str>) -> Result<&'a str, error::Property> {
+        let key = key.as_ref();
+        self.get(key)
+            .ok_or_else(|| error::Property::NotFound(self.key(key)))
+    }
+
+    /// Value of the relative `key`, converted with `convert`.
+    /// Allows using the `from_str(input) -> Option<Self>` constructors.
+    pub fn get_with<T>(
+        &self,
+        key: impl AsRef<str>,
+        convert: impl FnOnce(&'a str) -> Option<T>,
+    ) -> Result<T, error::Property> {
+        let key = key.as_ref();
+        let value = self.get_str(key)?;
+        convert(value).ok_or_else(|| error::Property::InvalidValue(self.key(key)))
+    }
+
+    /// Parse the value of the relative `key`.
+    pub fn parse<T>(&self, key: impl AsRef<str>) -> Result<T, error::Property>
+    where
+        T: std::str::FromStr,
+    {
+        self.get_with(key, |value| value.parse::<T>().ok())
+    }
+
+    /// # Notes
+    /// + `NaN`, `Infinity`, and `-Infinity` are accepted.
+    pub fn get_f64(&self, key: impl AsRef<str>) -> Result<f64, error::Property> {
+        self.parse::<f64>(key)
+    }
+
+    /// # Returns
+    /// `None` if the value is `NaN`.
+    pub fn get_f64_non_nan(&self, key: impl AsRef<str>) -> Result<Option<f64>, error::Property> {
+        let value = self.get_f64(key)?;
+        Ok((!value.is_nan()).then_some(value))
+    }
+
+    pub fn get_i64(&self, key: impl AsRef<str>) -> Result<i64, error::Property> {
+        self.parse::<i64>(key)
+    }
+
+    pub fn get_bool(&self, key: impl AsRef<str>) -> Result<bool, error::Property> {
+        self.parse::<bool>(key)
+    }
+
+    /// # See also
+    /// + [`Timestamp::from_value_str`]
+    pub fn get_timestamp(&self, key: impl AsRef<str>) -> Result<Timestamp, error::Property> {
+        self.get_with(key, Timestamp::from_value_str)
+    }
+
+    /// Value of `{key}.count`.
+    pub fn count(&self, key: impl AsRef<str>) -> Result<usize, error::Property> {
+        self.subtree(key).parse::<usize>(Self::COUNT_KEY)
+    }
+
+    /// Whitespace separated values of `{key}.list`.
+    pub fn list(&self, key: impl AsRef<str>) -> Result<Vec<&'a str>, error::Property> {
+        let list = self.subtree(key).get_str(Self::LIST_KEY)?;
+        Ok(list.split_ascii_whitespace().collect())
+    }
+}
+
+/// Read the entire input.
+/// Input is interpreted as UTF-8, falling back to ISO 8859-1.
+fn read_input(reader: &mut impl io::Read) -> Result<String, error::Parse> {
+    let mut input = Vec::new();
+    reader.read_to_end(&mut input)?;
+    let input = match String::from_utf8(input) {
+        Ok(input) => input,
+        Err(err) => err
+            .into_bytes()
+            .into_iter()
+            .map(|byte| byte as char)
+            .collect(),
+    };
+
+    Ok(input)
+}
+
+/// Time stamp as written by `java.util.Date#toString`,
+/// e.g. `Thu Jan 08 16:39:16 CET 2026`,
+/// or as a property value, e.g. `2026-01-08 16:35:28.552 +0100`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timestamp {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+    time_zone: String,
+}
+
+impl Timestamp {
+    const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    pub fn from_str(input: impl AsRef<str>) -> Option<Self> {
+        let mut parts = input.as_ref().split_ascii_whitespace();
+        let weekday = parts.next()?;
+        let month = parts.next()?;
+        let day = parts.next()?;
+        let time = parts.next()?;
+        let time_zone = parts.next()?;
+        let year = parts.next()?;
+        if parts.next().is_some() || !Self::WEEKDAYS.contains(&weekday) {
+            return None;
+        }
+
+        let month = Self::MONTHS.iter().position(|name| *name == month)? as u8 + 1;
+        let day = day
+            .parse::<u8>()
+            .ok()
+            .filter(|day| (1..=31).contains(day))?;
+        let year = year.parse::<i32>().ok()?;
+        let mut time = time.split(':');
+        let hour = time.next()?.parse::<u8>().ok().filter(|hour| *hour < 24)?;
+        let minute = time
+            .next()?
+            .parse::<u8>()
+            .ok()
+            .filter(|minute| *minute < 60)?;
+        let second = time
+            .next()?
+            .parse::<u8>()
+            .ok()
+            .filter(|second| *second <= 60)?;
+        if time.next().is_some() {
+            return None;
+        }
+
+        Some(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond: 0,
+            time_zone: time_zone.to_string(),
+        })
+    }
+
+    /// Parse a time stamp property value.
+    /// e.g. `2026-01-08 16:35:28.552 +0100`.
+    ///
+    /// # Notes
+    /// + The fractional seconds and time zone are optional.
+    pub fn from_value_str(input: impl AsRef<str>) -> Option<Self> {
+        let mut parts = input.as_ref().split_ascii_whitespace();
+        let date = parts.next()?;
+        let time = parts.next()?;
+        let time_zone = parts.next().unwrap_or("");
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let mut date = date.split('-');
+        let year = date.next()?.parse::<i32>().ok()?;
+        let month = date
+            .next()?
+            .parse::<u8>()
+            .ok()
+            .filter(|month| (1..=12).contains(month))?;
+        let day = date
+            .next()?
+            .parse::<u8>()
+            .ok()
+            .filter(|day| (1..=31).contains(day))?;
+        if date.next().is_some() {
+            return None;
+        }
+
+        let (time, fraction) = match time.split_once('.') {
+            Some((time, fraction)) => (time, Some(fraction)),
+            None => (time, None),
+        };
+        let mut time = time.split(':');
+        let hour = time.next()?.parse::<u8>().ok().filter(|hour| *hour < 24)?;
+        let minute = time
+            .next()?
+            .parse::<u8>()
+            .ok()
+            .filter(|minute| *minute < 60)?;
+        let second = time
+            .next()?
+            .parse::<u8>()
+            .ok()
+            .filter(|second| *second <= 60)?;
+        if time.next().is_some() {
+            return None;
+        }
+
+        let nanosecond = match fraction {
+            None => 0,
+            Some(fraction) => {
+                if fraction.is_empty()
+                    || fraction.len() > 9
+                    || !fraction.bytes().all(|byte| byte.is_ascii_digit())
+                {
+                    return None;
+                }
+                let digits = fraction.parse::<u32>().ok()?;
+                digits * 10_u32.pow(9 - fraction.len() as u32)
+            }
+        };
+
+        Some(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond,
+            time_zone: time_zone.to_string(),
+        })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Month of the year, starting from 1.
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Day of the month, starting from 1.
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Fractional part of the second.
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    /// Time zone abbreviation or offset. e.g. `CET` or `+0100`.
+    pub fn time_zone(&self) -> &String {
+        &self.time_zone
+    }
+
+    /// Day of the week, where `0` is Sunday.
+    pub fn weekday(&self) -> u8 {
+        // Sakamoto's method
+        const OFFSETS: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+        let year = if self.month < 3 {
+            self.year - 1
+        } else {
+            self.year
+        };
+        let day = year + year.div_euclid(4) - year.div_euclid(100)
+            + year.div_euclid(400)
+            + OFFSETS[self.month as usize - 1]
+            + self.day as i32;
+        day.rem_euclid(7) as u8
+    }
+}
+
+/// Formats as `java.util.Date#toString`.
+/// Fractional seconds are not included.
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {:02} {:02}:{:02}:{:02} {} {}",
+            Self::WEEKDAYS[self.weekday() as usize],
+            Self::MONTHS[self.month as usize - 1],
+            self.day,
+            self.hour,
+            self.minute,
+            self.second,
+            self.time_zone,
+            self.year
+        )
+    }
+}
+
+mod parse {
+    use super::error;
+    use std::{iter::Peekable, ops::Range, str::Chars};
+
+    /// Characters considered white space.
+    const WHITESPACE: [char; 3] = [' ', '\t', '\x0c'];
+
+    pub enum LineKind<'a> {
+        /// Contents of a comment line, excluding the comment marker.
+        Comment(&'a str),
+
+        /// Span of a key-value line, including its continuation lines.
+        Property(Range<usize>),
+    }
+
+    pub struct LogicalLine<'a> {
+        /// Line number of the first natural line, starting from 1.
+        pub number: usize,
+        pub kind: LineKind<'a>,
+    }
+
+    /// Iterator over the logical lines of a properties file.
+    /// Blank lines are skipped.
+    pub struct LogicalLines<'a> {
+        input: &'a str,
+        position: usize,
+        number: usize,
+    }
+
+    impl<'a> LogicalLines<'a> {
+        pub fn new(input: &'a str) -> Self {
+            Self {
+                input,
+                position: 0,
+                number: 0,
+            }
+        }
+
+        /// Span of the next natural line, without its terminator.
+        fn next_natural_line(&mut self) -> Option<Range<usize>> {
+            if self.position >= self.input.len() {
+                return None;
+            }
+
+            self.number += 1;
+            let start = self.position;
+            let rest = &self.input.as_bytes()[start..];
+            let Some(end) = rest.iter().position(|&byte| byte == b'\n' || byte == b'\r') else {
+                self.position = self.input.len();
+                return Some(start..self.input.len());
+            };
+
+            let terminator = if rest[end..].starts_with(b"\r\n") {
+                2
+            } else {
+                1
+            };
+            self.position = start + end + terminator;
+            Some(start..start + end)
+        }
+    }
+
+    impl<'a> Iterator for LogicalLines<'a> {
+        type Item = LogicalLine<'a>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            loop {
+                let line = self.next_natural_line()?;
+                let number = self.number;
+                let content = self.input[line.clone()].trim_start_matches(WHITESPACE);
+                if content.is_empty() {
+                    continue;
+                }
+
+                if let Some(comment) = content.strip_prefix(['#', '!']) {
+                    return Some(LogicalLine {
+                        number,
+                        kind: LineKind::Comment(comment),
+                    });
+                }
+
+                let start = line.end - content.len();
+                let mut end = line.end;
+                let mut last = content;
+                while is_continued(last) {
+                    let Some(next) = self.next_natural_line() else {
+                        break;
+                    };
+                    end = next.end;
+                    last = &self.input[next];
+                }
+
+                return Some(LogicalLine {
+                    number,
+                    kind: LineKind::Property(start..end),
+                });
+            }
+        }
+    }
+
+    /// A line is continued if it ends with an odd number of backslashes.
+    fn is_continued(line: &str) -> bool {
+        let backslashes = line.bytes().rev().take_while(|&byte| byte == b'\\').count();
+        backslashes % 2 == 1
+    }
+
+    pub enum Key {
+        /// Span of the source without escapes.
+        Span(Range<usize>),
+
+        /// Unescaped key.
+        Escaped(String),
+    }
+
+    /// Split a logical line into its key and value.
+    ///
+    /// # Arguments
+    /// + `span`: Span of the logical line in `source`.
+    ///
+    /// # Returns
+    /// `(key, value span, value contains escapes)`
+    pub fn key_value(
+        source: &str,
+        span: Range<usize>,
+        number: usize,
+    ) -> Result<(Key, Range<usize>, bool), error::Parse> {
+        // separators are ASCII, so the bytes can be scanned
+        let line = &source[span.clone()];
+        let bytes = line.as_bytes();
+        let mut key_end = line.len();
+        let mut idx = 0;
+        while idx < bytes.len() {
+            match bytes[idx] {
+                b'\\' => {
+                    idx += 1;
+                    if let Some(terminator @ (b'\r' | b'\n')) = bytes.get(idx) {
+                        if *terminator == b'\r' && bytes.get(idx + 1) == Some(&b'\n') {
+                            idx += 1;
+                        }
+                        idx += 1;
+                        while idx < bytes.len() && is_whitespace(bytes[idx]) {
+                            idx += 1;
+                        }
+                        continue;
+                    }
+                }
+                b'=' | b':' => {
+                    key_end = idx;
+                    break;
+                }
+                byte if is_whitespace(byte) => {
+                    key_end = idx;
+                    break;
+                }
+                _ => {}
+            }
+            idx += 1;
+        }
+
+        let key = &line[..key_end];
+        let rest = trim_blank(&line[key_end..]);
+        let rest = rest.strip_prefix(['=', ':']).unwrap_or(rest);
+        let value = trim_blank(rest);
+        let value_start = span.end - value.len();
+
+        let key = if key.contains('\\') {
+            Key::Escaped(unescape_checked(key, number)?)
+        } else {
+            Key::Span(span.start..span.start + key_end)
+        };
+
+        let escaped = value.contains('\\');
+        if escaped {
+            unescape_with(value, number, |_| {})?;
+        }
+
+        Ok((key, value_start..span.end, escaped))
+    }
+
+    fn is_whitespace(byte: u8) -> bool {
+        matches!(byte, b' ' | b'\t' | b'\x0c')
+    }
+
+    /// Trim leading white space and line continuations.
+    fn trim_blank(input: &str) -> &str {
+        let mut input = input.trim_start_matches(WHITESPACE);
+        while let Some(rest) = input.strip_prefix('\\') {
+            let Some(rest) = rest
+                .strip_prefix("\r\n")
+                .or_else(|| rest.strip_prefix(['\r', '\n']))
+            else {
+                break;
+            };
+            input = rest.trim_start_matches(WHITESPACE);
+        }
+
+        input
+    }
+
+    /// Resolve escape sequences and line continuations.
+    ///
+    /// # Panics
+    /// If `input` contains an invalid escape sequence.
+    /// Values are validated while parsing, so they can be resolved lazily.
+    pub fn unescape(input: &str) -> String {
+        unescape_checked(input, 0).expect("escapes are validated while parsing")
+    }
+
+    /// Resolve escape sequences and line continuations.
+    fn unescape_checked(input: &str, number: usize) -> Result<String, error::Parse> {
+        let mut output = String::with_capacity(input.len());
+        unescape_with(input, number, |ch| output.push(ch))?;
+        Ok(output)
+    }
+
+    /// Resolve escape sequences and line continuations,
+    /// passing each resulting character to `push`.
+    fn unescape_with(
+        input: &str,
+        number: usize,
+        mut push: impl FnMut(char),
+    ) -> Result<(), error::Parse> {
+        let mut chars = input.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if ch != '\\' {
+                push(ch);
+                continue;
+            }
+
+            let Some(escaped) = chars.next() else {
+                break;
+            };
+            match escaped {
+                '\r' | '\n' => {
+                    if escaped == '\r' {
+                        chars.next_if_eq(&'\n');
+                    }
+                    while chars.next_if(|ch| WHITESPACE.contains(ch)).is_some() {}
+                }
+                't' => push('\t'),
+                'n' => push('\n'),
+                'r' => push('\r'),
+                'f' => push('\x0c'),
+                'u' => {
+                    let unit = code_unit(&mut chars, number)?;
+                    let code_point = if (0xd800..0xdc00).contains(&unit) {
+                        // high surrogate must be followed by an escaped low surrogate
+                        let (Some('\\'), Some('u')) = (chars.next(), chars.next()) else {
+                            return Err(error::Parse::InvalidUnicodeEscape { line: number });
+                        };
+                        let low = code_unit(&mut chars, number)?;
+                        if !(0xdc00..0xe000).contains(&low) {
+                            return Err(error::Parse::InvalidUnicodeEscape { line: number });
+                        }
+
+                        0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00)
+                    } else {
+                        unit
+                    };
+
+                    let Some(ch) = char::from_u32(code_point) else {
+                        return Err(error::Parse::InvalidUnicodeEscape { line: number });
+                    };
+                    push(ch);
+                }
+                _ => push(escaped),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse the four hex digits of a `\uXXXX` escape.
+    fn code_unit(chars: &mut Peekable<Chars>, number: usize) -> Result<u32, error::Parse> {
+        let mut unit = 0;
+        for _ in 0..4 {
+            let Some(digit) = chars.next().and_then(|ch| ch.to_digit(16)) else {
+                return Err(error::Parse::InvalidUnicodeEscape { line: number });
+            };
+            unit = (unit << 4) | digit;
+        }
+
+        Ok(unit)
+    }
+
+    /// Escape a key or value so it is read back unchanged.
+    /// Characters outside of printable ASCII are written as `\uXXXX` escapes.
+    pub fn escape(input: &str, is_key: bool) -> String {
+        let mut output = String::with_capacity(input.len());
+        for (idx, ch) in input.chars().enumerate() {
+            match ch {
+                '\\' => output.push_str("\\\\"),
+                '\t' => output.push_str("\\t"),
+                '\n' => output.push_str("\\n"),
+                '\r' => output.push_str("\\r"),
+                '\x0c' => output.push_str("\\f"),
+                ' ' if is_key || idx == 0 => output.push_str("\\ "),
+                '=' | ':' | '#' | '!' => {
+                    output.push('\\');
+                    output.push(ch);
+                }
+                ' '..='~' => output.push(ch),
+                _ => {
+                    let mut buf = [0; 2];
+                    for unit in ch.encode_utf16(&mut buf) {
+                        output.push_str(&format!("\\u{unit:04X}"));
+                    }
+                }
+            }
+        }
+
+        output
     }
 }
 
 pub mod error {
-    /// The format of the file was invalid.
-    #[derive(Debug)]
-    pub struct InvalidFormat;
+    use std::{fmt, io};
+
+    /// The properties could not be parsed.
+    #[derive(Debug, derive_more::From)]
+    pub enum Parse {
+        /// The input could not be read.
+        Io(io::Error),
+
+        /// A `\uXXXX` escape on the given line was malformed.
+        InvalidUnicodeEscape { line: usize },
+    }
+
+    impl fmt::Display for Parse {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Io(_) => write!(f, "could not read properties"),
+                Self::InvalidUnicodeEscape { line } => {
+                    write!(f, "invalid unicode escape on line {line}")
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for Parse {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::Io(err) => Some(err),
+                Self::InvalidUnicodeEscape { .. } => None,
+            }
+        }
+    }
 
     #[derive(Debug)]
     pub enum Property {
@@ -83,13 +1151,24 @@ pub mod error {
         /// The property with the given key had an invalid value.
         InvalidValue(String),
     }
+
+    impl fmt::Display for Property {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::NotFound(key) => write!(f, "property `{key}` not found"),
+                Self::InvalidValue(key) => write!(f, "invalid value of `{key}`"),
+            }
+        }
+    }
+
+    impl std::error::Error for Property {}
 }
 
 /// Extract a value from `Properties`, returning the relevant error.
 ///
 /// # Returns
 /// `Result<T, [crate::dataset::properties::error::Property]>`
-/// where `T` is an `&String` if no type is given, otherwise it is the given type.
+/// where `T` is an `&str` if no type is given, otherwise it is the given type.
 ///
 /// # Examples
 /// let value_string = extract_value!(properties, "string_value_key");
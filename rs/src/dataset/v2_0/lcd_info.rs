@@ -1,5 +1,4 @@
-use super::properties;
-use crate::dataset::properties as dataset_properties;
+use crate::dataset::properties::{self as dataset_properties, PropertyTree};
 use std::sync::Arc;
 
 type Value = f64;
@@ -15,36 +14,45 @@ pub struct LcdInfo {
 
 impl LcdInfo {
     /// `lcd-info.{index}.unit.unit`
-    fn unit_key(index: usize) -> String {
-        format!("lcd-info.{index}.unit.unit")
-    }
+    const UNIT_KEY: &str = "unit.unit";
 
     /// `lcd-info.{index}.type`
-    fn data_type_key(index: usize) -> String {
-        format!("lcd-info.{index}.type")
-    }
+    const DATA_TYPE_KEY: &str = "type";
+
+    /// `lcd-info.{index}.encoder`
+    const ENCODER_KEY: &str = "encoder";
+
+    /// `lcd-info.{index}.channel`
+    const CHANNEL_KEY: &str = "channel";
+
+    /// `lcd-info.{index}.conversion-set`
+    const CONVERSION_SET_KEY: &str = "conversion-set";
 }
 
 impl LcdInfo {
+    /// # Arguments
+    /// + `properties`: The `lcd-info.{index}` tree.
     pub fn from_properties(
-        properties: &properties::SharedData,
-        index: usize,
+        properties: &PropertyTree,
     ) -> Result<Self, dataset_properties::error::Property> {
-        let unit = dataset_properties::extract_value!(properties, Self::unit_key(index))?;
-        let data_type = dataset_properties::extract_value!(properties, Self::data_type_key(index), from_str DataType)?;
+        let unit = properties.get_str(Self::UNIT_KEY)?;
+        let data_type = properties.get_with(Self::DATA_TYPE_KEY, DataType::from_str)?;
+        let encoder = properties.subtree(Self::ENCODER_KEY);
         let decoder = match data_type {
-            DataType::Integer => decoder::int_from_properties(&properties, index)?,
+            DataType::Integer => decoder::int_from_properties(&encoder)?,
             DataType::Float => Arc::new(decoder::RawFloatDecoder),
-            DataType::Raster => Arc::new(decoder::RasterDecoder),
+            DataType::Raster => Arc::new(decoder::RasterDecoder::from_properties(&encoder)),
         };
 
-        let channel_info = ChannelInfo::from_properties(properties, index)?;
-        let conversion_set = conversion::ConversionSet::from_properties(properties, index)?;
+        let channel_info = ChannelInfo::from_properties(&properties.subtree(Self::CHANNEL_KEY))?;
+        let conversion_set = conversion::ConversionSet::from_properties(
+            &properties.subtree(Self::CONVERSION_SET_KEY),
+        )?;
 
         Ok(Self {
             data_type,
             channel_info,
-            unit: unit.clone(),
+            unit: unit.to_string(),
             decoder,
             conversion_set,
         })
@@ -52,28 +60,183 @@ impl LcdInfo {
 }
 
 impl LcdInfo {
+    pub fn data_type(&self) -> DataType {
+        self.data_type
+    }
+
+    pub fn channel_info(&self) -> &ChannelInfo {
+        &self.channel_info
+    }
+
     /// # Returns
-    /// List of available unit conversions.
+    /// Calibration slots the data can be converted to,
+    /// starting with the base slot.
     pub fn available_units(&self) -> Vec<String> {
-        todo!("LcdInfo::available_units")
+        self.conversion_set.available_slots()
+    }
+
+    /// # Returns
+    /// Unit of the calibration slot, or of the default slot if `None`.
+    /// `None` if the slot does not exist.
+    pub fn unit(&self, slot: Option<&str>) -> Option<&String> {
+        let slot = slot.unwrap_or(self.conversion_set.default());
+        if slot == self.conversion_set.base() {
+            Some(&self.unit)
+        } else {
+            self.conversion_set.unit(slot)
+        }
+    }
+
+    pub fn conversion_set(&self) -> &conversion::ConversionSet {
+        &self.conversion_set
     }
 
     /// Convert raw data to default units.
-    pub fn convert_data(
+    pub fn convert_data(&self, data: &decoder::RawData) -> Result<Vec<Value>, error::ConvertData> {
+        let data = self.decoder.decode(data)?;
+        Ok(self.convert(data))
+    }
+
+    /// Convert raw data to the units of the given calibration slot.
+    pub fn convert_data_to(
         &self,
         data: &decoder::RawData,
-    ) -> Result<Vec<Value>, decoder::InvalidDataLength> {
+        slot: impl AsRef<str>,
+    ) -> Result<Vec<Value>, error::ConvertData> {
         let data = self.decoder.decode(data)?;
-        let data = self.conversion_set.convert(data);
-        Ok(data)
+        let slot = slot.as_ref();
+        self.convert_to(data, slot)
+            .ok_or_else(|| error::ConvertData::InvalidCalibrationSlot(slot.to_string()))
     }
 
-    /// Get data in given units.
-    pub fn convert_data_to(
+    /// Convert values from base to default units.
+    pub fn convert(&self, data: Vec<Value>) -> Vec<Value> {
+        self.conversion_set.convert(data)
+    }
+
+    /// Convert values from base to the units of the given calibration slot.
+    ///
+    /// # Returns
+    /// `None` if the slot does not exist.
+    pub fn convert_to(&self, data: Vec<Value>, slot: impl AsRef<str>) -> Option<Vec<Value>> {
+        self.conversion_set.convert_to(data, slot)
+    }
+
+    /// # Returns
+    /// Scaling of the conversion resulting in the given calibration slot.
+    /// `None` if no defined conversion results in the slot.
+    pub fn calibration(&self, slot: impl AsRef<str>) -> Option<&scale::LinearOffsetMultiplier> {
+        self.conversion_set
+            .conversion(slot)
+            .and_then(|conversion| conversion.scaling())
+    }
+}
+
+impl LcdInfo {
+    /// Copy of the info with the scaling of a conversion replaced.
+    /// Used to apply a calibration made after acquisition,
+    /// e.g. the `sensitivity` of the `distance` or the `springConstant` of the `force` slot.
+    ///
+    /// # Arguments
+    /// + `slot`: Calibration slot the conversion results in.
+    ///
+    /// # Returns
+    /// `None` if no defined conversion results in the slot.
+    pub fn with_calibration(
         &self,
-        unit: String,
-    ) -> Result<Vec<Value>, dataset_properties::error::Property> {
-        todo!("LcdInfo::convert_data_to")
+        slot: impl AsRef<str>,
+        multiplier: Value,
+        offset: Value,
+    ) -> Option<Self> {
+        let scaling = scale::LinearOffsetMultiplier::new(offset, multiplier);
+        let conversion_set = self.conversion_set.with_scaling(slot, scaling)?;
+        Some(Self {
+            conversion_set,
+            ..self.clone()
+        })
+    }
+
+    /// Properties recording the calibration of a slot.
+    /// The values of `properties` are recorded as the original calibration,
+    /// unless they already record one.
+    ///
+    /// # Arguments
+    /// + `properties`: The `lcd-info.{index}` tree the info was created from.
+    /// + `slot`: Calibration slot the conversion results in.
+    ///
+    /// # Returns
+    /// `(key, value)` pairs, with full keys.
+    /// `None` if no defined conversion results in the slot.
+    pub fn calibration_properties(
+        &self,
+        properties: &PropertyTree,
+        slot: impl AsRef<str>,
+    ) -> Option<Vec<(String, String)>> {
+        let slot = slot.as_ref();
+        let quantity = self.conversion_set.quantity(slot)?;
+        let scaling = self.calibration(slot)?;
+        let tree = properties
+            .subtree(Self::CONVERSION_SET_KEY)
+            .subtree(conversion::ConversionSet::CONVERSION_KEY)
+            .subtree(quantity)
+            .subtree(conversion::Conversion::SCALING_KEY);
+
+        let mut values = Vec::with_capacity(4);
+        for (key, original_key) in [
+            (scale::MULTIPLIER_KEY, scale::ORIGINAL_MULTIPLIER_KEY),
+            (scale::OFFSET_KEY, scale::ORIGINAL_OFFSET_KEY),
+        ] {
+            if tree.get(original_key).is_none()
+                && let Some(original) = tree.get(key)
+            {
+                values.push((tree.key(original_key), original.to_string()));
+            }
+        }
+        values.push((
+            tree.key(scale::MULTIPLIER_KEY),
+            scaling.multiplier().to_string(),
+        ));
+        values.push((tree.key(scale::OFFSET_KEY), scaling.offset().to_string()));
+        Some(values)
+    }
+}
+
+pub mod error {
+    use super::decoder;
+    use std::fmt;
+
+    #[derive(Debug)]
+    pub enum ConvertData {
+        InvalidDataLength,
+        UnsupportedRasterLayout(String),
+        InvalidCalibrationSlot(String),
+    }
+
+    impl fmt::Display for ConvertData {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::InvalidDataLength => write!(f, "{}", decoder::InvalidDataLength),
+                Self::UnsupportedRasterLayout(layout) => {
+                    write!(f, "unsupported raster layout: {layout}")
+                }
+                Self::InvalidCalibrationSlot(slot) => {
+                    write!(f, "calibration slot `{slot}` does not exist")
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for ConvertData {}
+
+    impl From<decoder::Error> for ConvertData {
+        fn from(value: decoder::Error) -> Self {
+            match value {
+                decoder::Error::InvalidDataLength => Self::InvalidDataLength,
+                decoder::Error::UnsupportedRasterLayout(layout) => {
+                    Self::UnsupportedRasterLayout(layout)
+                }
+            }
+        }
     }
 }
 
@@ -106,35 +269,29 @@ pub struct ChannelInfo {
 
 impl ChannelInfo {
     /// `lcd-info.{index}.channel.type`
-    fn type_key(index: usize) -> String {
-        format!("lcd-info.{index}.channel.type")
-    }
+    const TYPE_KEY: &str = "type";
 
     /// `lcd-info.{index}.channel.name`
-    fn name_key(index: usize) -> String {
-        format!("lcd-info.{index}.channel.name")
-    }
+    const NAME_KEY: &str = "name";
 
     /// `lcd-info.{index}.channel.fancy-name`
-    fn fancy_name_key(index: usize) -> String {
-        format!("lcd-info.{index}.channel.fancy-name")
-    }
+    const FANCY_NAME_KEY: &str = "fancy-name";
 }
 
 impl ChannelInfo {
+    /// # Arguments
+    /// + `properties`: The `lcd-info.{index}.channel` tree.
     pub fn from_properties(
-        properties: &properties::SharedData,
-        index: usize,
+        properties: &PropertyTree,
     ) -> Result<Self, dataset_properties::error::Property> {
-        let kind = dataset_properties::extract_value!(properties, Self::type_key(index), from_str ChannelKind)?;
-        let name = dataset_properties::extract_value!(properties, Self::name_key(index))?;
-        let fancy_name =
-            dataset_properties::extract_value!(properties, Self::fancy_name_key(index))?;
+        let kind = properties.get_with(Self::TYPE_KEY, ChannelKind::from_str)?;
+        let name = properties.get_str(Self::NAME_KEY)?;
+        let fancy_name = properties.get_str(Self::FANCY_NAME_KEY)?;
 
         Ok(Self {
             kind,
-            name: name.clone(),
-            fancy_name: fancy_name.clone(),
+            name: name.to_string(),
+            fancy_name: fancy_name.to_string(),
         })
     }
 }
@@ -154,7 +311,45 @@ impl ChannelKind {
 }
 
 pub mod scale {
-    use super::Value;
+    use super::{PropertyTree, Value, dataset_properties};
+
+    /// `{scaling}.type`
+    pub const TYPE_KEY: &str = "type";
+
+    /// `{scaling}.style`
+    pub const STYLE_KEY: &str = "style";
+
+    /// `{scaling}.offset`
+    pub const OFFSET_KEY: &str = "offset";
+
+    /// `{scaling}.multiplier`
+    pub const MULTIPLIER_KEY: &str = "multiplier";
+
+    /// `{scaling}.unit.unit`
+    pub const UNIT_KEY: &str = "unit.unit";
+
+    /// `{scaling}.original-offset`
+    /// Offset of the file, set when the calibration is overridden.
+    pub const ORIGINAL_OFFSET_KEY: &str = "original-offset";
+
+    /// `{scaling}.original-multiplier`
+    /// Multiplier of the file, set when the calibration is overridden.
+    pub const ORIGINAL_MULTIPLIER_KEY: &str = "original-multiplier";
+
+    /// Create the scale described by a `scaling` tree.
+    pub fn from_properties(
+        properties: &PropertyTree,
+    ) -> Result<LinearOffsetMultiplier, dataset_properties::error::Property> {
+        let scale_type = properties.get_with(TYPE_KEY, Type::from_str)?;
+        let scale_style = properties.get_with(STYLE_KEY, Style::from_str)?;
+        match (scale_type, scale_style) {
+            (Type::Linear, Style::OffsetMultiplier) => {
+                let offset = properties.get_f64(OFFSET_KEY)?;
+                let multiplier = properties.get_f64(MULTIPLIER_KEY)?;
+                Ok(LinearOffsetMultiplier::new(offset, multiplier))
+            }
+        }
+    }
 
     pub trait Scale<T> {
         /// Scale data.
@@ -211,6 +406,7 @@ pub mod scale {
         }
     }
 
+    #[derive(Clone, Copy, PartialEq, Debug)]
     pub struct LinearOffsetMultiplier {
         a0: Value,
         a1: Value,
@@ -220,6 +416,14 @@ pub mod scale {
         pub fn new(a0: Value, a1: Value) -> Self {
             Self { a0, a1 }
         }
+
+        pub fn offset(&self) -> Value {
+            self.a0
+        }
+
+        pub fn multiplier(&self) -> Value {
+            self.a1
+        }
     }
 
     macro_rules! impl_scale_linear_offset_multiplier {
@@ -247,20 +451,20 @@ pub mod scale {
 }
 
 pub mod decoder {
-    use super::{Value, dataset_properties, properties, properties::SharedData, scale::Scale};
-    use std::{mem, sync::Arc};
+    use super::{PropertyTree, Value, dataset_properties, scale::Scale};
+    use std::{fmt, mem, sync::Arc};
 
     pub type RawData = [u8];
 
     pub trait Decode {
-        fn decode(&self, data: &RawData) -> Result<Vec<Value>, InvalidDataLength>;
+        fn decode(&self, data: &RawData) -> Result<Vec<Value>, Error>;
     }
 
     impl<T, D> Decode for T
     where
         T: DecodeRaw<Data = D> + Scale<D>,
     {
-        fn decode(&self, data: &RawData) -> Result<Vec<Value>, InvalidDataLength> {
+        fn decode(&self, data: &RawData) -> Result<Vec<Value>, Error> {
             let data = DecodeRaw::decode_raw(self, data)?;
             let data = data
                 .into_iter()
@@ -318,27 +522,21 @@ pub mod decoder {
         }
     }
 
+    /// `lcd-info.{index}.encoder.type`
+    const TYPE_KEY: &str = "type";
+
+    /// `lcd-info.{index}.encoder.scaling`
+    const SCALING_KEY: &str = "scaling";
+
+    /// # Arguments
+    /// + `properties`: The `lcd-info.{index}.encoder` tree.
     pub fn int_from_properties(
-        properties: &properties::SharedData,
-        idx: usize,
+        properties: &PropertyTree,
     ) -> Result<Arc<dyn Decode + Sync + Send>, super::dataset_properties::error::Property> {
-        use super::scale::{Style, Type};
-
-        let data_type = dataset_properties::extract_value!(properties, properties::SharedData::lcd_info_encoder_type_key(idx), from_str DataType)?;
-        let scale_type = dataset_properties::extract_value!(properties, properties::SharedData::lcd_info_encoder_scaling_type_key(idx), from_str super::scale::Type)?;
-        let scale_style = dataset_properties::extract_value!(properties, properties::SharedData::lcd_info_encoder_scaling_style_key(idx), from_str super::scale::Style)?;
-        let unit = dataset_properties::extract_value!(
-            properties,
-            properties::SharedData::lcd_info_encoder_unit_key(idx)
-        )?;
-        let unit = unit.clone();
-
-        let scale = match (scale_type, scale_style) {
-            (Type::Linear, Style::OffsetMultiplier) => {
-                _create_decoder_linear_offset_multiplier(properties, idx)?
-            }
-        };
-        let scale = Arc::new(scale);
+        let data_type = properties.get_with(TYPE_KEY, DataType::from_str)?;
+        let scaling = properties.subtree(SCALING_KEY);
+        let unit = scaling.get_str(super::scale::UNIT_KEY)?.to_string();
+        let scale = Arc::new(super::scale::from_properties(&scaling)?);
 
         match data_type {
             DataType::I16 => Ok(Arc::new(IntDecoder::<i16>::new(scale, unit))),
@@ -349,40 +547,6 @@ pub mod decoder {
         }
     }
 
-    fn _create_decoder_linear_offset_multiplier(
-        properties: &properties::SharedData,
-        idx: usize,
-    ) -> Result<super::scale::LinearOffsetMultiplier, super::dataset_properties::error::Property>
-    {
-        let multiplier_key = properties::SharedData::lcd_info_encoder_scaling_multiplier_key(idx);
-        let Some(multiplier) = properties.get(&multiplier_key) else {
-            return Err(super::dataset_properties::error::Property::NotFound(
-                multiplier_key.clone(),
-            ));
-        };
-        let Ok(multiplier) = multiplier.parse::<f64>() else {
-            return Err(super::dataset_properties::error::Property::InvalidValue(
-                multiplier_key.clone(),
-            ));
-        };
-
-        let offset_key = properties::SharedData::lcd_info_encoder_scaling_offset_key(idx);
-        let Some(offset) = properties.get(&offset_key) else {
-            return Err(super::dataset_properties::error::Property::NotFound(
-                offset_key.clone(),
-            ));
-        };
-        let Ok(offset) = offset.parse::<f64>() else {
-            return Err(super::dataset_properties::error::Property::InvalidValue(
-                offset_key.clone(),
-            ));
-        };
-
-        Ok(super::scale::LinearOffsetMultiplier::new(
-            offset, multiplier,
-        ))
-    }
-
     macro_rules! impl_decode_raw_for {
         ($ty:ty) => {
             impl DecodeRaw for IntDecoder<$ty> {
@@ -443,31 +607,100 @@ pub mod decoder {
         }
     }
 
-    pub struct RasterDecoder;
-    impl DecodeRaw for RasterDecoder {
-        type Data = usize;
+    /// Decoder for `raster-data`.
+    ///
+    /// # Notes
+    /// + Raster channels are usually generated from the segment's
+    ///   `data.start` and `data.step` and have no data file.
+    ///   If samples are stored they are decoded using the LCD info's encoder.
+    pub enum RasterDecoder {
+        /// Samples are stored using the encoder.
+        Encoded(Arc<dyn Decode + Sync + Send>),
+
+        /// Raster layout can not be decoded.
+        /// Contains a description of the layout.
+        Unsupported(String),
+    }
 
-        fn decode_raw(&self, data: &RawData) -> Result<Vec<Self::Data>, InvalidDataLength> {
-            todo!("RasterDecoder::decode_raw")
+    impl RasterDecoder {
+        /// Create a decoder from the encoder properties of the LCD info.
+        ///
+        /// # Notes
+        /// + Missing or invalid encoder properties result in an
+        ///   [`Unsupported`](RasterDecoder::Unsupported) decoder, so the dataset can still be
+        ///   opened, and only reading the raw data fails.
+        ///
+        /// # Arguments
+        /// + `properties`: The `lcd-info.{index}.encoder` tree.
+        pub fn from_properties(properties: &PropertyTree) -> Self {
+            let Some(encoder_type) = properties.get(TYPE_KEY) else {
+                return Self::Unsupported("raster without encoder".to_string());
+            };
+
+            match int_from_properties(properties) {
+                Ok(decoder) => Self::Encoded(decoder),
+                Err(dataset_properties::error::Property::NotFound(key)) => {
+                    Self::Unsupported(format!("raster encoder missing `{key}`"))
+                }
+                Err(dataset_properties::error::Property::InvalidValue(key)) => Self::Unsupported(
+                    format!("raster encoder `{encoder_type}` with invalid value of `{key}`"),
+                ),
+            }
+        }
+    }
+
+    impl Decode for RasterDecoder {
+        fn decode(&self, data: &RawData) -> Result<Vec<Value>, Error> {
+            match self {
+                Self::Encoded(decoder) => decoder.decode(data),
+                Self::Unsupported(layout) => Err(Error::UnsupportedRasterLayout(layout.clone())),
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub enum Error {
+        /// Data has an invalid number of bytes for the given type.
+        InvalidDataLength,
+
+        /// The raster layout can not be decoded.
+        UnsupportedRasterLayout(String),
+    }
+
+    impl From<InvalidDataLength> for Error {
+        fn from(_value: InvalidDataLength) -> Self {
+            Self::InvalidDataLength
         }
     }
 
-    impl Scale<usize> for RasterDecoder {
-        fn scale(&self, value: usize) -> Value {
-            todo!("RasterDecoder::scale");
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::InvalidDataLength => write!(f, "{InvalidDataLength}"),
+                Self::UnsupportedRasterLayout(layout) => {
+                    write!(f, "unsupported raster layout: {layout}")
+                }
+            }
         }
     }
 
+    impl std::error::Error for Error {}
+
     /// Data has an invalid number of bytes for the given type.
+    #[derive(Debug)]
     pub struct InvalidDataLength;
+
+    impl fmt::Display for InvalidDataLength {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "data has an invalid number of bytes for its type")
+        }
+    }
+
+    impl std::error::Error for InvalidDataLength {}
 }
 
-mod conversion {
-    use super::{
-        super::{lcd_info::scale::Scale, properties::SharedData},
-        Value, dataset_properties, properties, scale,
-    };
-    use std::{fmt, sync::Arc};
+pub mod conversion {
+    use super::{super::lcd_info::scale::Scale, PropertyTree, Value, scale};
 
     #[derive(Clone)]
     pub struct ConversionSet {
@@ -475,66 +708,190 @@ mod conversion {
         base: String,
         default: String,
         conversions: Vec<Conversion>,
+        /// Indices of the conversions to apply, in order, to get from the `base` to the `default` slot.
+        default_path: Vec<usize>,
     }
 
     impl ConversionSet {
-        /// `lcd-info.{index}.conversion-set.conversions.list`
-        pub fn list_key(index: usize) -> String {
-            format!("lcd-info.{index}.conversion-set.conversions.list")
-        }
+        /// `lcd-info.{index}.conversion-set.conversions`
+        pub const CONVERSIONS_KEY: &str = "conversions";
 
         /// `lcd-info.{index}.conversion-set.conversions.default`
-        pub fn default_key(index: usize) -> String {
-            format!("lcd-info.{index}.conversion-set.conversions.default")
-        }
+        pub const DEFAULT_KEY: &str = "conversions.default";
 
         /// `lcd-info.{index}.conversion-set.conversions.base`
-        pub fn base_key(index: usize) -> String {
-            format!("lcd-info.{index}.conversion-set.conversions.base")
-        }
+        pub const BASE_KEY: &str = "conversions.base";
+
+        /// `lcd-info.{index}.conversion-set.conversion`
+        pub const CONVERSION_KEY: &str = "conversion";
     }
 
     impl ConversionSet {
+        /// # Arguments
+        /// + `properties`: The `lcd-info.{index}.conversion-set` tree.
         pub fn from_properties(
-            properties: &properties::SharedData,
-            index: usize,
+            properties: &PropertyTree,
         ) -> Result<Self, super::dataset_properties::error::Property> {
-            let quantities = Self::_quantities(properties, index)?;
-            let base = dataset_properties::extract_value!(properties, Self::base_key(index))?;
-            let default = dataset_properties::extract_value!(properties, Self::default_key(index))?;
+            let quantities = properties
+                .list(Self::CONVERSIONS_KEY)?
+                .into_iter()
+                .map(|quantity| quantity.to_string())
+                .collect::<Vec<_>>();
+            let base = properties.get_str(Self::BASE_KEY)?;
+            let default = properties.get_str(Self::DEFAULT_KEY)?;
+            let conversion_properties = properties.subtree(Self::CONVERSION_KEY);
             let conversions = quantities
                 .iter()
-                .map(|conversion| Conversion::from_properties(properties, index, conversion))
+                .map(|conversion| {
+                    Conversion::from_properties(&conversion_properties.subtree(conversion))
+                })
                 .collect::<Result<Vec<_>, _>>()?;
 
+            let Some(default_path) = Self::_resolve_path(&conversions, base, default) else {
+                return Err(super::dataset_properties::error::Property::InvalidValue(
+                    properties.key(Self::DEFAULT_KEY),
+                ));
+            };
+
             Ok(Self {
                 quantities,
-                base: base.clone(),
-                default: default.clone(),
+                base: base.to_string(),
+                default: default.to_string(),
                 conversions,
+                default_path,
             })
         }
 
-        fn _quantities(
-            properties: &properties::SharedData,
-            index: usize,
-        ) -> Result<Vec<String>, super::dataset_properties::error::Property> {
-            let value = dataset_properties::extract_value!(properties, Self::list_key(index))?;
-            let value = value
-                .split_ascii_whitespace()
-                .map(|value| value.to_string())
-                .collect();
-            Ok(value)
+        /// Resolve the conversions needed to get from the `base` slot to the `target` slot.
+        ///
+        /// # Returns
+        /// Indices of the conversions to apply, in order,
+        /// or `None` if `target` can not be reached from `base`.
+        fn _resolve_path(
+            conversions: &[Conversion],
+            base: &str,
+            target: &str,
+        ) -> Option<Vec<usize>> {
+            let mut path = Vec::new();
+            let mut slot = target;
+            while slot != base {
+                // every conversion may be used at most once, guards against cycles
+                if path.len() >= conversions.len() {
+                    return None;
+                }
+
+                let (idx, conversion) = conversions
+                    .iter()
+                    .enumerate()
+                    .find(|(_, conversion)| conversion.calibration_slot.as_deref() == Some(slot))?;
+
+                path.push(idx);
+                slot = conversion.base_slot.as_deref()?;
+            }
+
+            path.reverse();
+            Some(path)
         }
     }
 
     impl ConversionSet {
-        /// Convert from the `base` to the `default` quantity.
+        pub fn base(&self) -> &String {
+            &self.base
+        }
+
+        pub fn default(&self) -> &String {
+            &self.default
+        }
+
+        /// Names of the defined conversions.
+        pub fn quantities(&self) -> &Vec<String> {
+            &self.quantities
+        }
+
+        /// # Returns
+        /// Calibration slots reachable from the `base` slot, starting with the `base` slot.
+        pub fn available_slots(&self) -> Vec<String> {
+            let slots = self
+                .conversions
+                .iter()
+                .filter_map(|conversion| conversion.calibration_slot.as_ref())
+                .filter(|slot| {
+                    Self::_resolve_path(&self.conversions, &self.base, slot.as_str()).is_some()
+                })
+                .cloned();
+
+            std::iter::once(self.base.clone()).chain(slots).collect()
+        }
+
+        /// # Returns
+        /// Unit of the conversion resulting in the given calibration slot.
+        /// `None` if no defined conversion results in the slot.
+        pub fn unit(&self, slot: impl AsRef<str>) -> Option<&String> {
+            self.conversion(slot)
+                .and_then(|conversion| conversion.unit.as_ref())
+        }
+
+        /// # Returns
+        /// Conversion resulting in the given calibration slot.
+        /// `None` if no defined conversion results in the slot.
+        pub fn conversion(&self, slot: impl AsRef<str>) -> Option<&Conversion> {
+            self._position(slot.as_ref())
+                .map(|idx| &self.conversions[idx])
+        }
+
+        /// # Returns
+        /// Name of the conversion resulting in the given calibration slot,
+        /// as used in the `conversion` tree.
+        /// `None` if no defined conversion results in the slot.
+        pub fn quantity(&self, slot: impl AsRef<str>) -> Option<&String> {
+            self._position(slot.as_ref())
+                .map(|idx| &self.quantities[idx])
+        }
+
+        /// Copy of the set with the scaling of the conversion resulting in `slot` replaced.
+        ///
+        /// # Returns
+        /// `None` if no defined conversion results in the slot.
+        pub fn with_scaling(
+            &self,
+            slot: impl AsRef<str>,
+            scaling: scale::LinearOffsetMultiplier,
+        ) -> Option<Self> {
+            let idx = self._position(slot.as_ref())?;
+            let mut conversions = self.clone();
+            conversions.conversions[idx].scaling = Some(scaling);
+            Some(conversions)
+        }
+
+        fn _position(&self, slot: &str) -> Option<usize> {
+            self.conversions
+                .iter()
+                .position(|conversion| conversion.calibration_slot.as_deref() == Some(slot))
+        }
+
+        /// Convert from the `base` to the `default` slot.
         pub fn convert(&self, data: Vec<Value>) -> Vec<Value> {
+            self._convert_path(data, &self.default_path)
+        }
+
+        /// Convert from the `base` to the given calibration slot.
+        ///
+        /// # Returns
+        /// `None` if `slot` can not be reached from the `base` slot.
+        pub fn convert_to(&self, data: Vec<Value>, slot: impl AsRef<str>) -> Option<Vec<Value>> {
+            let path = Self::_resolve_path(&self.conversions, &self.base, slot.as_ref())?;
+            Some(self._convert_path(data, &path))
+        }
+
+        fn _convert_path(&self, data: Vec<Value>, path: &[usize]) -> Vec<Value> {
+            if path.is_empty() {
+                return data;
+            }
+
             data.into_iter()
                 .map(|mut value| {
-                    for conversion in self.conversions.iter() {
-                        value = conversion.scale(value);
+                    for &idx in path {
+                        value = self.conversions[idx].scale(value);
                     }
                     value
                 })
@@ -547,135 +904,296 @@ mod conversion {
         name: String,
         base_slot: Option<String>,
         calibration_slot: Option<String>,
-        scale: Arc<dyn scale::Scale<Value> + Sync + Send>,
+        unit: Option<String>,
+        /// `None` if the conversion is not defined.
+        scaling: Option<scale::LinearOffsetMultiplier>,
     }
 
     impl Conversion {
         /// `lcd-info.{index}.conversion-set.conversion.{conversion}.name`
-        pub fn name_key(index: usize, conversion: impl fmt::Display) -> String {
-            format!("lcd-info.{index}.conversion-set.conversion.{conversion}.name")
-        }
+        pub const NAME_KEY: &str = "name";
 
         /// `lcd-info.{index}.conversion-set.conversion.{conversion}.defined`
-        pub fn defined_key(index: usize, conversion: impl fmt::Display) -> String {
-            format!("lcd-info.{index}.conversion-set.conversion.{conversion}.defined")
-        }
+        pub const DEFINED_KEY: &str = "defined";
 
         /// `lcd-info.{index}.conversion-set.conversion.{conversion}.base-calibration-slot`
-        pub fn base_slot_key(index: usize, conversion: impl fmt::Display) -> String {
-            format!("lcd-info.{index}.conversion-set.conversion.{conversion}.base-calibration-slot")
-        }
+        pub const BASE_SLOT_KEY: &str = "base-calibration-slot";
 
         /// `lcd-info.{index}.conversion-set.conversion.{conversion}.calibration-slot`
-        pub fn slot_key(index: usize, conversion: impl fmt::Display) -> String {
-            format!("lcd-info.{index}.conversion-set.conversion.{conversion}.calibration-slot")
-        }
-
-        /// `lcd-info.{index}.conversion-set.conversion.{conversion}.scaling.type=linear`
-        pub fn scaling_type_key(index: usize, conversion: impl fmt::Display) -> String {
-            format!("lcd-info.{index}.conversion-set.conversion.{conversion}.scaling.type")
-        }
-
-        /// `lcd-info.{index}.conversion-set.conversion.{conversion}.scaling.style=offsetmultiplier`
-        pub fn scaling_style_key(index: usize, conversion: impl fmt::Display) -> String {
-            format!("lcd-info.{index}.conversion-set.conversion.{conversion}.scaling.style")
-        }
-
-        /// `lcd-info.{index}.conversion-set.conversion.{conversion}.scaling.offset=0.0`
-        pub fn scaling_offset_key(index: usize, conversion: impl fmt::Display) -> String {
-            format!("lcd-info.{index}.conversion-set.conversion.{conversion}.scaling.offset")
-        }
-
-        /// `lcd-info.{index}.conversion-set.conversion.{conversion}.scaling.multiplier=3.5381190902750224E-8`
-        pub fn scaling_multiplier_key(index: usize, conversion: impl fmt::Display) -> String {
-            format!("lcd-info.{index}.conversion-set.conversion.{conversion}.scaling.multiplier")
-        }
+        pub const SLOT_KEY: &str = "calibration-slot";
 
-        /// `lcd-info.{index}.conversion-set.conversion.{conversion}.scaling.unit.unit=m`
-        pub fn scaling_unit_key(index: usize, conversion: impl fmt::Display) -> String {
-            format!("lcd-info.{index}.conversion-set.conversion.{conversion}.scaling.unit.unit")
-        }
+        /// `lcd-info.{index}.conversion-set.conversion.{conversion}.scaling`
+        pub const SCALING_KEY: &str = "scaling";
     }
 
     impl Conversion {
+        /// # Arguments
+        /// + `properties`: The `lcd-info.{index}.conversion-set.conversion.{conversion}` tree.
         pub fn from_properties(
-            properties: &properties::SharedData,
-            index: usize,
-            conversion: impl fmt::Display,
+            properties: &PropertyTree,
         ) -> Result<Self, super::dataset_properties::error::Property> {
-            let defined = dataset_properties::extract_value!(properties, Self::defined_key(index, &conversion), parse bool)?;
-            let name =
-                dataset_properties::extract_value!(properties, Self::name_key(index, &conversion))?;
+            let defined = properties.get_bool(Self::DEFINED_KEY)?;
+            let name = properties.get_str(Self::NAME_KEY)?;
             if !defined {
                 return Ok(Self {
-                    name: name.clone(),
+                    name: name.to_string(),
                     base_slot: None,
                     calibration_slot: None,
-                    scale: Arc::new(scale::Identity),
+                    unit: None,
+                    scaling: None,
                 });
             }
 
-            let base_slot = dataset_properties::extract_value!(
-                properties,
-                Self::base_slot_key(index, &conversion)
-            )?;
-            let calibration_slot =
-                dataset_properties::extract_value!(properties, Self::slot_key(index, &conversion))?;
-
-            let scale_type = dataset_properties::extract_value!(properties, Self::scaling_type_key(index, &conversion), from_str super::scale::Type)?;
-            let scale_style = dataset_properties::extract_value!(properties, Self::scaling_style_key(index, &conversion), from_str super::scale::Style)?;
-            let scale = match (scale_type, scale_style) {
-                (scale::Type::Linear, scale::Style::OffsetMultiplier) => {
-                    Self::_create_linear_offset_multiplier(properties, index, &conversion)?
-                }
-            };
-            let scale = Arc::new(scale);
+            let base_slot = properties.get_str(Self::BASE_SLOT_KEY)?;
+            let calibration_slot = properties.get_str(Self::SLOT_KEY)?;
+            let scaling = properties.subtree(Self::SCALING_KEY);
+            let unit = scaling.get_str(scale::UNIT_KEY)?;
+            let scaling = scale::from_properties(&scaling)?;
 
             Ok(Self {
-                name: name.clone(),
-                base_slot: Some(base_slot.clone()),
-                calibration_slot: Some(calibration_slot.clone()),
-                scale,
+                name: name.to_string(),
+                base_slot: Some(base_slot.to_string()),
+                calibration_slot: Some(calibration_slot.to_string()),
+                unit: Some(unit.to_string()),
+                scaling: Some(scaling),
             })
         }
+    }
 
-        fn _create_linear_offset_multiplier(
-            properties: &properties::SharedData,
-            idx: usize,
-            conversion: impl fmt::Display,
-        ) -> Result<super::scale::LinearOffsetMultiplier, super::dataset_properties::error::Property>
-        {
-            let multiplier_key = Self::scaling_multiplier_key(idx, &conversion);
-            let Some(multiplier) = properties.get(&multiplier_key) else {
-                return Err(super::dataset_properties::error::Property::NotFound(
-                    multiplier_key.clone(),
-                ));
-            };
-            let Ok(multiplier) = multiplier.parse::<f64>() else {
-                return Err(super::dataset_properties::error::Property::InvalidValue(
-                    multiplier_key.clone(),
-                ));
-            };
+    impl Conversion {
+        pub fn name(&self) -> &String {
+            &self.name
+        }
 
-            let offset_key = Self::scaling_offset_key(idx, &conversion);
-            let Some(offset) = properties.get(&offset_key) else {
-                return Err(super::dataset_properties::error::Property::NotFound(
-                    offset_key.clone(),
-                ));
-            };
-            let Ok(offset) = offset.parse::<f64>() else {
-                return Err(super::dataset_properties::error::Property::InvalidValue(
-                    offset_key.clone(),
-                ));
-            };
+        /// Calibration slot the conversion is applied to.
+        /// `None` if the conversion is not defined.
+        pub fn base_slot(&self) -> Option<&String> {
+            self.base_slot.as_ref()
+        }
 
-            Ok(scale::LinearOffsetMultiplier::new(offset, multiplier))
+        /// Calibration slot the conversion results in.
+        /// `None` if the conversion is not defined.
+        pub fn calibration_slot(&self) -> Option<&String> {
+            self.calibration_slot.as_ref()
+        }
+
+        /// Unit of the converted data.
+        /// `None` if the conversion is not defined.
+        pub fn unit(&self) -> Option<&String> {
+            self.unit.as_ref()
+        }
+
+        /// Scaling from the base to the calibration slot.
+        /// `None` if the conversion is not defined.
+        pub fn scaling(&self) -> Option<&scale::LinearOffsetMultiplier> {
+            self.scaling.as_ref()
         }
     }
 
     impl scale::Scale<Value> for Conversion {
         fn scale(&self, value: Value) -> Value {
-            self.scale.scale(value)
+            match &self.scaling {
+                Some(scaling) => scaling.scale(value),
+                None => value,
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{super::properties, *};
+    use std::{fs, path::PathBuf};
+
+    const SHARED_DATA_PATH: &str = "../data/qi_data/qi_data-2_0-xs/shared-data/header.properties";
+    const DISTANCE_MULTIPLIER: Value = 5.740781811552423E-8;
+    const FORCE_MULTIPLIER: Value = 2.8307938999982456;
+    const NOMINAL_MULTIPLIER: Value = 1.2704636760459918;
+
+    fn shared_data() -> properties::SharedData {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(SHARED_DATA_PATH);
+        let mut file = fs::File::open(path).unwrap();
+        let inner = dataset_properties::Properties::new(&mut file).unwrap();
+        properties::SharedData { inner }
+    }
+
+    #[test]
+    fn conversion_set_convert_to_slot() {
+        let properties = shared_data();
+        let conversions = conversion::ConversionSet::from_properties(
+            &properties.lcd_info(1).subtree("conversion-set"),
+        )
+        .unwrap();
+        let volts = vec![-1.0, 0.0, 2.0];
+
+        let result = conversions.convert_to(volts.clone(), "volts").unwrap();
+        assert_eq!(result, volts);
+
+        let result = conversions.convert_to(volts.clone(), "distance").unwrap();
+        let expected = volts
+            .iter()
+            .map(|v| v * DISTANCE_MULTIPLIER)
+            .collect::<Vec<_>>();
+        assert_eq!(result, expected);
+
+        let result = conversions.convert_to(volts.clone(), "force").unwrap();
+        let expected = volts
+            .iter()
+            .map(|v| v * DISTANCE_MULTIPLIER * FORCE_MULTIPLIER)
+            .collect::<Vec<_>>();
+        assert_eq!(result, expected);
+
+        assert!(conversions.convert_to(volts.clone(), "nominal").is_none());
+    }
+
+    #[test]
+    fn conversion_set_convert_default() {
+        let properties = shared_data();
+
+        // base `volts`, default `force`
+        let conversions = conversion::ConversionSet::from_properties(
+            &properties.lcd_info(1).subtree("conversion-set"),
+        )
+        .unwrap();
+        let result = conversions.convert(vec![1.0]);
+        assert_eq!(result, vec![DISTANCE_MULTIPLIER * FORCE_MULTIPLIER]);
+
+        // base and default `calibrated`, `nominal` is not applied
+        let conversions = conversion::ConversionSet::from_properties(
+            &properties.lcd_info(9).subtree("conversion-set"),
+        )
+        .unwrap();
+        let result = conversions.convert(vec![1.0]);
+        assert_eq!(result, vec![1.0]);
+        let result = conversions.convert_to(vec![1.0], "nominal").unwrap();
+        assert_eq!(result, vec![NOMINAL_MULTIPLIER]);
+    }
+
+    #[test]
+    fn lcd_info_with_calibration() {
+        const SENSITIVITY: Value = 6E-8;
+        let properties = shared_data();
+        let lcd_info = LcdInfo::from_properties(&properties.lcd_info(1)).unwrap();
+        let calibrated = lcd_info
+            .with_calibration("distance", SENSITIVITY, 1.0)
+            .unwrap();
+        assert_eq!(
+            calibrated.calibration("distance").unwrap(),
+            &scale::LinearOffsetMultiplier::new(1.0, SENSITIVITY)
+        );
+        assert_eq!(
+            calibrated.convert_to(vec![2.0], "force").unwrap(),
+            vec![(1.0 + 2.0 * SENSITIVITY) * FORCE_MULTIPLIER]
+        );
+        assert_eq!(
+            lcd_info.calibration("distance").unwrap().multiplier(),
+            DISTANCE_MULTIPLIER
+        );
+        assert!(lcd_info.with_calibration("volts", 1.0, 0.0).is_none());
+
+        let values = calibrated
+            .calibration_properties(&properties.lcd_info(1), "distance")
+            .unwrap();
+        let key = "lcd-info.1.conversion-set.conversion.distance.scaling";
+        assert_eq!(
+            values,
+            vec![
+                (
+                    format!("{key}.original-multiplier"),
+                    "5.740781811552423E-8".to_string()
+                ),
+                (format!("{key}.original-offset"), "0.0".to_string()),
+                (format!("{key}.multiplier"), SENSITIVITY.to_string()),
+                (format!("{key}.offset"), "1".to_string()),
+            ]
+        );
+
+        // the original calibration is kept when overriding again
+        let inner = properties.with_values(values);
+        let properties = super::super::properties::SharedData { inner };
+        let values = calibrated
+            .calibration_properties(&properties.lcd_info(1), "distance")
+            .unwrap();
+        assert_eq!(values.len(), 2);
+    }
+
+    #[test]
+    fn raster_decoder_unsupported_layout() {
+        use decoder::Decode;
+
+        // `time` has no encoder
+        let properties = shared_data();
+        let lcd_info = LcdInfo::from_properties(&properties.lcd_info(8)).unwrap();
+        assert!(matches!(lcd_info.data_type(), DataType::Raster));
+
+        let decoder =
+            decoder::RasterDecoder::from_properties(&properties.lcd_info(8).subtree("encoder"));
+        let result = decoder.decode(&[0, 0, 0, 1]);
+        assert!(matches!(
+            result,
+            Err(decoder::Error::UnsupportedRasterLayout(_))
+        ));
+
+        let result = lcd_info.convert_data(&[0, 0, 0, 1]);
+        assert!(matches!(
+            result,
+            Err(error::ConvertData::UnsupportedRasterLayout(_))
+        ));
+    }
+
+    #[test]
+    fn raster_decoder_encoded() {
+        use decoder::Decode;
+
+        let header = "lcd-info.0.encoder.type=signedshort
+lcd-info.0.encoder.scaling.type=linear
+lcd-info.0.encoder.scaling.style=offsetmultiplier
+lcd-info.0.encoder.scaling.offset=1.0
+lcd-info.0.encoder.scaling.multiplier=0.5
+lcd-info.0.encoder.scaling.unit.unit=s
+";
+        let inner = dataset_properties::Properties::new(&mut header.as_bytes()).unwrap();
+        let properties = properties::SharedData { inner };
+
+        let decoder =
+            decoder::RasterDecoder::from_properties(&properties.lcd_info(0).subtree("encoder"));
+        let result = decoder.decode(&[0, 2, 0xff, 0xfe]).unwrap();
+        assert_eq!(result, vec![2.0, 0.0]);
+
+        let result = decoder.decode(&[0, 2, 0]);
+        assert!(matches!(result, Err(decoder::Error::InvalidDataLength)));
+    }
+
+    #[test]
+    fn lcd_info_error_contains_full_key() {
+        let header = "lcd-info.3.type=float-data
+lcd-info.3.unit.unit=V
+lcd-info.3.channel.type=channel
+lcd-info.3.channel.name=vDeflection
+lcd-info.3.channel.fancy-name=Vertical Deflection
+lcd-info.3.conversion-set.conversions.list=distance
+lcd-info.3.conversion-set.conversions.base=volts
+lcd-info.3.conversion-set.conversions.default=distance
+lcd-info.3.conversion-set.conversion.distance.name=Distance
+lcd-info.3.conversion-set.conversion.distance.defined=true
+lcd-info.3.conversion-set.conversion.distance.base-calibration-slot=volts
+lcd-info.3.conversion-set.conversion.distance.calibration-slot=distance
+lcd-info.3.conversion-set.conversion.distance.scaling.type=linear
+lcd-info.3.conversion-set.conversion.distance.scaling.style=offsetmultiplier
+lcd-info.3.conversion-set.conversion.distance.scaling.offset=0.0
+lcd-info.3.conversion-set.conversion.distance.scaling.multiplier=invalid
+lcd-info.3.conversion-set.conversion.distance.scaling.unit.unit=m
+";
+        let inner = dataset_properties::Properties::new(&mut header.as_bytes()).unwrap();
+        let properties = properties::SharedData { inner };
+
+        let result = LcdInfo::from_properties(&properties.lcd_info(3));
+        let Err(dataset_properties::error::Property::InvalidValue(key)) = result else {
+            panic!("expected invalid value");
+        };
+        assert_eq!(
+            key,
+            "lcd-info.3.conversion-set.conversion.distance.scaling.multiplier"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! Dataset reader for JPK file format version 2.0.
 
-use super::{DatasetError, properties as dataset_properties};
+use super::{DatasetError, FileFormat, FormatVersion, preview, properties as dataset_properties};
 use std::{
     io::{self, Read},
     path::{Path, PathBuf},
@@ -17,13 +17,16 @@ pub type LcdInfoIndexType = u8;
 
 const DATASET_PROPERTIES_SHARED_DATA_FILE_PATH: &str = "shared-data/header.properties";
 const DATASET_SEGMENT_CHANNEL_DIR: &str = "channels";
-const SEGMENT_PROPERTIES_FILE: &str = "segment-header.properties";
+pub const SEGMENT_PROPERTIES_FILE: &str = "segment-header.properties";
 
 pub struct DatasetReader<R> {
     archive: zip::ZipArchive<R>,
     dataset_properties: Arc<properties::Dataset>,
     shared_properties: Arc<properties::SharedData>,
     lcd_info: Arc<Vec<lcd_info::LcdInfo>>,
+
+    /// Keys of the properties read from the archive.
+    keys: Arc<dataset_properties::KeyInterner>,
 }
 
 impl<R> DatasetReader<R>
@@ -31,37 +34,21 @@ where
     R: io::Read + io::Seek,
 {
     pub fn new(mut archive: zip::ZipArchive<R>) -> Result<Self, DatasetError> {
-        let dataset_properties = {
-            let path = PathBuf::from(super::DATASET_PROPERTIES_FILE_PATH);
-            let mut properties = archive.by_path(&path).map_err(|error| DatasetError::Zip {
-                path: path.clone(),
-                error,
-            })?;
-
-            let properties =
-                dataset_properties::Properties::new(&mut properties).map_err(|_| {
-                    DatasetError::InvalidFormat {
-                        path: path.clone(),
-                        cause: "invalid properties file".to_string(),
-                    }
-                })?;
-            properties::Dataset { inner: properties }
-        };
+        let dataset_properties = Self::read_dataset_properties(&mut archive)?;
+        match Self::file_format(&dataset_properties)?.supported_version()? {
+            FormatVersion::V2_0 => {}
+        }
 
+        let keys = dataset_properties::KeyInterner::new();
         let shared_properties = {
             let path = DATASET_PROPERTIES_SHARED_DATA_FILE_PATH;
-            let mut properties = archive.by_path(&path).map_err(|error| DatasetError::Zip {
+            let mut properties = archive.by_path(path).map_err(|error| DatasetError::Zip {
                 path: PathBuf::from(path),
                 error,
             })?;
 
-            let properties =
-                dataset_properties::Properties::new(&mut properties).map_err(|_err| {
-                    DatasetError::InvalidFormat {
-                        path: PathBuf::from(path),
-                        cause: "invalid properties file".to_string(),
-                    }
-                })?;
+            let properties = dataset_properties::Properties::new_interned(&mut properties, &keys)
+                .map_err(|err| DatasetError::properties(path, err))?;
             properties::SharedData { inner: properties }
         };
         let lcd_info = Self::_init_lcd_infos(&shared_properties)?;
@@ -71,24 +58,19 @@ where
             dataset_properties: Arc::new(dataset_properties),
             shared_properties: Arc::new(shared_properties),
             lcd_info: Arc::new(lcd_info),
+            keys: Arc::new(keys),
         })
     }
 
     fn _init_lcd_infos(
         properties: &properties::SharedData,
     ) -> Result<Vec<lcd_info::LcdInfo>, DatasetError> {
-        let infos_count =
-            dataset_properties::extract_value!(properties, properties::SharedData::LCD_INFOS_COUNT_KEY, parse usize)
-                .map_err(|err| match err {
-                    dataset_properties::error::Property::NotFound(key) => DatasetError::InvalidFormat {
-                        path: PathBuf::from(DATASET_PROPERTIES_SHARED_DATA_FILE_PATH),
-                        cause: format!(" property `{key}` not found",),
-                    },
-                    dataset_properties::error::Property::InvalidValue(key) => DatasetError::InvalidFormat {
-                        path: PathBuf::from(DATASET_PROPERTIES_SHARED_DATA_FILE_PATH),
-                        cause: format!("invalid value for property `{key}`",),
-                    },
-                })?;
+        let infos_count = properties
+            .lcd_infos_count()
+            .map_err(|error| DatasetError::Property {
+                path: PathBuf::from(DATASET_PROPERTIES_SHARED_DATA_FILE_PATH),
+                error,
+            })?;
 
         (0..infos_count)
             .map(|idx| Self::_init_lcd_info(properties, idx))
@@ -99,20 +81,35 @@ where
         properties: &properties::SharedData,
         index: usize,
     ) -> Result<lcd_info::LcdInfo, DatasetError> {
-        lcd_info::LcdInfo::from_properties(properties, index).map_err(|err| match err {
-            dataset_properties::error::Property::NotFound(key) => DatasetError::InvalidFormat {
-                path: PathBuf::from(DATASET_PROPERTIES_SHARED_DATA_FILE_PATH),
-                cause: format!("property `{key}` not found"),
-            },
-            dataset_properties::error::Property::InvalidValue(key) => DatasetError::InvalidFormat {
+        lcd_info::LcdInfo::from_properties(&properties.lcd_info(index)).map_err(|error| {
+            DatasetError::Property {
                 path: PathBuf::from(DATASET_PROPERTIES_SHARED_DATA_FILE_PATH),
-                cause: format!("invalid property value of `{key}`"),
-            },
+                error,
+            }
         })
     }
 }
 
 impl<R> DatasetReader<R> {
+    /// Create a reader over `archive` that shares this reader's parsed dataset properties.
+    /// Used to read an archive from multiple threads without re-parsing the shared data.
+    ///
+    /// # Safety
+    /// It is left to the user to ensure that `archive` is the same archive as the one being read.
+    pub unsafe fn with_archive<S>(&self, archive: zip::ZipArchive<S>) -> DatasetReader<S> {
+        DatasetReader {
+            archive,
+            dataset_properties: self.dataset_properties.clone(),
+            shared_properties: self.shared_properties.clone(),
+            lcd_info: self.lcd_info.clone(),
+            keys: self.keys.clone(),
+        }
+    }
+
+    pub fn archive(&self) -> &zip::ZipArchive<R> {
+        &self.archive
+    }
+
     pub fn dataset_properties(&self) -> &Arc<properties::Dataset> {
         &self.dataset_properties
     }
@@ -126,20 +123,97 @@ impl<R> DatasetReader<R>
 where
     R: io::Read + io::Seek,
 {
+    /// Read the properties file at `path`.
+    pub fn properties(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<dataset_properties::Properties, error::Properties> {
+        let mut file = self.archive.by_path(path)?;
+        let properties = dataset_properties::Properties::new_interned(&mut file, &self.keys)?;
+        Ok(properties)
+    }
+
     pub fn segment_properties(
         &mut self,
         segment_path: impl AsRef<Path>,
     ) -> Result<properties::segment::Properties, error::Properties> {
         let properties_path = segment_path.as_ref().join(SEGMENT_PROPERTIES_FILE);
-        let mut file = self.archive.by_path(properties_path)?;
-        let properties = dataset_properties::Properties::new(&mut file)?;
+        let properties = self.properties(properties_path)?;
         Ok(properties::segment::Properties { inner: properties })
     }
 
+    /// Preview image of the dataset.
+    /// A `thumbnail` image is preferred, falling back to any other preview image.
+    ///
+    /// # Returns
+    /// `None` if the archive does not contain a preview image.
+    pub fn thumbnail(&mut self) -> Result<Option<preview::Preview>, DatasetError> {
+        preview::read_thumbnail(&mut self.archive)
+    }
+
+    /// All preview images of the dataset.
+    pub fn previews(&mut self) -> Result<Vec<preview::Preview>, DatasetError> {
+        preview::read_previews(&mut self.archive)
+    }
+
     pub fn lcd_info_for_index(&self, index: LcdInfoIndexType) -> Option<&lcd_info::LcdInfo> {
         self.lcd_info.get(index as usize)
     }
 
+    /// Replace the scaling of a conversion of a channel for all subsequent reads.
+    /// The original and new values are recorded in the shared properties,
+    /// see [`lcd_info::LcdInfo::calibration_properties`].
+    ///
+    /// # Arguments
+    /// + `channel`: Name of the channel.
+    /// + `slot`: Calibration slot the conversion results in,
+    ///   e.g. `distance` to set the sensitivity or `force` to set the spring constant.
+    pub fn override_calibration(
+        &mut self,
+        channel: impl AsRef<str>,
+        slot: impl AsRef<str>,
+        multiplier: DataValue,
+        offset: DataValue,
+    ) -> Result<(), error::OverrideCalibration> {
+        let channel = channel.as_ref();
+        let slot = slot.as_ref();
+        let indices = self
+            .lcd_info
+            .iter()
+            .enumerate()
+            .filter(|(_, info)| info.channel_info().name == channel)
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        if indices.is_empty() {
+            return Err(error::OverrideCalibration::ChannelNotFound(
+                channel.to_string(),
+            ));
+        }
+
+        let mut infos = Vec::with_capacity(indices.len());
+        let mut values = Vec::new();
+        for idx in indices {
+            let info = self.lcd_info[idx]
+                .with_calibration(slot, multiplier, offset)
+                .ok_or_else(|| {
+                    error::OverrideCalibration::InvalidCalibrationSlot(slot.to_string())
+                })?;
+            let properties = info
+                .calibration_properties(&self.shared_properties.lcd_info(idx), slot)
+                .expect("calibration slot exists");
+            values.extend(properties);
+            infos.push((idx, info));
+        }
+
+        let lcd_info = Arc::make_mut(&mut self.lcd_info);
+        for (idx, info) in infos {
+            lcd_info[idx] = info;
+        }
+        let shared_properties = Arc::make_mut(&mut self.shared_properties);
+        shared_properties.inner = shared_properties.with_values(values);
+        Ok(())
+    }
+
     pub fn channel_info(
         &mut self,
         segment_path: impl AsRef<Path>,
@@ -151,24 +225,93 @@ where
         Ok(info)
     }
 
+    /// Read the data of a channel.
+    ///
+    /// # Arguments
+    /// + `calibration_slot`: Calibration slot to convert the data to.
+    ///   If `None` the default slot is used.
     pub fn channel_data(
         &mut self,
         segment_path: impl AsRef<Path>,
         channel: impl AsRef<str>,
-    ) -> Result<Vec<DataValue>, error::ChannelData> {
+        calibration_slot: Option<&str>,
+    ) -> Result<ChannelData, error::ChannelData> {
         let channel_info = self.channel_info(&segment_path, channel)?;
-        let data_file_path = segment_path.as_ref().join(channel_info.file_path());
-        let mut data_file = self.archive.by_path(&data_file_path)?;
-        let mut raw_data = Vec::with_capacity(data_file.size() as usize);
-        data_file
-            .read_to_end(&mut raw_data)
-            .map_err(|err| zip::result::ZipError::Io(err))?;
-        drop(data_file);
-        let lcd_info = self
-            .lcd_info_for_index(channel_info.lcd_info_index())
-            .expect("lcd info not found");
-        let data = lcd_info.convert_data(&raw_data)?;
-        Ok(data)
+        self.read_channel_data(segment_path, &channel_info, calibration_slot)
+    }
+
+    /// Read the data of a channel whose info is already known.
+    /// Avoids re-reading the segment properties when loading multiple channels of a segment.
+    pub fn read_channel_data(
+        &mut self,
+        segment_path: impl AsRef<Path>,
+        channel_info: &properties::channel::Info,
+        calibration_slot: Option<&str>,
+    ) -> Result<ChannelData, error::ChannelData> {
+        use properties::channel::Source;
+
+        let raw_data = match channel_info.source() {
+            Source::File { path, .. } => {
+                let data_file_path = segment_path.as_ref().join(path);
+                let mut data_file = self.archive.by_path(&data_file_path)?;
+                let mut raw_data = Vec::with_capacity(data_file.size() as usize);
+                data_file
+                    .read_to_end(&mut raw_data)
+                    .map_err(zip::result::ZipError::Io)?;
+                Some(raw_data)
+            }
+            Source::Generated(_) => None,
+        };
+
+        let lcd_info_index = channel_info.lcd_info_index();
+        let Some(lcd_info) = self.lcd_info_for_index(lcd_info_index) else {
+            return Err(error::ChannelData::LcdInfoNotFound(lcd_info_index));
+        };
+        let slot = calibration_slot.unwrap_or(lcd_info.conversion_set().default());
+        let Some(unit) = lcd_info.unit(Some(slot)) else {
+            return Err(error::ChannelData::InvalidCalibrationSlot(slot.to_string()));
+        };
+
+        let data = match (channel_info.source(), raw_data) {
+            (Source::File { .. }, Some(raw_data)) => lcd_info.convert_data_to(&raw_data, slot)?,
+            (Source::Generated(generator), _) => {
+                let data = generator.generate(channel_info.num_points() as usize);
+                lcd_info
+                    .convert_to(data, slot)
+                    .ok_or_else(|| error::ChannelData::InvalidCalibrationSlot(slot.to_string()))?
+            }
+            (Source::File { .. }, None) => unreachable!("file backed channel data is read"),
+        };
+
+        Ok(ChannelData {
+            data,
+            unit: unit.clone(),
+        })
+    }
+}
+
+/// Data of a channel converted to a calibration slot.
+#[derive(Debug, Clone)]
+pub struct ChannelData {
+    data: Vec<DataValue>,
+    unit: String,
+}
+
+impl ChannelData {
+    pub fn data(&self) -> &Vec<DataValue> {
+        &self.data
+    }
+
+    pub fn unit(&self) -> &String {
+        &self.unit
+    }
+
+    pub fn into_data(self) -> Vec<DataValue> {
+        self.data
+    }
+
+    pub fn into_parts(self) -> (Vec<DataValue>, String) {
+        (self.data, self.unit)
     }
 }
 
@@ -176,35 +319,35 @@ impl<R> DatasetReader<R>
 where
     R: io::Read + io::Seek,
 {
-    fn format_version(archive: &mut zip::ZipArchive<R>) -> Result<String, DatasetError> {
-        let properties = {
-            let mut properties = archive
-                .by_path(super::DATASET_PROPERTIES_FILE_PATH)
-                .map_err(|error| DatasetError::Zip {
-                    path: PathBuf::from(super::DATASET_PROPERTIES_FILE_PATH),
-                    error,
-                })?;
-
-            dataset_properties::Properties::new(&mut properties).map_err(|_err| {
-                DatasetError::InvalidFormat {
-                    path: PathBuf::from(super::DATASET_PROPERTIES_FILE_PATH),
-                    cause: "invalid format".to_string(),
-                }
-            })?
-        };
+    /// Read the dataset properties of an archive without initializing a reader.
+    pub fn read_dataset_properties(
+        archive: &mut zip::ZipArchive<R>,
+    ) -> Result<properties::Dataset, DatasetError> {
+        let path = PathBuf::from(super::DATASET_PROPERTIES_FILE_PATH);
+        let mut properties = archive.by_path(&path).map_err(|error| DatasetError::Zip {
+            path: path.clone(),
+            error,
+        })?;
+
+        let properties = dataset_properties::Properties::new(&mut properties)
+            .map_err(|err| DatasetError::properties(&path, err))?;
+        Ok(properties::Dataset { inner: properties })
+    }
 
-        let Some(format_version) = properties.get(properties::Dataset::FILE_FORMAT_VERSION_KEY)
-        else {
-            return Err(DatasetError::InvalidFormat {
-                path: PathBuf::from(super::DATASET_PROPERTIES_FILE_PATH),
-                cause: format!(
-                    "property `{}` not found",
-                    properties::Dataset::FILE_FORMAT_VERSION_KEY
-                ),
-            });
-        };
+    /// Get the JPK file format version of the archive.
+    pub fn format_version(archive: &mut zip::ZipArchive<R>) -> Result<String, DatasetError> {
+        let properties = Self::read_dataset_properties(archive)?;
+        Ok(Self::file_format(&properties)?.version().clone())
+    }
+}
 
-        Ok(format_version.clone())
+impl<R> DatasetReader<R> {
+    /// File format version and features from the dataset properties.
+    pub fn file_format(properties: &properties::Dataset) -> Result<FileFormat, DatasetError> {
+        FileFormat::from_properties(&properties.tree()).map_err(|error| DatasetError::Property {
+            path: PathBuf::from(super::DATASET_PROPERTIES_FILE_PATH),
+            error,
+        })
     }
 }
 
@@ -216,9 +359,15 @@ pub mod utils {
     pub const SEGMENT_DIR: &str = "segments";
     pub const DATASET_PROPERTIES_FILE: &str = "header.properties";
     pub const INDEX_PROPERTIES_FILE: &str = "header.properties";
+    pub const SHARED_DATA_DIR: &str = "shared-data";
     pub const SHARED_DATA_PROPERTIES_FILE: &str = "header.properties";
     pub const PROPERTIES_KEY_SEGMENT_CHANNELS_LIST: &str = "channels.list";
 
+    pub fn shared_data_properties_path() -> PathBuf {
+        let path = format!("{SHARED_DATA_DIR}/{SHARED_DATA_PROPERTIES_FILE}");
+        PathBuf::from(path)
+    }
+
     pub fn segment_path(segment: SegmentType) -> PathBuf {
         let path = format!("{SEGMENT_DIR}/{segment}/");
         PathBuf::from(path)
@@ -241,18 +390,46 @@ pub mod utils {
 }
 
 pub mod error {
-    use super::{dataset_properties, lcd_info};
+    use super::{LcdInfoIndexType, dataset_properties, lcd_info, properties};
+    use std::fmt;
 
     #[derive(Debug, derive_more::From)]
     pub enum Properties {
         #[from]
         Zip(zip::result::ZipError),
-        InvalidFormat,
+
+        /// The file could not be parsed as properties.
+        InvalidFormat(dataset_properties::error::Parse),
+    }
+
+    impl From<dataset_properties::error::Parse> for Properties {
+        fn from(value: dataset_properties::error::Parse) -> Self {
+            match value {
+                dataset_properties::error::Parse::Io(err) => {
+                    Self::Zip(zip::result::ZipError::Io(err))
+                }
+                err @ dataset_properties::error::Parse::InvalidUnicodeEscape { .. } => {
+                    Self::InvalidFormat(err)
+                }
+            }
+        }
     }
 
-    impl From<dataset_properties::error::InvalidFormat> for Properties {
-        fn from(_value: dataset_properties::error::InvalidFormat) -> Self {
-            Self::InvalidFormat
+    impl fmt::Display for Properties {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Zip(_) => write!(f, "could not read properties file"),
+                Self::InvalidFormat(_) => write!(f, "invalid properties file"),
+            }
+        }
+    }
+
+    impl std::error::Error for Properties {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::Zip(err) => Some(err),
+                Self::InvalidFormat(err) => Some(err),
+            }
         }
     }
 
@@ -264,8 +441,71 @@ pub mod error {
         SegmentProperties(Properties),
         #[from]
         Property(dataset_properties::error::Property),
+
+        /// The channel is generated, but its generator is not supported.
+        #[from(ignore)]
+        UnsupportedGenerator { channel: String, keys: Vec<String> },
     }
 
+    impl From<properties::channel::error::Info> for ChannelInfo {
+        fn from(value: properties::channel::error::Info) -> Self {
+            match value {
+                properties::channel::error::Info::Property(err) => Self::Property(err),
+                properties::channel::error::Info::UnsupportedGenerator { channel, keys } => {
+                    Self::UnsupportedGenerator { channel, keys }
+                }
+            }
+        }
+    }
+
+    impl fmt::Display for ChannelInfo {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Zip(_) => write!(f, "could not read archive"),
+                Self::SegmentProperties(_) => write!(f, "could not read segment properties"),
+                Self::Property(err) => write!(f, "{err}"),
+                Self::UnsupportedGenerator { channel, keys } => write!(
+                    f,
+                    "unsupported generator for channel `{channel}` with keys {}",
+                    keys.join(", ")
+                ),
+            }
+        }
+    }
+
+    impl std::error::Error for ChannelInfo {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::Zip(err) => Some(err),
+                Self::SegmentProperties(err) => Some(err),
+                Self::Property(err) => err.source(),
+                Self::UnsupportedGenerator { .. } => None,
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub enum OverrideCalibration {
+        /// No lcd info is defined for the channel.
+        ChannelNotFound(String),
+
+        /// No conversion of the channel results in the calibration slot.
+        InvalidCalibrationSlot(String),
+    }
+
+    impl fmt::Display for OverrideCalibration {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::ChannelNotFound(channel) => write!(f, "channel `{channel}` not found"),
+                Self::InvalidCalibrationSlot(slot) => {
+                    write!(f, "calibration slot `{slot}` does not exist")
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for OverrideCalibration {}
+
     #[derive(derive_more::From, Debug)]
     pub enum ChannelData {
         #[from]
@@ -275,6 +515,18 @@ pub mod error {
         #[from]
         Property(dataset_properties::error::Property),
         InvalidDataLength,
+        UnsupportedRasterLayout(String),
+        InvalidCalibrationSlot(String),
+
+        /// The channel is generated, but its generator is not supported.
+        #[from(ignore)]
+        UnsupportedGenerator {
+            channel: String,
+            keys: Vec<String>,
+        },
+
+        /// The channel refers to an lcd info that is not defined in the shared data.
+        LcdInfoNotFound(LcdInfoIndexType),
     }
 
     impl From<ChannelInfo> for ChannelData {
@@ -283,6 +535,23 @@ pub mod error {
                 ChannelInfo::Zip(err) => Self::Zip(err),
                 ChannelInfo::Property(err) => Self::Property(err),
                 ChannelInfo::SegmentProperties(err) => Self::SegmentProperties(err),
+                ChannelInfo::UnsupportedGenerator { channel, keys } => {
+                    Self::UnsupportedGenerator { channel, keys }
+                }
+            }
+        }
+    }
+
+    impl From<lcd_info::error::ConvertData> for ChannelData {
+        fn from(value: lcd_info::error::ConvertData) -> Self {
+            match value {
+                lcd_info::error::ConvertData::InvalidDataLength => Self::InvalidDataLength,
+                lcd_info::error::ConvertData::UnsupportedRasterLayout(layout) => {
+                    Self::UnsupportedRasterLayout(layout)
+                }
+                lcd_info::error::ConvertData::InvalidCalibrationSlot(slot) => {
+                    Self::InvalidCalibrationSlot(slot)
+                }
             }
         }
     }
@@ -292,4 +561,42 @@ pub mod error {
             Self::InvalidDataLength
         }
     }
+
+    impl fmt::Display for ChannelData {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Zip(_) => write!(f, "could not read archive"),
+                Self::SegmentProperties(_) => write!(f, "could not read segment properties"),
+                Self::Property(err) => write!(f, "{err}"),
+                Self::InvalidDataLength => write!(f, "{}", lcd_info::decoder::InvalidDataLength),
+                Self::UnsupportedRasterLayout(layout) => {
+                    write!(f, "unsupported raster layout: {layout}")
+                }
+                Self::InvalidCalibrationSlot(slot) => {
+                    write!(f, "calibration slot `{slot}` does not exist")
+                }
+                Self::UnsupportedGenerator { channel, keys } => write!(
+                    f,
+                    "unsupported generator for channel `{channel}` with keys {}",
+                    keys.join(", ")
+                ),
+                Self::LcdInfoNotFound(index) => write!(f, "lcd info {index} not found"),
+            }
+        }
+    }
+
+    impl std::error::Error for ChannelData {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::Zip(err) => Some(err),
+                Self::SegmentProperties(err) => Some(err),
+                Self::Property(err) => err.source(),
+                Self::InvalidDataLength
+                | Self::UnsupportedRasterLayout(_)
+                | Self::InvalidCalibrationSlot(_)
+                | Self::UnsupportedGenerator { .. }
+                | Self::LcdInfoNotFound(_) => None,
+            }
+        }
+    }
 }
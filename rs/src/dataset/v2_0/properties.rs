@@ -11,55 +11,39 @@ impl Dataset {
     pub const DATA_FILE_KEY: &str = "jpk-data-file";
     pub const FILE_FORMAT_VERSION_KEY: &str = "file-format-version";
 
-    pub fn data_file(&self) -> Option<&String> {
+    pub fn data_file(&self) -> Option<&str> {
         self.get(Self::DATA_FILE_KEY)
     }
 
-    pub fn file_format_version(&self) -> Option<&String> {
+    pub fn file_format_version(&self) -> Option<&str> {
         self.get(Self::FILE_FORMAT_VERSION_KEY)
     }
 
-    pub fn dataset_type(&self) -> Option<&String> {
+    pub fn dataset_type(&self) -> Option<&str> {
         self.get(Self::DATASET_TYPE_KEY)
     }
 }
 
-#[derive(derive_more::Deref)]
+#[derive(derive_more::Deref, Clone)]
 pub struct SharedData {
     pub(super) inner: dataset_properties::Properties,
 }
 
 impl SharedData {
-    pub const LCD_INFOS_COUNT_KEY: &str = "lcd-infos.count";
+    /// `lcd-infos`
+    pub const LCD_INFOS_KEY: &str = "lcd-infos";
 
-    /// `lcd-info.{index}.encoder.type`
-    pub fn lcd_info_encoder_type_key(index: usize) -> String {
-        format!("lcd-info.{index}.encoder.type")
-    }
+    /// `lcd-info`
+    pub const LCD_INFO_KEY: &str = "lcd-info";
 
-    /// `lcd-info.{index}.encoder.scaling.unit.unit``
-    pub fn lcd_info_encoder_unit_key(index: usize) -> String {
-        format!("lcd-info.{index}.encoder.scaling.unit.unit")
+    /// Number of LCD infos, from `lcd-infos.count`.
+    pub fn lcd_infos_count(&self) -> Result<usize, dataset_properties::error::Property> {
+        self.tree().count(Self::LCD_INFOS_KEY)
     }
 
-    /// `lcd-info.{index}.encoder.scaling.type`
-    pub fn lcd_info_encoder_scaling_type_key(index: usize) -> String {
-        format!("lcd-info.{index}.encoder.scaling.type")
-    }
-
-    /// `lcd-info.{index}.encoder.scaling.style`
-    pub fn lcd_info_encoder_scaling_style_key(index: usize) -> String {
-        format!("lcd-info.{index}.encoder.scaling.style")
-    }
-
-    /// `lcd-info.{index}.encoder.scaling.offset`
-    pub fn lcd_info_encoder_scaling_offset_key(index: usize) -> String {
-        format!("lcd-info.{index}.encoder.scaling.offset")
-    }
-
-    /// `lcd-info.{index}.encoder.scaling.multiplier`
-    pub fn lcd_info_encoder_scaling_multiplier_key(index: usize) -> String {
-        format!("lcd-info.{index}.encoder.scaling.multiplier")
+    /// `lcd-info.{index}` tree.
+    pub fn lcd_info(&self, index: usize) -> dataset_properties::PropertyTree<'_> {
+        self.subtree(format!("{}.{index}", Self::LCD_INFO_KEY))
     }
 }
 
@@ -77,14 +61,18 @@ pub mod index {
     }
 
     impl IndexData {
-        const SEGMENT_COUNT_KEY: &str = "quantitative-imaging-series.force-segments.count";
+        /// `{prefix}.force-segments.count`
+        const SEGMENT_COUNT_KEY: &str = "force-segments.count";
     }
 
     impl IndexData {
+        /// # Arguments
+        /// + `properties`: The `{prefix}` tree of the index properties,
+        ///   where the prefix depends on the kind of map.
         pub fn from_properties(
-            properties: &dataset_properties::Properties,
+            properties: &dataset_properties::PropertyTree,
         ) -> Result<Self, dataset_properties::error::Property> {
-            let segment_count = dataset_properties::extract_value!(properties, Self::SEGMENT_COUNT_KEY, parse SegmentType)?;
+            let segment_count = properties.parse::<SegmentType>(Self::SEGMENT_COUNT_KEY)?;
 
             Ok(Self { segment_count })
         }
@@ -109,6 +97,10 @@ pub mod segment {
     impl Properties {
         const CHANNELS_LIST_KEY: &str = "channels.list";
 
+        /// Number of data points of the segment.
+        /// Used for channels that do not define their own.
+        pub const NUM_POINTS_KEY: &str = "force-segment-header.num-points";
+
         /// `channel.{channel}.data.file.name`
         pub fn channel_data_file_name_key(channel: impl fmt::Display) -> String {
             format!("channel.{channel}.data.file.name")
@@ -119,6 +111,21 @@ pub mod segment {
             format!("channel.{channel}.data.file.format")
         }
 
+        /// `channel.{channel}.data.start`
+        pub fn channel_data_start_key(channel: impl fmt::Display) -> String {
+            format!("channel.{channel}.data.start")
+        }
+
+        /// `channel.{channel}.data.step`
+        pub fn channel_data_step_key(channel: impl fmt::Display) -> String {
+            format!("channel.{channel}.data.step")
+        }
+
+        /// `channel.{channel}.data`
+        pub fn channel_data_key(channel: impl fmt::Display) -> String {
+            format!("channel.{channel}.data")
+        }
+
         /// `channel.{channel}.data.num-points`
         pub fn channel_data_num_points_key(channel: impl fmt::Display) -> String {
             format!("channel.{channel}.data.num-points")
@@ -140,41 +147,46 @@ pub mod segment {
         pub fn channel_info(
             &self,
             channel: impl fmt::Display,
-        ) -> Result<channel::Info, dataset_properties::error::Property> {
+        ) -> Result<channel::Info, channel::error::Info> {
             channel::Info::from(self, channel)
         }
     }
 }
 
 pub mod channel {
-    use super::{super::LcdInfoIndexType, dataset_properties, segment};
+    use super::{
+        super::{DataValue, LcdInfoIndexType},
+        dataset_properties, segment,
+    };
     use std::{fmt, path::PathBuf};
 
     #[derive(Debug)]
     pub struct Info {
-        file_path: PathBuf,
-        file_format: DataFileFormat,
+        source: Source,
         num_points: u32,
         lcd_info_index: LcdInfoIndexType,
     }
 
     impl Info {
+        /// # Notes
+        /// + Number of points is taken from the channel's `data.num-points` if present,
+        ///   otherwise from the segment's.
         #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
         pub fn from(
             properties: &segment::Properties,
             channel: impl fmt::Display,
-        ) -> Result<Self, dataset_properties::error::Property> {
-            let file_path = dataset_properties::extract_value!(
-                properties,
-                segment::Properties::channel_data_file_name_key(&channel)
-            )?;
-            let file_format = dataset_properties::extract_value!(properties,segment::Properties::channel_data_file_format_key(&channel), from_str DataFileFormat )?;
-            let num_points = dataset_properties::extract_value!(properties, segment::Properties::channel_data_num_points_key(&channel), parse u32)?;
+        ) -> Result<Self, error::Info> {
+            let source = Source::from_properties(properties, &channel)?;
+            let num_points_key = segment::Properties::channel_data_num_points_key(&channel);
+            let num_points = if properties.get(&num_points_key).is_some() {
+                dataset_properties::extract_value!(properties, &num_points_key, parse u32)?
+            } else {
+                dataset_properties::extract_value!(properties, segment::Properties::NUM_POINTS_KEY, parse u32)?
+            };
             let lcd_info_index = dataset_properties::extract_value!(properties, segment::Properties::channel_lcd_info_index_key(&channel), parse u8)?;
 
             let data = Self {
-                file_path: PathBuf::from(file_path),
-                file_format,
+                source,
                 num_points,
                 lcd_info_index,
             };
@@ -186,13 +198,19 @@ pub mod channel {
     }
 
     impl Info {
-        pub fn file_path(&self) -> &PathBuf {
-            &self.file_path
+        pub fn source(&self) -> &Source {
+            &self.source
         }
 
-        pub fn file_format(&self) -> DataFileFormat {
-            self.file_format
+        /// # Returns
+        /// Path to the data file relative to the segment, if the channel is file backed.
+        pub fn file_path(&self) -> Option<&PathBuf> {
+            match &self.source {
+                Source::File { path, .. } => Some(path),
+                Source::Generated(_) => None,
+            }
         }
+
         pub fn num_points(&self) -> u32 {
             self.num_points
         }
@@ -201,8 +219,49 @@ pub mod channel {
         }
     }
 
+    /// Where the data of a channel comes from.
+    #[derive(Debug, Clone)]
+    pub enum Source {
+        /// Data is stored in a file.
+        File {
+            path: PathBuf,
+            format: DataFileFormat,
+        },
+
+        /// Data is computed from properties of the segment.
+        Generated(Generator),
+    }
+
+    impl Source {
+        /// # Notes
+        /// + If the channel has a data file it is assumed to be file backed,
+        ///   otherwise it is assumed to be generated.
+        ///
+        /// # Errors
+        /// + [`error::Info::Property`]: If the channel defines neither a data file nor a generator.
+        /// + [`error::Info::UnsupportedGenerator`]: If the generator of the channel is not supported.
+        pub fn from_properties(
+            properties: &segment::Properties,
+            channel: impl fmt::Display,
+        ) -> Result<Self, error::Info> {
+            let file_name_key = segment::Properties::channel_data_file_name_key(&channel);
+            if let Some(path) = properties.get(&file_name_key) {
+                let format = dataset_properties::extract_value!(properties, segment::Properties::channel_data_file_format_key(&channel), from_str DataFileFormat)?;
+                return Ok(Self::File {
+                    path: PathBuf::from(path),
+                    format,
+                });
+            }
+
+            match Generator::from_properties(properties, &channel)? {
+                Some(generator) => Ok(Self::Generated(generator)),
+                None => Err(dataset_properties::error::Property::NotFound(file_name_key).into()),
+            }
+        }
+    }
+
     #[derive(Clone, Copy, Debug)]
-    enum DataFileFormat {
+    pub enum DataFileFormat {
         Raw,
     }
 
@@ -214,4 +273,176 @@ pub mod channel {
             }
         }
     }
+
+    /// Generator for computed channels.
+    #[derive(Clone, Copy, Debug)]
+    pub enum Generator {
+        /// Evenly spaced values.
+        /// e.g. `time`.
+        Raster { start: DataValue, step: DataValue },
+    }
+
+    impl Generator {
+        /// `channel.{channel}.data.*` keys that do not describe the generator.
+        const NON_GENERATOR_KEYS: [&str; 1] = ["num-points"];
+
+        /// `channel.{channel}.data.*` keys of a raster generator.
+        const RASTER_KEYS: [&str; 2] = ["start", "step"];
+
+        /// The generator type is determined by the keys under `channel.{channel}.data`.
+        ///
+        /// # Returns
+        /// `None` if the channel defines no generator.
+        ///
+        /// # Errors
+        /// + [`error::Info::UnsupportedGenerator`]: If the generator keys do not match
+        ///   a supported generator.
+        pub fn from_properties(
+            properties: &segment::Properties,
+            channel: impl fmt::Display,
+        ) -> Result<Option<Self>, error::Info> {
+            let data = properties.subtree(segment::Properties::channel_data_key(&channel));
+            let mut keys = data.children();
+            keys.retain(|key| !Self::NON_GENERATOR_KEYS.contains(key));
+            if keys.is_empty() {
+                return Ok(None);
+            }
+
+            if keys != Self::RASTER_KEYS {
+                return Err(error::Info::UnsupportedGenerator {
+                    channel: channel.to_string(),
+                    keys: keys.into_iter().map(|key| key.to_string()).collect(),
+                });
+            }
+
+            let start = dataset_properties::extract_value!(properties, segment::Properties::channel_data_start_key(&channel), parse DataValue)?;
+            let step = dataset_properties::extract_value!(properties, segment::Properties::channel_data_step_key(&channel), parse DataValue)?;
+            Ok(Some(Self::Raster { start, step }))
+        }
+
+        /// Generate `num_points` values.
+        pub fn generate(&self, num_points: usize) -> Vec<DataValue> {
+            match self {
+                Self::Raster { start, step } => (0..num_points)
+                    .map(|idx| start + step * idx as DataValue)
+                    .collect(),
+            }
+        }
+    }
+
+    pub mod error {
+        use super::dataset_properties;
+        use std::fmt;
+
+        #[derive(Debug, derive_more::From)]
+        pub enum Info {
+            Property(dataset_properties::error::Property),
+
+            /// The channel is generated, but its generator is not supported.
+            #[from(ignore)]
+            UnsupportedGenerator {
+                channel: String,
+
+                /// `channel.{channel}.data.*` keys describing the generator.
+                keys: Vec<String>,
+            },
+        }
+
+        impl fmt::Display for Info {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    Self::Property(err) => write!(f, "{err}"),
+                    Self::UnsupportedGenerator { channel, keys } => write!(
+                        f,
+                        "unsupported generator for channel `{channel}` with keys {}",
+                        keys.join(", ")
+                    ),
+                }
+            }
+        }
+
+        impl std::error::Error for Info {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                match self {
+                    Self::Property(err) => err.source(),
+                    Self::UnsupportedGenerator { .. } => None,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn segment_properties(input: &str) -> segment::Properties {
+        segment::Properties {
+            inner: dataset_properties::Properties::parse(input).unwrap(),
+        }
+    }
+
+    #[test]
+    fn channel_info_raster_generator() {
+        let properties = segment_properties(
+            "force-segment-header.num-points=4\n\
+            channel.time.lcd-info.*=8\n\
+            channel.time.data.start=1.0\n\
+            channel.time.data.step=0.5\n",
+        );
+        let info = properties.channel_info("time").unwrap();
+        assert_eq!(info.num_points(), 4);
+        let channel::Source::Generated(generator) = info.source() else {
+            panic!("expected generated channel");
+        };
+        assert_eq!(
+            generator.generate(info.num_points() as usize),
+            vec![1.0, 1.5, 2.0, 2.5]
+        );
+    }
+
+    #[test]
+    fn channel_info_num_points() {
+        let properties = segment_properties(
+            "force-segment-header.num-points=4\n\
+            channel.height.lcd-info.*=0\n\
+            channel.height.data.file.name=channels/height.dat\n\
+            channel.height.data.file.format=raw\n\
+            channel.height.data.num-points=2\n",
+        );
+        let info = properties.channel_info("height").unwrap();
+        assert_eq!(info.num_points(), 2);
+    }
+
+    #[test]
+    fn channel_info_unsupported_generator() {
+        let properties = segment_properties(
+            "force-segment-header.num-points=4\n\
+            channel.time.lcd-info.*=8\n\
+            channel.time.data.num-points=4\n\
+            channel.time.data.values=1 2 3 4\n",
+        );
+        let Err(channel::error::Info::UnsupportedGenerator { channel, keys }) =
+            properties.channel_info("time")
+        else {
+            panic!("expected unsupported generator");
+        };
+        assert_eq!(channel, "time");
+        assert_eq!(keys, vec!["values"]);
+    }
+
+    #[test]
+    fn channel_info_missing_source() {
+        let properties = segment_properties(
+            "force-segment-header.num-points=4\n\
+            channel.time.lcd-info.*=8\n\
+            channel.time.data.num-points=4\n",
+        );
+        let Err(channel::error::Info::Property(dataset_properties::error::Property::NotFound(key))) =
+            properties.channel_info("time")
+        else {
+            panic!("expected missing property");
+        };
+        assert_eq!(key, segment::Properties::channel_data_file_name_key("time"));
+    }
 }
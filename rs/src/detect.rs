@@ -0,0 +1,399 @@
+//! Open JPK files by detecting their format.
+//!
+//! Archives are identified by the `type` and `jpk-data-file` properties of their
+//! `header.properties`, images by their TIFF header,
+//! and scope and text exports by their `#` comment header.
+use crate::dataset::{self, DatasetError, FileFormat, preview::Preview, v2_0::DatasetReader};
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+const ZIP_SIGNATURE: &[u8] = b"PK\x03\x04";
+const TIFF_BIG_ENDIAN_SIGNATURE: &[u8] = b"MM\x00\x2a";
+const TIFF_LITTLE_ENDIAN_SIGNATURE: &[u8] = b"II\x2a\x00";
+const SCOPE_FILE_EXT: &str = "out";
+const EXPORT_FILE_EXTS: [&str; 2] = ["txt", "tsv"];
+const COMMENT_PREFIX: u8 = b'#';
+
+/// Kind of JPK file.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FileType {
+    /// `.jpk-qi-data`
+    QiMap,
+
+    /// `.jpk-force-map`
+    ForceMap,
+
+    /// `.jpk-voltage-ramp`
+    VoltageSpectroscopy,
+
+    /// `.jpk-force`
+    ForceCurve,
+
+    /// `.out`
+    Scope,
+
+    /// `.txt`, `.tsv` exported by JPK Data Processing.
+    Export,
+
+    /// `.jpk`, `.jpk-qi-image`
+    Image,
+}
+
+impl FileType {
+    /// # Arguments
+    /// + `input`: Value of the `type` dataset property.
+    pub fn from_dataset_type(input: impl AsRef<str>) -> Option<Self> {
+        match input.as_ref() {
+            "quantitative-imaging-map" => Some(Self::QiMap),
+            "force-scan-map" => Some(Self::ForceMap),
+            "voltage-spectroscopy-segment-series" => Some(Self::VoltageSpectroscopy),
+            "force-scan-series" => Some(Self::ForceCurve),
+            _ => None,
+        }
+    }
+
+    /// # Arguments
+    /// + `input`: Value of the `jpk-data-file` dataset property.
+    ///
+    /// # Returns
+    /// `None` if the data file is unknown or shared by multiple file types.
+    pub fn from_data_file(input: impl AsRef<str>) -> Option<Self> {
+        match input.as_ref() {
+            "spm-quantitative-image-data-file" => Some(Self::QiMap),
+            _ => None,
+        }
+    }
+}
+
+/// Reader for a detected file.
+pub enum JpkFile {
+    #[cfg(feature = "qi_map")]
+    QiMap(crate::qi_map::VersionedFileReader),
+
+    #[cfg(feature = "qi_map")]
+    ForceMap(crate::qi_map::VersionedFileReader),
+
+    #[cfg(feature = "voltage_spectroscopy")]
+    VoltageSpectroscopy(crate::voltage_spectroscopy::v2_0::FileReader),
+
+    #[cfg(feature = "force_curve")]
+    ForceCurve(crate::force_curve::v2_0::FileReader),
+
+    #[cfg(feature = "scope")]
+    Scope(crate::scope::ScopeFile),
+
+    #[cfg(feature = "jpk_export")]
+    Export(crate::jpk_export::FileReader),
+
+    #[cfg(feature = "jpk_image")]
+    Image(crate::jpk_image::Image),
+}
+
+impl JpkFile {
+    pub fn file_type(&self) -> FileType {
+        match *self {
+            #[cfg(feature = "qi_map")]
+            Self::QiMap(_) => FileType::QiMap,
+            #[cfg(feature = "qi_map")]
+            Self::ForceMap(_) => FileType::ForceMap,
+            #[cfg(feature = "voltage_spectroscopy")]
+            Self::VoltageSpectroscopy(_) => FileType::VoltageSpectroscopy,
+            #[cfg(feature = "force_curve")]
+            Self::ForceCurve(_) => FileType::ForceCurve,
+            #[cfg(feature = "scope")]
+            Self::Scope(_) => FileType::Scope,
+            #[cfg(feature = "jpk_export")]
+            Self::Export(_) => FileType::Export,
+            #[cfg(feature = "jpk_image")]
+            Self::Image(_) => FileType::Image,
+        }
+    }
+
+    /// Preview image of the file.
+    /// `None` for scope and export files.
+    pub fn thumbnail(&mut self) -> Result<Option<Preview>, DatasetError> {
+        match *self {
+            #[cfg(feature = "qi_map")]
+            Self::QiMap(ref mut reader) | Self::ForceMap(ref mut reader) => {
+                crate::qi_map::QIMapReader::thumbnail(reader)
+            }
+            #[cfg(feature = "voltage_spectroscopy")]
+            Self::VoltageSpectroscopy(ref mut reader) => reader.thumbnail(),
+            #[cfg(feature = "force_curve")]
+            Self::ForceCurve(ref mut reader) => reader.thumbnail(),
+            #[cfg(feature = "scope")]
+            Self::Scope(_) => Ok(None),
+            #[cfg(feature = "jpk_export")]
+            Self::Export(_) => Ok(None),
+            #[cfg(feature = "jpk_image")]
+            Self::Image(ref image) => Ok(image.thumbnail().cloned()),
+        }
+    }
+
+    /// All preview images of the file.
+    /// Empty for scope and export files.
+    pub fn previews(&mut self) -> Result<Vec<Preview>, DatasetError> {
+        match *self {
+            #[cfg(feature = "qi_map")]
+            Self::QiMap(ref mut reader) | Self::ForceMap(ref mut reader) => {
+                crate::qi_map::QIMapReader::previews(reader)
+            }
+            #[cfg(feature = "voltage_spectroscopy")]
+            Self::VoltageSpectroscopy(ref mut reader) => reader.previews(),
+            #[cfg(feature = "force_curve")]
+            Self::ForceCurve(ref mut reader) => reader.previews(),
+            #[cfg(feature = "scope")]
+            Self::Scope(_) => Ok(Vec::new()),
+            #[cfg(feature = "jpk_export")]
+            Self::Export(_) => Ok(Vec::new()),
+            #[cfg(feature = "jpk_image")]
+            Self::Image(ref image) => Ok(image.thumbnail().cloned().into_iter().collect()),
+        }
+    }
+}
+
+/// Detected type of a file.
+pub struct Detected {
+    file_type: FileType,
+    file_format: Option<FileFormat>,
+}
+
+impl Detected {
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// File format of archives.
+    /// `None` for files without a version.
+    pub fn file_format(&self) -> Option<&FileFormat> {
+        self.file_format.as_ref()
+    }
+
+    /// File format version of archives.
+    /// `None` for files without a version.
+    pub fn format_version(&self) -> Option<&String> {
+        self.file_format.as_ref().map(FileFormat::version)
+    }
+}
+
+/// Detect the type of the file at `path`.
+pub fn detect(path: impl AsRef<Path>) -> Result<Detected, error::Open> {
+    let path = path.as_ref();
+    let io_error = |error| error::Open::Io {
+        path: path.to_path_buf(),
+        error,
+    };
+
+    let mut signature = Vec::with_capacity(ZIP_SIGNATURE.len());
+    fs::File::open(path)
+        .map_err(io_error)?
+        .take(ZIP_SIGNATURE.len() as u64)
+        .read_to_end(&mut signature)
+        .map_err(io_error)?;
+
+    if signature == ZIP_SIGNATURE {
+        return detect_archive(path);
+    }
+
+    let file_type =
+        if signature == TIFF_BIG_ENDIAN_SIGNATURE || signature == TIFF_LITTLE_ENDIAN_SIGNATURE {
+            FileType::Image
+        } else if signature.first() == Some(&COMMENT_PREFIX)
+            && path
+                .extension()
+                .is_some_and(|ext| EXPORT_FILE_EXTS.iter().any(|export| ext == *export))
+        {
+            FileType::Export
+        } else if signature.first() == Some(&COMMENT_PREFIX)
+            || path.extension().is_some_and(|ext| ext == SCOPE_FILE_EXT)
+        {
+            FileType::Scope
+        } else {
+            return Err(error::Open::UnknownFormat(path.to_path_buf()));
+        };
+
+    Ok(Detected {
+        file_type,
+        file_format: None,
+    })
+}
+
+fn detect_archive(path: &Path) -> Result<Detected, error::Open> {
+    let mut archive = dataset::open_archive(path).map_err(|error| DatasetError::OpenFile {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    let properties = DatasetReader::read_dataset_properties(&mut archive)?;
+    let dataset_type = properties.dataset_type();
+    let file_type = dataset_type
+        .and_then(FileType::from_dataset_type)
+        .or_else(|| properties.data_file().and_then(FileType::from_data_file));
+    let Some(file_type) = file_type else {
+        return Err(error::Open::UnknownDatasetType {
+            path: path.to_path_buf(),
+            dataset_type: dataset_type.map(String::from),
+        });
+    };
+
+    let file_format = DatasetReader::<fs::File>::file_format(&properties)?;
+    Ok(Detected {
+        file_type,
+        file_format: Some(file_format),
+    })
+}
+
+/// Open the file at `path` with the reader for its detected type.
+pub fn open(path: impl Into<PathBuf>) -> Result<JpkFile, error::Open> {
+    let path = path.into();
+    let detected = detect(&path)?;
+    if let Some(file_format) = detected.file_format() {
+        file_format
+            .supported_version()
+            .map_err(|error| error::Open::FileFormatNotSupported {
+                file_type: detected.file_type,
+                error,
+            })?;
+    }
+
+    // each arm returns, so no arm is unreachable when all readers are disabled
+    match detected.file_type {
+        #[cfg(feature = "qi_map")]
+        FileType::QiMap => Ok(JpkFile::QiMap(crate::qi_map::FileReader::new_versioned(
+            path,
+        )?)),
+        #[cfg(feature = "qi_map")]
+        FileType::ForceMap => Ok(JpkFile::ForceMap(crate::qi_map::FileReader::new_versioned(
+            path,
+        )?)),
+        #[cfg(feature = "voltage_spectroscopy")]
+        FileType::VoltageSpectroscopy => Ok(JpkFile::VoltageSpectroscopy(
+            crate::voltage_spectroscopy::v2_0::FileReader::new(path)?,
+        )),
+        #[cfg(feature = "force_curve")]
+        FileType::ForceCurve => Ok(JpkFile::ForceCurve(
+            crate::force_curve::v2_0::FileReader::new(path)?,
+        )),
+        #[cfg(feature = "scope")]
+        FileType::Scope => Ok(JpkFile::Scope(crate::scope::ScopeFile::open(path)?)),
+        #[cfg(feature = "jpk_export")]
+        FileType::Export => Ok(JpkFile::Export(crate::jpk_export::FileReader::new(path)?)),
+        #[cfg(feature = "jpk_image")]
+        FileType::Image => Ok(JpkFile::Image(crate::jpk_image::Image::open(path)?)),
+        #[allow(unreachable_patterns)]
+        file_type => Err(error::Open::FeatureNotEnabled(file_type)),
+    }
+}
+
+pub mod error {
+    use super::FileType;
+    use crate::dataset::{DatasetError, format::UnsupportedFormat};
+    use std::{fmt, io, path::PathBuf};
+
+    #[derive(derive_more::From, Debug)]
+    pub enum Open {
+        /// The file at the given path could not be read.
+        #[from(ignore)]
+        Io {
+            path: PathBuf,
+            error: io::Error,
+        },
+
+        Dataset(DatasetError),
+
+        #[cfg(feature = "qi_map")]
+        QiMap(crate::qi_map::Error),
+
+        #[cfg(feature = "scope")]
+        Scope(crate::scope::error::Scope),
+
+        #[cfg(feature = "jpk_export")]
+        Export(crate::jpk_export::error::Export),
+
+        #[cfg(feature = "jpk_image")]
+        Image(crate::jpk_image::error::Image),
+
+        /// The file at the given path is not a known JPK file.
+        #[from(ignore)]
+        UnknownFormat(PathBuf),
+
+        /// The archive at the given path has an unknown dataset type.
+        #[from(ignore)]
+        UnknownDatasetType {
+            path: PathBuf,
+            dataset_type: Option<String>,
+        },
+
+        /// The file format version or features of the file type can not be read.
+        #[from(ignore)]
+        FileFormatNotSupported {
+            file_type: FileType,
+            error: UnsupportedFormat,
+        },
+
+        /// The reader for the file type is disabled.
+        #[from(ignore)]
+        FeatureNotEnabled(FileType),
+    }
+
+    impl fmt::Display for Open {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Io { path, .. } => write!(f, "could not read `{}`", path.display()),
+                Self::Dataset(err) => write!(f, "{err}"),
+                #[cfg(feature = "qi_map")]
+                Self::QiMap(err) => write!(f, "{err}"),
+                #[cfg(feature = "scope")]
+                Self::Scope(err) => write!(f, "{err}"),
+                #[cfg(feature = "jpk_export")]
+                Self::Export(err) => write!(f, "{err}"),
+                #[cfg(feature = "jpk_image")]
+                Self::Image(err) => write!(f, "{err}"),
+                Self::UnknownFormat(path) => {
+                    write!(f, "`{}` is not a known JPK file", path.display())
+                }
+                Self::UnknownDatasetType {
+                    path,
+                    dataset_type: Some(dataset_type),
+                } => write!(
+                    f,
+                    "`{}` has unknown dataset type `{dataset_type}`",
+                    path.display()
+                ),
+                Self::UnknownDatasetType {
+                    path,
+                    dataset_type: None,
+                } => write!(f, "`{}` has no dataset type", path.display()),
+                Self::FileFormatNotSupported { file_type, .. } => {
+                    write!(f, "{file_type:?} file format is not supported")
+                }
+                Self::FeatureNotEnabled(file_type) => {
+                    write!(f, "reading {file_type:?} files is not enabled")
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for Open {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::Io { error, .. } => Some(error),
+                Self::Dataset(err) => err.source(),
+                #[cfg(feature = "qi_map")]
+                Self::QiMap(err) => err.source(),
+                #[cfg(feature = "scope")]
+                Self::Scope(err) => err.source(),
+                #[cfg(feature = "jpk_export")]
+                Self::Export(err) => err.source(),
+                #[cfg(feature = "jpk_image")]
+                Self::Image(err) => err.source(),
+                Self::FileFormatNotSupported { error, .. } => Some(error),
+                Self::UnknownFormat(_)
+                | Self::UnknownDatasetType { .. }
+                | Self::FeatureNotEnabled(_) => None,
+            }
+        }
+    }
+}
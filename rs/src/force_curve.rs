@@ -0,0 +1,280 @@
+//! Force curve data reader.
+//! (`.jpk-force`)
+
+pub mod v2_0 {
+    use crate::{
+        dataset::{
+            DatasetError, preview::Preview, properties, v2_0 as dataset, v2_0::DatasetReader,
+        },
+        segment_series,
+    };
+    use polars::prelude::{self as pl, IntoColumn};
+    use rayon::prelude::*;
+    use std::{fs, io, path::PathBuf};
+
+    const FORCE_CURVE_FILE_EXT: &str = "jpk-force";
+    const DATASET_TYPE_PROPERTY_VALUE: &str = "force-scan-series";
+    const SEGMENT_SERIES_PROPERTY_KEY: &str = "force-scan-series";
+    const SEGMENTS_PROPERTY_KEY: &str = "force-segments";
+    const POSITION_PROPERTY_KEY: &str = "header.position";
+    const SEGMENT_HEADER_PROPERTY_KEY: &str = "force-segment-header";
+    const SEGMENT_STYLE_PROPERTY_KEY: &str = "settings.style";
+
+    /// Name of the segment style column.
+    const STYLE_COLUMN: &str = "style";
+
+    /// Motion of the cantilever during a segment.
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    pub enum SegmentStyle {
+        /// Cantilever approaches the sample.
+        Extend,
+
+        /// Cantilever is withdrawn from the sample.
+        Retract,
+
+        /// Cantilever is held in place.
+        Pause,
+    }
+
+    impl SegmentStyle {
+        pub fn from_str(input: impl AsRef<str>) -> Option<Self> {
+            match input.as_ref() {
+                "extend" => Some(Self::Extend),
+                "retract" => Some(Self::Retract),
+                "pause" => Some(Self::Pause),
+                _ => None,
+            }
+        }
+
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Self::Extend => "extend",
+                Self::Retract => "retract",
+                Self::Pause => "pause",
+            }
+        }
+
+        /// # Arguments
+        /// + `properties`: Segment properties.
+        pub fn from_properties(
+            properties: &dataset::properties::segment::Properties,
+        ) -> Result<Self, properties::error::Property> {
+            properties
+                .subtree(SEGMENT_HEADER_PROPERTY_KEY)
+                .get_with(SEGMENT_STYLE_PROPERTY_KEY, Self::from_str)
+        }
+    }
+
+    #[derive(derive_more::Deref)]
+    pub struct Reader<R> {
+        inner: DatasetReader<R>,
+    }
+
+    impl<R> Reader<R>
+    where
+        R: io::Read + io::Seek,
+    {
+        pub fn new(archive: ::zip::ZipArchive<R>) -> Result<Self, DatasetError> {
+            let reader = DatasetReader::new(archive)?;
+            Ok(Self { inner: reader })
+        }
+
+        pub fn segment_properties(
+            &mut self,
+            segment: dataset::SegmentType,
+        ) -> Result<dataset::properties::segment::Properties, dataset::error::Properties> {
+            let segment_path = dataset::utils::segment_path(segment);
+            self.inner.segment_properties(segment_path)
+        }
+
+        /// Style of a segment.
+        pub fn segment_style(
+            &mut self,
+            segment: dataset::SegmentType,
+        ) -> Result<SegmentStyle, error::DataFile> {
+            let properties = self.segment_properties(segment)?;
+            Ok(SegmentStyle::from_properties(&properties)?)
+        }
+
+        /// Preview image of the curve.
+        pub fn thumbnail(&mut self) -> Result<Option<Preview>, DatasetError> {
+            self.inner.thumbnail()
+        }
+
+        /// All preview images of the curve.
+        pub fn previews(&mut self) -> Result<Vec<Preview>, DatasetError> {
+            self.inner.previews()
+        }
+
+        /// Replace the scaling of a conversion of a channel for all subsequent reads.
+        /// The original and new values are recorded in the shared properties.
+        /// See [`DatasetReader::override_calibration`].
+        pub fn override_calibration(
+            &mut self,
+            channel: impl AsRef<str>,
+            slot: impl AsRef<str>,
+            multiplier: f64,
+            offset: f64,
+        ) -> Result<(), dataset::error::OverrideCalibration> {
+            self.inner
+                .override_calibration(channel, slot, multiplier, offset)
+        }
+
+        /// Read the data of a channel.
+        ///
+        /// # Arguments
+        /// + `calibration_slot`: Calibration slot to convert the data to.
+        ///   If `None` the default slot is used.
+        pub fn channel_data(
+            &mut self,
+            segment: dataset::SegmentType,
+            channel: impl AsRef<str>,
+            calibration_slot: Option<&str>,
+        ) -> Result<dataset::ChannelData, dataset::error::ChannelData> {
+            let segment_path = dataset::utils::segment_path(segment);
+            self.inner
+                .channel_data(segment_path, channel, calibration_slot)
+        }
+
+        /// Loads data from all segments and all channels.
+        ///
+        /// # Returns
+        /// Data frame with `segment` and `style` columns and a column for each channel.
+        pub fn load_data_all(&mut self) -> Result<pl::DataFrame, error::DataFile> {
+            let segments_count = self.segments_count()?;
+            let mut df = segment_series::load_data_all(&mut self.inner, segments_count)?;
+            if segments_count == 0 {
+                return Ok(df);
+            }
+
+            let styles = (0..segments_count)
+                .map(|segment| self.segment_style(segment))
+                .collect::<Result<Vec<_>, _>>()?;
+            let style = df
+                .column(segment_series::SEGMENT_COLUMN)?
+                .u8()?
+                .into_no_null_iter()
+                .map(|segment| styles[segment as usize].as_str())
+                .collect::<pl::StringChunked>()
+                .with_name(STYLE_COLUMN.into())
+                .into_column();
+            df.insert_column(1, style)?;
+            Ok(df)
+        }
+    }
+
+    impl<R> Reader<R> {
+        /// # Returns
+        /// If the dataset type property matches the expected value.
+        pub fn validate_dataset_type(&self) -> bool {
+            self.inner
+                .dataset_properties()
+                .dataset_type()
+                .map(|dataset_type| dataset_type == DATASET_TYPE_PROPERTY_VALUE)
+                .unwrap_or(false)
+        }
+
+        /// Number of segments in the dataset as indicated in the dataset properties.
+        pub fn segments_count(&self) -> Result<dataset::SegmentType, properties::error::Property> {
+            self.inner
+                .dataset_properties()
+                .subtree(SEGMENT_SERIES_PROPERTY_KEY)
+                .subtree(SEGMENTS_PROPERTY_KEY)
+                .parse::<dataset::SegmentType>("count")
+        }
+
+        /// `(x, y)` coordinate.
+        pub fn position(&self) -> Result<(f64, f64), properties::error::Property> {
+            let position = self
+                .inner
+                .dataset_properties()
+                .subtree(SEGMENT_SERIES_PROPERTY_KEY)
+                .subtree(POSITION_PROPERTY_KEY);
+            let x = position.get_f64("x")?;
+            let y = position.get_f64("y")?;
+            Ok((x, y))
+        }
+    }
+
+    /// Read a single force curve (`.jpk-force`) file.
+    #[derive(derive_more::Deref, derive_more::DerefMut)]
+    pub struct FileReader {
+        path: PathBuf,
+        #[deref]
+        #[deref_mut]
+        inner: Reader<fs::File>,
+    }
+
+    impl FileReader {
+        pub fn new(path: impl Into<PathBuf>) -> Result<Self, DatasetError> {
+            let path = path.into();
+            let archive =
+                crate::dataset::open_archive(&path).map_err(|error| DatasetError::OpenFile {
+                    path: path.clone(),
+                    error,
+                })?;
+            let inner = Reader::new(archive)?;
+            Ok(Self { path, inner })
+        }
+
+        pub fn path(&self) -> &PathBuf {
+            &self.path
+        }
+    }
+
+    /// Read a collection of force curve files (`.jpk-force`) from a directory.
+    pub struct DirReader {
+        path: PathBuf,
+    }
+
+    impl DirReader {
+        pub fn new(path: impl Into<PathBuf>) -> Self {
+            Self { path: path.into() }
+        }
+
+        pub fn path(&self) -> &PathBuf {
+            &self.path
+        }
+
+        /// Loads data from all segments and all channels of each file,
+        /// adding the `x` and `y` position of the curve.
+        /// Does not recurse into children folders.
+        pub fn load_data_all(&self) -> Result<pl::DataFrame, error::DataCollection> {
+            let files = segment_series::files_with_extension(&self.path, FORCE_CURVE_FILE_EXT)?;
+            let readers = files
+                .into_par_iter()
+                .map(|path| {
+                    FileReader::new(path.clone())
+                        .map_err(|error| error::DataCollection::Dataset { path, error })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let data = readers
+                .into_par_iter()
+                .map(|mut reader| {
+                    let data =
+                        reader
+                            .load_data_all()
+                            .map_err(|err| error::DataCollection::DataFile {
+                                path: reader.path().clone(),
+                                error: err,
+                            })?;
+                    let xy = reader
+                        .position()
+                        .map_err(|err| error::DataCollection::DataFile {
+                            path: reader.path().clone(),
+                            error: err.into(),
+                        })?;
+
+                    Result::<_, error::DataCollection>::Ok((xy, data))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(segment_series::stack_with_position(data)?)
+        }
+    }
+
+    pub mod error {
+        pub use crate::segment_series::error::{DataCollection, DataFile};
+    }
+}
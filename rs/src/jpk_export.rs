@@ -0,0 +1,374 @@
+//! JPK Data Processing text export reader.
+//! (`.txt`, `.tsv`)
+//!
+//! Exports contain a block for each segment of a curve.
+//! Each block starts with a header of `# key: value` lines,
+//! including the segment name, column names and units,
+//! followed by whitespace separated rows of values.
+//! The header of the first block also holds the properties of the originating archive.
+use crate::{
+    dataset::{
+        properties::{self, Properties},
+        v2_0::SegmentType,
+    },
+    scope::Metadata,
+    segment_series::{self, SegmentColumns},
+};
+use polars::prelude as pl;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::PathBuf,
+};
+
+const COMMENT_PREFIX: &str = "#";
+const HEADER_KEY_SEPARATOR: char = ':';
+const SEGMENT_INDEX_KEY: &str = "segmentIndex";
+const SEGMENT_NAME_KEY: &str = "segment";
+const X_POSITION_KEY: &str = "xPosition";
+const Y_POSITION_KEY: &str = "yPosition";
+
+/// Segment of an export.
+#[derive(Debug)]
+pub struct Segment {
+    index: SegmentType,
+    metadata: Metadata,
+    data: Vec<Vec<f64>>,
+}
+
+impl Segment {
+    pub fn index(&self) -> SegmentType {
+        self.index
+    }
+
+    /// Name of the segment, e.g. `extend` or `retract`.
+    pub fn name(&self) -> Option<&str> {
+        self.metadata.get(SEGMENT_NAME_KEY)
+    }
+
+    /// Header of the segment, with its columns and units.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Number of data points.
+    pub fn len(&self) -> usize {
+        self.data.first().map(Vec::len).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Values of a column.
+    pub fn column(&self, name: impl AsRef<str>) -> Option<&Vec<f64>> {
+        let name = name.as_ref();
+        let idx = self
+            .metadata
+            .columns()
+            .iter()
+            .position(|column| column.name() == name)?;
+        self.data.get(idx)
+    }
+}
+
+/// Read a JPK Data Processing text export.
+pub struct FileReader {
+    path: PathBuf,
+    properties: Properties,
+    segments: Vec<Segment>,
+}
+
+impl FileReader {
+    /// Read the export at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, error::Export> {
+        let path = path.into();
+        let io_error = |error| error::Export::Io {
+            path: path.clone(),
+            error,
+        };
+
+        let file = File::open(&path).map_err(io_error)?;
+        let (properties, segments) = parse(BufReader::new(file)).map_err(|err| match err {
+            error::Export::Io { error, .. } => io_error(error),
+            err => err,
+        })?;
+        Ok(Self {
+            path,
+            properties,
+            segments,
+        })
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Header of the first segment, including the properties of the originating archive.
+    pub fn properties(&self) -> &Properties {
+        &self.properties
+    }
+
+    pub fn segments(&self) -> &Vec<Segment> {
+        &self.segments
+    }
+
+    /// `(x, y)` coordinate.
+    pub fn position(&self) -> Result<(f64, f64), properties::error::Property> {
+        let properties = self.properties.tree();
+        let x = properties.get_f64(X_POSITION_KEY)?;
+        let y = properties.get_f64(Y_POSITION_KEY)?;
+        Ok((x, y))
+    }
+
+    /// Loads data from all segments and all channels.
+    ///
+    /// # Returns
+    /// Data frame with a `segment` column and a column for each channel,
+    /// matching [`crate::voltage_spectroscopy::v2_0::FileReader::load_data_all`].
+    /// Channels that are not present in a segment are null for that segment.
+    pub fn load_data_all(&self) -> Result<pl::DataFrame, pl::PolarsError> {
+        let segments = self
+            .segments
+            .iter()
+            .map(|segment| {
+                let columns = std::iter::zip(segment.metadata.columns(), &segment.data)
+                    .map(|(column, data)| pl::Column::new(column.name().into(), data.as_slice()))
+                    .collect();
+                SegmentColumns {
+                    segment: segment.index,
+                    length: segment.len(),
+                    columns,
+                }
+            })
+            .collect();
+        segment_series::combine_segments(segments)
+    }
+}
+
+/// Parse the blocks of an export.
+///
+/// # Returns
+/// Properties from the header of the first block, and the segments.
+fn parse(reader: impl BufRead) -> Result<(Properties, Vec<Segment>), error::Export> {
+    let mut segments = Vec::new();
+    let mut header = Vec::new();
+    let mut block: Option<(Metadata, Vec<Vec<f64>>)> = None;
+    let mut properties = None;
+    for (idx, line) in reader.lines().enumerate() {
+        let line_number = idx + 1;
+        let line = line.map_err(|error| error::Export::Io {
+            path: PathBuf::new(),
+            error,
+        })?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(entry) = line.strip_prefix(COMMENT_PREFIX) {
+            if let Some((metadata, data)) = block.take() {
+                segments.push(new_segment(metadata, data, segments.len())?);
+            }
+            if let Some((key, value)) = entry.split_once(HEADER_KEY_SEPARATOR) {
+                let key = key.trim();
+                // a segment without data rows ends where the next segment starts
+                if key == SEGMENT_INDEX_KEY && has_segment_index(&header) {
+                    push_empty_segment(&mut header, &mut properties, &mut segments)?;
+                }
+                header.push((key.to_string(), value.trim().to_string()));
+            }
+            continue;
+        }
+
+        let (metadata, data) = match block {
+            Some(ref mut block) => block,
+            None => {
+                if properties.is_none() {
+                    properties = Some(header_properties(&header)?);
+                }
+                let metadata = Metadata::from_header(std::mem::take(&mut header));
+                if metadata.columns().is_empty() {
+                    return Err(error::Export::MissingColumns { line: line_number });
+                }
+                let data = vec![Vec::new(); metadata.columns().len()];
+                block.insert((metadata, data))
+            }
+        };
+
+        let mut found = 0;
+        for value in line.split_ascii_whitespace() {
+            if let Some(column) = data.get_mut(found) {
+                let value = value
+                    .parse::<f64>()
+                    .map_err(|_| error::Export::InvalidValue {
+                        line: line_number,
+                        column: found,
+                        value: value.to_string(),
+                    })?;
+                column.push(value);
+            }
+            found += 1;
+        }
+        if found != metadata.columns().len() {
+            return Err(error::Export::InvalidRow {
+                line: line_number,
+                expected: metadata.columns().len(),
+                found,
+            });
+        }
+    }
+
+    if let Some((metadata, data)) = block.take() {
+        segments.push(new_segment(metadata, data, segments.len())?);
+    }
+    if has_segment_index(&header) {
+        push_empty_segment(&mut header, &mut properties, &mut segments)?;
+    }
+    let properties = match properties {
+        Some(properties) => properties,
+        None => header_properties(&header)?,
+    };
+    Ok((properties, segments))
+}
+
+fn has_segment_index(header: &[(String, String)]) -> bool {
+    header.iter().any(|(key, _)| key == SEGMENT_INDEX_KEY)
+}
+
+/// End a header block that is not followed by data rows as a segment without data.
+fn push_empty_segment(
+    header: &mut Vec<(String, String)>,
+    properties: &mut Option<Properties>,
+    segments: &mut Vec<Segment>,
+) -> Result<(), error::Export> {
+    if properties.is_none() {
+        *properties = Some(header_properties(header)?);
+    }
+    let metadata = Metadata::from_header(std::mem::take(header));
+    let data = vec![Vec::new(); metadata.columns().len()];
+    segments.push(new_segment(metadata, data, segments.len())?);
+    Ok(())
+}
+
+/// # Arguments
+/// + `position`: Position of the segment in the file,
+///   used as its index if the header does not have one.
+fn new_segment(
+    metadata: Metadata,
+    data: Vec<Vec<f64>>,
+    position: usize,
+) -> Result<Segment, error::Export> {
+    let index = match metadata.get(SEGMENT_INDEX_KEY) {
+        Some(index) => index
+            .parse::<SegmentType>()
+            .map_err(|_| error::Export::InvalidSegmentIndex(index.to_string()))?,
+        None => SegmentType::try_from(position)
+            .map_err(|_| error::Export::InvalidSegmentIndex(position.to_string()))?,
+    };
+
+    Ok(Segment {
+        index,
+        metadata,
+        data,
+    })
+}
+
+/// Convert header entries to properties.
+fn header_properties(header: &[(String, String)]) -> Result<Properties, error::Export> {
+    let escape = |input: &str, key: bool| {
+        let mut escaped = String::with_capacity(input.len());
+        for c in input.chars() {
+            match c {
+                '\\' => escaped.push_str("\\\\"),
+                '=' | ':' | ' ' if key => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                c => escaped.push(c),
+            }
+        }
+        escaped
+    };
+
+    let mut input = String::new();
+    for (key, value) in header {
+        input.push_str(&escape(key, true));
+        input.push('=');
+        input.push_str(&escape(value, false));
+        input.push('\n');
+    }
+    Properties::parse(&input).map_err(error::Export::Properties)
+}
+
+pub mod error {
+    use crate::dataset::properties;
+    use std::{fmt, io, path::PathBuf};
+
+    #[derive(Debug)]
+    pub enum Export {
+        /// The file at the given path could not be read.
+        Io { path: PathBuf, error: io::Error },
+
+        /// The header could not be parsed as properties.
+        Properties(properties::error::Parse),
+
+        /// A data row is not preceded by a `columns` header.
+        MissingColumns { line: usize },
+
+        /// A row does not have a value for each column.
+        InvalidRow {
+            line: usize,
+            expected: usize,
+            found: usize,
+        },
+
+        /// A value could not be parsed as a number.
+        InvalidValue {
+            line: usize,
+            column: usize,
+            value: String,
+        },
+
+        /// The segment index is not valid.
+        InvalidSegmentIndex(String),
+    }
+
+    impl fmt::Display for Export {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Io { path, .. } => write!(f, "could not read `{}`", path.display()),
+                Self::Properties(_) => write!(f, "could not parse header"),
+                Self::MissingColumns { line } => {
+                    write!(f, "line {line}: data does not have a `columns` header")
+                }
+                Self::InvalidRow {
+                    line,
+                    expected,
+                    found,
+                } => write!(f, "line {line}: expected {expected} values, found {found}"),
+                Self::InvalidValue {
+                    line,
+                    column,
+                    value,
+                } => write!(f, "line {line}, column {column}: invalid value `{value}`"),
+                Self::InvalidSegmentIndex(index) => {
+                    write!(f, "invalid segment index `{index}`")
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for Export {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::Io { error, .. } => Some(error),
+                Self::Properties(error) => Some(error),
+                Self::MissingColumns { .. }
+                | Self::InvalidRow { .. }
+                | Self::InvalidValue { .. }
+                | Self::InvalidSegmentIndex(_) => None,
+            }
+        }
+    }
+}
@@ -0,0 +1,796 @@
+//! JPK image reader.
+//! (`.jpk`, `data-image.jpk-qi-image`)
+//!
+//! Images are big-endian TIFF files.
+//! The first image file directory (IFD) holds a thumbnail and the scan metadata,
+//! each following IFD holds the raw data of a channel.
+//! JPK specific information is stored in private tags.
+pub mod tiff;
+
+use crate::dataset::preview::{self, Preview};
+use std::{fs, io, path::PathBuf};
+use tiff::{Ifd, Tag};
+
+/// Name of the image file in a QI map archive.
+pub const QI_DATA_IMAGE_FILE: &str = "data-image.jpk-qi-image";
+
+/// Index of the IFD holding the scan metadata.
+const METADATA_IFD: usize = 0;
+
+/// Index of the IFD holding the thumbnail.
+const THUMBNAIL_IFD: usize = 0;
+
+/// Scan metadata.
+pub struct Metadata {
+    software_version: Option<String>,
+    file_format_version: Option<f64>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    account_name: Option<String>,
+    scan_number: Option<i64>,
+}
+
+impl Metadata {
+    const SOFTWARE_VERSION_TAG: Tag = 0x8000;
+    const FILE_FORMAT_VERSION_TAG: Tag = 0x8001;
+    const START_DATE_TAG: Tag = 0x8003;
+    const END_DATE_TAG: Tag = 0x8006;
+    const SCAN_NUMBER_TAG: Tag = 0x8008;
+    const ACCOUNT_NAME_TAG: Tag = 0x8009;
+}
+
+impl Metadata {
+    pub fn from_ifd(ifd: &Ifd) -> Self {
+        Self {
+            software_version: ifd.get_str(Self::SOFTWARE_VERSION_TAG).map(String::from),
+            file_format_version: ifd.get_f64(Self::FILE_FORMAT_VERSION_TAG),
+            start_date: ifd.get_str(Self::START_DATE_TAG).map(String::from),
+            end_date: ifd.get_str(Self::END_DATE_TAG).map(String::from),
+            account_name: ifd.get_str(Self::ACCOUNT_NAME_TAG).map(String::from),
+            scan_number: ifd.get_i64(Self::SCAN_NUMBER_TAG),
+        }
+    }
+}
+
+impl Metadata {
+    /// Version of the software that recorded the scan.
+    pub fn software_version(&self) -> Option<&String> {
+        self.software_version.as_ref()
+    }
+
+    pub fn file_format_version(&self) -> Option<f64> {
+        self.file_format_version
+    }
+
+    pub fn start_date(&self) -> Option<&String> {
+        self.start_date.as_ref()
+    }
+
+    pub fn end_date(&self) -> Option<&String> {
+        self.end_date.as_ref()
+    }
+
+    /// Account the scan was recorded with.
+    pub fn account_name(&self) -> Option<&String> {
+        self.account_name.as_ref()
+    }
+
+    pub fn scan_number(&self) -> Option<i64> {
+        self.scan_number
+    }
+}
+
+/// Scan grid.
+/// Lengths and coordinates are in meters.
+pub struct Grid {
+    x_origin: f64,
+    y_origin: f64,
+    u_length: f64,
+    v_length: f64,
+    theta: f64,
+    reflect: bool,
+    i_length: u32,
+    j_length: u32,
+}
+
+impl Grid {
+    const X_ORIGIN_TAG: Tag = 0x8040;
+    const Y_ORIGIN_TAG: Tag = 0x8041;
+    const U_LENGTH_TAG: Tag = 0x8042;
+    const V_LENGTH_TAG: Tag = 0x8043;
+    const THETA_TAG: Tag = 0x8044;
+    const REFLECT_TAG: Tag = 0x8045;
+    const I_LENGTH_TAG: Tag = 0x8046;
+    const J_LENGTH_TAG: Tag = 0x8047;
+}
+
+impl Grid {
+    /// # Returns
+    /// `None` if the IFD does not contain grid information.
+    ///
+    /// # Errors
+    /// + If a grid tag is missing or invalid.
+    pub fn from_ifd(ifd: &Ifd) -> Result<Option<Self>, Tag> {
+        if !ifd.contains(Self::X_ORIGIN_TAG) {
+            return Ok(None);
+        }
+
+        let f64_tag = |tag| ifd.get_f64(tag).ok_or(tag);
+        let u32_tag = |tag| {
+            ifd.get_u64(tag)
+                .and_then(|value| u32::try_from(value).ok())
+                .ok_or(tag)
+        };
+
+        Ok(Some(Self {
+            x_origin: f64_tag(Self::X_ORIGIN_TAG)?,
+            y_origin: f64_tag(Self::Y_ORIGIN_TAG)?,
+            u_length: f64_tag(Self::U_LENGTH_TAG)?,
+            v_length: f64_tag(Self::V_LENGTH_TAG)?,
+            theta: f64_tag(Self::THETA_TAG)?,
+            reflect: ifd.get_i64(Self::REFLECT_TAG).ok_or(Self::REFLECT_TAG)? != 0,
+            i_length: u32_tag(Self::I_LENGTH_TAG)?,
+            j_length: u32_tag(Self::J_LENGTH_TAG)?,
+        }))
+    }
+}
+
+impl Grid {
+    /// `x` coordinate of the grid origin.
+    pub fn x_origin(&self) -> f64 {
+        self.x_origin
+    }
+
+    /// `y` coordinate of the grid origin.
+    pub fn y_origin(&self) -> f64 {
+        self.y_origin
+    }
+
+    /// `x` coordinate of the grid center.
+    pub fn x_center(&self) -> f64 {
+        self.x_origin + self.u_length / 2.0
+    }
+
+    /// `y` coordinate of the grid center.
+    pub fn y_center(&self) -> f64 {
+        self.y_origin + self.v_length / 2.0
+    }
+
+    /// Length of the grid along the `i` axis.
+    pub fn u_length(&self) -> f64 {
+        self.u_length
+    }
+
+    /// Length of the grid along the `j` axis.
+    pub fn v_length(&self) -> f64 {
+        self.v_length
+    }
+
+    /// Rotation of the grid in radians.
+    pub fn theta(&self) -> f64 {
+        self.theta
+    }
+
+    pub fn reflect(&self) -> bool {
+        self.reflect
+    }
+
+    /// Number of pixels along the `i` axis.
+    pub fn i_length(&self) -> u32 {
+        self.i_length
+    }
+
+    /// Number of pixels along the `j` axis.
+    pub fn j_length(&self) -> u32 {
+        self.j_length
+    }
+}
+
+/// Conversion from raw values to a calibration slot.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Scaling {
+    /// Raw values are used as is.
+    Null,
+
+    /// `raw * multiplier + offset`
+    Linear { multiplier: f64, offset: f64 },
+}
+
+impl Scaling {
+    pub fn apply(&self, value: f64) -> f64 {
+        match self {
+            Self::Null => value,
+            Self::Linear { multiplier, offset } => value * multiplier + offset,
+        }
+    }
+}
+
+/// Calibration slot of a channel.
+pub struct Slot {
+    name: String,
+    parent: Option<String>,
+    calibration_name: Option<String>,
+    unit: Option<String>,
+    scaling: Scaling,
+}
+
+impl Slot {
+    /// Tag of the first slot.
+    const BASE_TAG: Tag = 0x8090;
+    /// Tag distance between consecutive slots.
+    const STRIDE: Tag = 0x30;
+
+    const NAME_OFFSET: Tag = 0x00;
+    const PARENT_OFFSET: Tag = 0x02;
+    const CALIBRATION_NAME_OFFSET: Tag = 0x10;
+    const UNIT_OFFSET: Tag = 0x12;
+    const SCALING_TYPE_OFFSET: Tag = 0x13;
+    const MULTIPLIER_OFFSET: Tag = 0x14;
+    const OFFSET_OFFSET: Tag = 0x15;
+
+    const NULL_SCALING: &str = "NullScaling";
+    const LINEAR_SCALING: &str = "LinearScaling";
+}
+
+impl Slot {
+    /// # Arguments
+    /// + `index`: Index of the slot in the channel.
+    ///
+    /// # Errors
+    /// + If a slot tag is missing or invalid.
+    pub fn from_ifd(ifd: &Ifd, index: u16) -> Result<Self, Tag> {
+        let base = index
+            .checked_mul(Self::STRIDE)
+            .and_then(|offset| Self::BASE_TAG.checked_add(offset))
+            .filter(|base| base.checked_add(Self::STRIDE).is_some())
+            .ok_or(Self::BASE_TAG)?;
+        let str_tag = |offset: Tag| ifd.get_str(base + offset).map(String::from);
+
+        let name = str_tag(Self::NAME_OFFSET).ok_or(base + Self::NAME_OFFSET)?;
+        let scaling_tag = base + Self::SCALING_TYPE_OFFSET;
+        let scaling = match ifd.get_str(scaling_tag) {
+            None | Some(Self::NULL_SCALING) => Scaling::Null,
+            Some(Self::LINEAR_SCALING) => {
+                let multiplier_tag = base + Self::MULTIPLIER_OFFSET;
+                let offset_tag = base + Self::OFFSET_OFFSET;
+                Scaling::Linear {
+                    multiplier: ifd.get_f64(multiplier_tag).ok_or(multiplier_tag)?,
+                    offset: ifd.get_f64(offset_tag).ok_or(offset_tag)?,
+                }
+            }
+            Some(_) => return Err(scaling_tag),
+        };
+
+        Ok(Self {
+            name,
+            parent: str_tag(Self::PARENT_OFFSET),
+            calibration_name: str_tag(Self::CALIBRATION_NAME_OFFSET),
+            unit: str_tag(Self::UNIT_OFFSET),
+            scaling,
+        })
+    }
+}
+
+impl Slot {
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Slot the calibration is defined relative to.
+    /// `None` for absolute calibrations.
+    pub fn parent(&self) -> Option<&String> {
+        self.parent.as_ref()
+    }
+
+    /// Display name of the calibration.
+    pub fn calibration_name(&self) -> Option<&String> {
+        self.calibration_name.as_ref()
+    }
+
+    pub fn unit(&self) -> Option<&String> {
+        self.unit.as_ref()
+    }
+
+    /// Conversion from raw values.
+    /// Relative calibrations are stored already composed with their parents,
+    /// so the scaling always applies to the raw values.
+    pub fn scaling(&self) -> &Scaling {
+        &self.scaling
+    }
+}
+
+/// Channel of an image.
+pub struct Channel {
+    name: String,
+    fancy_name: Option<String>,
+    retrace: bool,
+    info: Option<String>,
+    default_slot: Option<String>,
+    slots: Vec<Slot>,
+    width: usize,
+    height: usize,
+    raw: Vec<f64>,
+}
+
+impl Channel {
+    const NAME_TAG: Tag = 0x8050;
+    const RETRACE_TAG: Tag = 0x8051;
+    const FANCY_NAME_TAG: Tag = 0x8052;
+    const INFO_TAG: Tag = 0x8053;
+    const SLOT_COUNT_TAG: Tag = 0x8080;
+    const DEFAULT_SLOT_TAG: Tag = 0x8081;
+
+    /// Unsigned integer `SampleFormat`.
+    const SAMPLE_FORMAT_UINT: u64 = 1;
+    /// Signed integer `SampleFormat`.
+    const SAMPLE_FORMAT_INT: u64 = 2;
+    /// Floating point `SampleFormat`.
+    const SAMPLE_FORMAT_FLOAT: u64 = 3;
+}
+
+impl Channel {
+    /// # Returns
+    /// If the IFD holds channel data.
+    pub fn is_channel_ifd(ifd: &Ifd) -> bool {
+        ifd.contains(Self::NAME_TAG)
+    }
+
+    /// # Arguments
+    /// + `tiff`: Image file.
+    /// + `index`: Index of the IFD holding the channel.
+    pub fn from_tiff(tiff: &tiff::Tiff, index: usize) -> Result<Self, error::Image> {
+        let ifd = tiff
+            .ifds()
+            .get(index)
+            .ok_or(error::Image::Tiff(tiff::error::Tiff::IfdNotFound(index)))?;
+        let tag_error = |tag| error::Image::InvalidTag { ifd: index, tag };
+
+        let name = ifd
+            .get_str(Self::NAME_TAG)
+            .ok_or(tag_error(Self::NAME_TAG))?
+            .to_string();
+        let retrace = ifd.get_i64(Self::RETRACE_TAG).unwrap_or(0) != 0;
+
+        let slots_count = ifd
+            .get_u64(Self::SLOT_COUNT_TAG)
+            .map(|count| u16::try_from(count).map_err(|_| tag_error(Self::SLOT_COUNT_TAG)))
+            .transpose()?
+            .unwrap_or(0);
+        let slots = (0..slots_count)
+            .map(|slot| Slot::from_ifd(ifd, slot).map_err(tag_error))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let width = ifd
+            .get_u64(tiff::IMAGE_WIDTH)
+            .ok_or(tag_error(tiff::IMAGE_WIDTH))? as usize;
+        let height = ifd
+            .get_u64(tiff::IMAGE_LENGTH)
+            .ok_or(tag_error(tiff::IMAGE_LENGTH))? as usize;
+        let raw = Self::decode(tiff, index)?;
+        if raw.len() != width * height {
+            return Err(error::Image::InvalidDataLength { ifd: index });
+        }
+
+        Ok(Self {
+            name,
+            fancy_name: ifd.get_str(Self::FANCY_NAME_TAG).map(String::from),
+            retrace,
+            info: ifd.get_str(Self::INFO_TAG).map(String::from),
+            default_slot: ifd.get_str(Self::DEFAULT_SLOT_TAG).map(String::from),
+            slots,
+            width,
+            height,
+            raw,
+        })
+    }
+
+    /// Decode the raw samples of the IFD at `index`.
+    fn decode(tiff: &tiff::Tiff, index: usize) -> Result<Vec<f64>, error::Image> {
+        let ifd = &tiff.ifds()[index];
+        let samples_per_pixel = ifd.get_u64(tiff::SAMPLES_PER_PIXEL).unwrap_or(1);
+        let bits = ifd.get_u64(tiff::BITS_PER_SAMPLE).unwrap_or(1);
+        let format = ifd
+            .get_u64(tiff::SAMPLE_FORMAT)
+            .unwrap_or(Self::SAMPLE_FORMAT_UINT);
+        if samples_per_pixel != 1 {
+            return Err(error::Image::UnsupportedSampleFormat { ifd: index });
+        }
+
+        let data = tiff.strip_data(index)?;
+        let big_endian = tiff.byte_order() == tiff::ByteOrder::BigEndian;
+        macro_rules! decode {
+            ($ty:ty) => {
+                data.chunks_exact(size_of::<$ty>())
+                    .map(|chunk| {
+                        let bytes = chunk.try_into().expect("chunk has correct length");
+                        let value = if big_endian {
+                            <$ty>::from_be_bytes(bytes)
+                        } else {
+                            <$ty>::from_le_bytes(bytes)
+                        };
+                        value as f64
+                    })
+                    .collect()
+            };
+        }
+
+        let values = match (format, bits) {
+            (Self::SAMPLE_FORMAT_UINT, 8) => decode!(u8),
+            (Self::SAMPLE_FORMAT_UINT, 16) => decode!(u16),
+            (Self::SAMPLE_FORMAT_UINT, 32) => decode!(u32),
+            (Self::SAMPLE_FORMAT_INT, 8) => decode!(i8),
+            (Self::SAMPLE_FORMAT_INT, 16) => decode!(i16),
+            (Self::SAMPLE_FORMAT_INT, 32) => decode!(i32),
+            (Self::SAMPLE_FORMAT_FLOAT, 32) => decode!(f32),
+            (Self::SAMPLE_FORMAT_FLOAT, 64) => decode!(f64),
+            _ => return Err(error::Image::UnsupportedSampleFormat { ifd: index }),
+        };
+
+        Ok(values)
+    }
+}
+
+impl Channel {
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Display name of the channel.
+    pub fn fancy_name(&self) -> Option<&String> {
+        self.fancy_name.as_ref()
+    }
+
+    /// If the channel was recorded on the retrace.
+    pub fn retrace(&self) -> bool {
+        self.retrace
+    }
+
+    /// Free-form information about how the channel was computed.
+    pub fn info(&self) -> Option<&String> {
+        self.info.as_ref()
+    }
+
+    pub fn default_slot(&self) -> Option<&String> {
+        self.default_slot.as_ref()
+    }
+
+    pub fn slots(&self) -> &Vec<Slot> {
+        &self.slots
+    }
+
+    pub fn slot(&self, name: impl AsRef<str>) -> Option<&Slot> {
+        let name = name.as_ref();
+        self.slots.iter().find(|slot| slot.name == name)
+    }
+
+    /// Number of pixels along the `i` axis.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of pixels along the `j` axis.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Raw values in row-major order.
+    pub fn raw(&self) -> &Vec<f64> {
+        &self.raw
+    }
+
+    /// Channel data converted to a calibration slot.
+    ///
+    /// # Arguments
+    /// + `slot`: Calibration slot to convert the data to.
+    ///   If `None` the default slot is used.
+    pub fn data(&self, slot: Option<&str>) -> Result<ChannelData, error::Slot> {
+        let slot = match slot.or(self.default_slot.as_deref()) {
+            None => None,
+            Some(name) => Some(
+                self.slot(name)
+                    .ok_or_else(|| error::Slot::NotFound(name.to_string()))?,
+            ),
+        };
+
+        let (data, unit) = match slot {
+            None => (self.raw.clone(), None),
+            Some(slot) => {
+                let data = self.raw.iter().map(|v| slot.scaling.apply(*v)).collect();
+                (data, slot.unit.clone())
+            }
+        };
+
+        Ok(ChannelData {
+            width: self.width,
+            height: self.height,
+            data,
+            unit,
+        })
+    }
+}
+
+/// Two dimensional channel data.
+/// Values are stored in row-major order, with rows along the `i` axis.
+pub struct ChannelData {
+    width: usize,
+    height: usize,
+    data: Vec<f64>,
+    unit: Option<String>,
+}
+
+impl ChannelData {
+    /// Number of pixels along the `i` axis.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of pixels along the `j` axis.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn unit(&self) -> Option<&String> {
+        self.unit.as_ref()
+    }
+
+    /// Value at pixel `(i, j)`.
+    pub fn get(&self, i: usize, j: usize) -> Option<f64> {
+        if i >= self.width {
+            return None;
+        }
+        self.data.get(j * self.width + i).copied()
+    }
+
+    /// Values of row `j`.
+    pub fn row(&self, j: usize) -> Option<&[f64]> {
+        let start = j.checked_mul(self.width)?;
+        self.data.get(start..start + self.width)
+    }
+
+    pub fn data(&self) -> &Vec<f64> {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<f64> {
+        self.data
+    }
+}
+
+/// JPK image.
+pub struct Image {
+    metadata: Metadata,
+    grid: Option<Grid>,
+    channels: Vec<Channel>,
+    thumbnail: Option<Preview>,
+}
+
+impl Image {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, error::Image> {
+        let path = path.into();
+        let data = fs::read(&path).map_err(|error| error::Image::OpenFile {
+            path: path.clone(),
+            error,
+        })?;
+        Self::from_bytes_at(&data, path)
+    }
+
+    pub fn from_reader(mut reader: impl io::Read) -> Result<Self, error::Image> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::from_bytes(&data)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, error::Image> {
+        Self::from_bytes_at(data, PathBuf::new())
+    }
+
+    /// # Arguments
+    /// + `path`: Path of the image file, used as the path of the thumbnail.
+    fn from_bytes_at(data: &[u8], path: PathBuf) -> Result<Self, error::Image> {
+        let tiff = tiff::Tiff::parse(data)?;
+        let ifd = tiff
+            .ifds()
+            .get(METADATA_IFD)
+            .ok_or(tiff::error::Tiff::IfdNotFound(METADATA_IFD))?;
+
+        let metadata = Metadata::from_ifd(ifd);
+        let grid = Grid::from_ifd(ifd).map_err(|tag| error::Image::InvalidTag {
+            ifd: METADATA_IFD,
+            tag,
+        })?;
+        let channels = tiff
+            .ifds()
+            .iter()
+            .enumerate()
+            .filter(|(_, ifd)| Channel::is_channel_ifd(ifd))
+            .map(|(index, _)| Channel::from_tiff(&tiff, index))
+            .collect::<Result<Vec<_>, _>>()?;
+        let thumbnail = thumbnail(&tiff, path);
+
+        Ok(Self {
+            metadata,
+            grid,
+            channels,
+            thumbnail,
+        })
+    }
+}
+
+impl Image {
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Scan grid, if present.
+    pub fn grid(&self) -> Option<&Grid> {
+        self.grid.as_ref()
+    }
+
+    pub fn channels(&self) -> &Vec<Channel> {
+        &self.channels
+    }
+
+    /// Thumbnail of the first IFD, encoded as a bitmap.
+    /// Its path is the path of the image file, empty if the image was not opened from a file.
+    pub fn thumbnail(&self) -> Option<&Preview> {
+        self.thumbnail.as_ref()
+    }
+
+    /// # Arguments
+    /// + `name`: Channel name.
+    /// + `retrace`: Whether to get the trace or retrace channel.
+    pub fn channel(&self, name: impl AsRef<str>, retrace: bool) -> Option<&Channel> {
+        let name = name.as_ref();
+        self.channels
+            .iter()
+            .find(|channel| channel.name == name && channel.retrace == retrace)
+    }
+}
+
+/// Thumbnail of an image file.
+///
+/// # Arguments
+/// + `data`: Image file.
+/// + `path`: Path of the image file.
+///
+/// # Returns
+/// `None` if `data` is not a TIFF file or its first IFD does not hold a supported thumbnail.
+pub(crate) fn read_thumbnail(data: &[u8], path: PathBuf) -> Option<Preview> {
+    let tiff = tiff::Tiff::parse(data).ok()?;
+    thumbnail(&tiff, path)
+}
+
+/// Thumbnail of the first IFD, re-encoded as a bitmap.
+///
+/// # Returns
+/// `None` if the IFD does not hold an uncompressed 8 bit grayscale, RGB or palette image.
+fn thumbnail(tiff: &tiff::Tiff, path: PathBuf) -> Option<Preview> {
+    /// `PhotometricInterpretation` of grayscale images where zero is white.
+    const WHITE_IS_ZERO: u64 = 0;
+    /// `PhotometricInterpretation` of grayscale images where zero is black.
+    const BLACK_IS_ZERO: u64 = 1;
+    const RGB: u64 = 2;
+    const PALETTE: u64 = 3;
+
+    let ifd = tiff.ifds().get(THUMBNAIL_IFD)?;
+    let width = u32::try_from(ifd.get_u64(tiff::IMAGE_WIDTH)?).ok()?;
+    let height = u32::try_from(ifd.get_u64(tiff::IMAGE_LENGTH)?).ok()?;
+    if ifd.get_u64(tiff::BITS_PER_SAMPLE).unwrap_or(1) != 8 {
+        return None;
+    }
+
+    let samples = tiff.strip_data(THUMBNAIL_IFD).ok()?;
+    let pixels = width as usize * height as usize;
+    let rgb = match ifd.get_u64(tiff::PHOTOMETRIC_INTERPRETATION)? {
+        WHITE_IS_ZERO => samples
+            .get(..pixels)?
+            .iter()
+            .map(|v| [255 - v; 3])
+            .collect(),
+        BLACK_IS_ZERO => samples.get(..pixels)?.iter().map(|v| [*v; 3]).collect(),
+        RGB => samples
+            .get(..pixels * 3)?
+            .chunks_exact(3)
+            .map(|rgb| [rgb[0], rgb[1], rgb[2]])
+            .collect(),
+        PALETTE => {
+            // red, green and blue 16 bit entries for each of the 256 values
+            let colors = ifd.get(tiff::COLOR_MAP)?.as_u64_vec()?;
+            if colors.len() != 3 * 256 {
+                return None;
+            }
+            let channel = |offset: usize, value: u8| (colors[offset + value as usize] >> 8) as u8;
+            samples
+                .get(..pixels)?
+                .iter()
+                .map(|v| [channel(0, *v), channel(256, *v), channel(512, *v)])
+                .collect::<Vec<_>>()
+        }
+        _ => return None,
+    };
+
+    Preview::new(path, preview::encode_bmp(width, height, &rgb))
+}
+
+pub mod error {
+    use super::{Tag, tiff};
+    use std::{fmt, io, path::PathBuf};
+
+    #[derive(derive_more::From, Debug)]
+    pub enum Image {
+        #[from(ignore)]
+        OpenFile {
+            path: PathBuf,
+            error: io::Error,
+        },
+        Io(io::Error),
+        Tiff(tiff::error::Tiff),
+
+        /// A JPK tag of an IFD is missing or invalid.
+        #[from(ignore)]
+        InvalidTag {
+            ifd: usize,
+            tag: Tag,
+        },
+
+        /// The samples of an IFD are not a single numeric value per pixel.
+        #[from(ignore)]
+        UnsupportedSampleFormat {
+            ifd: usize,
+        },
+
+        /// The number of samples of an IFD does not match its dimensions.
+        #[from(ignore)]
+        InvalidDataLength {
+            ifd: usize,
+        },
+    }
+
+    impl fmt::Display for Image {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::OpenFile { path, .. } => write!(f, "could not read `{}`", path.display()),
+                Self::Io(_) => write!(f, "could not read image"),
+                Self::Tiff(_) => write!(f, "invalid image"),
+                Self::InvalidTag { ifd, tag } => {
+                    write!(f, "tag {tag:#06x} of ifd {ifd} is missing or invalid")
+                }
+                Self::UnsupportedSampleFormat { ifd } => {
+                    write!(f, "unsupported sample format of ifd {ifd}")
+                }
+                Self::InvalidDataLength { ifd } => {
+                    write!(f, "data of ifd {ifd} does not match the image size")
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for Image {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::OpenFile { error, .. } => Some(error),
+                Self::Io(err) => Some(err),
+                Self::Tiff(err) => Some(err),
+                Self::InvalidTag { .. }
+                | Self::UnsupportedSampleFormat { .. }
+                | Self::InvalidDataLength { .. } => None,
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub enum Slot {
+        /// The calibration slot does not exist for the channel.
+        NotFound(String),
+    }
+
+    impl fmt::Display for Slot {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::NotFound(slot) => write!(f, "calibration slot `{slot}` not found"),
+            }
+        }
+    }
+
+    impl std::error::Error for Slot {}
+}
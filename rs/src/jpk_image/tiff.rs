@@ -0,0 +1,474 @@
+//! Minimal TIFF parser.
+//! Reads the image file directories (IFDs) and uncompressed strip data.
+use std::collections::BTreeMap;
+
+pub type Tag = u16;
+
+/// `ImageWidth`
+pub const IMAGE_WIDTH: Tag = 0x0100;
+/// `ImageLength`
+pub const IMAGE_LENGTH: Tag = 0x0101;
+/// `BitsPerSample`
+pub const BITS_PER_SAMPLE: Tag = 0x0102;
+/// `Compression`
+pub const COMPRESSION: Tag = 0x0103;
+/// `PhotometricInterpretation`
+pub const PHOTOMETRIC_INTERPRETATION: Tag = 0x0106;
+/// `StripOffsets`
+pub const STRIP_OFFSETS: Tag = 0x0111;
+/// `SamplesPerPixel`
+pub const SAMPLES_PER_PIXEL: Tag = 0x0115;
+/// `StripByteCounts`
+pub const STRIP_BYTE_COUNTS: Tag = 0x0117;
+/// `ColorMap`
+pub const COLOR_MAP: Tag = 0x0140;
+/// `SampleFormat`
+pub const SAMPLE_FORMAT: Tag = 0x0153;
+
+/// `Compression` value of uncompressed data.
+const COMPRESSION_NONE: u64 = 1;
+
+/// Maximum number of IFDs read, guarding against cyclic offsets.
+const MAX_IFDS: usize = 4096;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ByteOrder {
+    /// `II`
+    LittleEndian,
+    /// `MM`
+    BigEndian,
+}
+
+/// Value of an IFD entry.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    Byte(Vec<u8>),
+    Ascii(String),
+    Short(Vec<u16>),
+    Long(Vec<u32>),
+    /// `(numerator, denominator)`
+    Rational(Vec<(u32, u32)>),
+    SByte(Vec<i8>),
+    Undefined(Vec<u8>),
+    SShort(Vec<i16>),
+    SLong(Vec<i32>),
+    /// `(numerator, denominator)`
+    SRational(Vec<(i32, i32)>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+}
+
+impl Value {
+    /// # Returns
+    /// The first value as an unsigned integer,
+    /// or `None` if the value is not an integer type, is negative, or is empty.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_u64_vec()?.first().copied()
+    }
+
+    /// # Returns
+    /// The values as unsigned integers,
+    /// or `None` if the value is not an integer type or is negative.
+    pub fn as_u64_vec(&self) -> Option<Vec<u64>> {
+        match self {
+            Self::Byte(values) => Some(values.iter().map(|v| *v as u64).collect()),
+            Self::Short(values) => Some(values.iter().map(|v| *v as u64).collect()),
+            Self::Long(values) => Some(values.iter().map(|v| *v as u64).collect()),
+            Self::SByte(values) => values.iter().map(|v| u64::try_from(*v).ok()).collect(),
+            Self::SShort(values) => values.iter().map(|v| u64::try_from(*v).ok()).collect(),
+            Self::SLong(values) => values.iter().map(|v| u64::try_from(*v).ok()).collect(),
+            _ => None,
+        }
+    }
+
+    /// # Returns
+    /// The first value as a signed integer,
+    /// or `None` if the value is not an integer type or is empty.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Byte(values) => values.first().map(|v| *v as i64),
+            Self::Short(values) => values.first().map(|v| *v as i64),
+            Self::Long(values) => values.first().map(|v| *v as i64),
+            Self::SByte(values) => values.first().map(|v| *v as i64),
+            Self::SShort(values) => values.first().map(|v| *v as i64),
+            Self::SLong(values) => values.first().map(|v| *v as i64),
+            _ => None,
+        }
+    }
+
+    /// # Returns
+    /// The first value as a float,
+    /// or `None` if the value is not numeric or is empty.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Float(values) => values.first().map(|v| *v as f64),
+            Self::Double(values) => values.first().copied(),
+            Self::Rational(values) => values.first().map(|(n, d)| *n as f64 / *d as f64),
+            Self::SRational(values) => values.first().map(|(n, d)| *n as f64 / *d as f64),
+            _ => self.as_i64().map(|v| v as f64),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Ascii(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Image file directory.
+#[derive(Clone, Default, Debug)]
+pub struct Ifd {
+    entries: BTreeMap<Tag, Value>,
+}
+
+impl Ifd {
+    pub fn get(&self, tag: Tag) -> Option<&Value> {
+        self.entries.get(&tag)
+    }
+
+    pub fn contains(&self, tag: Tag) -> bool {
+        self.entries.contains_key(&tag)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Tag, &Value)> {
+        self.entries.iter().map(|(tag, value)| (*tag, value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get_str(&self, tag: Tag) -> Option<&str> {
+        self.get(tag)?.as_str()
+    }
+
+    pub fn get_u64(&self, tag: Tag) -> Option<u64> {
+        self.get(tag)?.as_u64()
+    }
+
+    pub fn get_i64(&self, tag: Tag) -> Option<i64> {
+        self.get(tag)?.as_i64()
+    }
+
+    pub fn get_f64(&self, tag: Tag) -> Option<f64> {
+        self.get(tag)?.as_f64()
+    }
+}
+
+/// Parsed TIFF file.
+/// Holds the raw file to read strip data from.
+pub struct Tiff<'a> {
+    data: &'a [u8],
+    byte_order: ByteOrder,
+    ifds: Vec<Ifd>,
+}
+
+impl<'a> Tiff<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, error::Tiff> {
+        let byte_order = match data.get(..2) {
+            Some(b"II") => ByteOrder::LittleEndian,
+            Some(b"MM") => ByteOrder::BigEndian,
+            _ => return Err(error::Tiff::InvalidHeader),
+        };
+        let reader = ByteReader { data, byte_order };
+        if reader.u16(2)? != 42 {
+            return Err(error::Tiff::InvalidHeader);
+        }
+
+        let mut ifds = Vec::new();
+        let mut offset = reader.u32(4)? as usize;
+        while offset != 0 {
+            if ifds.len() == MAX_IFDS {
+                return Err(error::Tiff::TooManyIfds);
+            }
+
+            let (ifd, next) = reader.ifd(offset)?;
+            ifds.push(ifd);
+            offset = next;
+        }
+
+        Ok(Self {
+            data,
+            byte_order,
+            ifds,
+        })
+    }
+
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    pub fn ifds(&self) -> &Vec<Ifd> {
+        &self.ifds
+    }
+
+    /// Concatenated strip data of the IFD at `index`.
+    pub fn strip_data(&self, index: usize) -> Result<Vec<u8>, error::Tiff> {
+        let ifd = self
+            .ifds
+            .get(index)
+            .ok_or(error::Tiff::IfdNotFound(index))?;
+        let compression = ifd.get_u64(COMPRESSION).unwrap_or(COMPRESSION_NONE);
+        if compression != COMPRESSION_NONE {
+            return Err(error::Tiff::UnsupportedCompression(compression));
+        }
+
+        let offsets = ifd
+            .get(STRIP_OFFSETS)
+            .and_then(Value::as_u64_vec)
+            .ok_or(error::Tiff::MissingTag(STRIP_OFFSETS))?;
+        let counts = ifd
+            .get(STRIP_BYTE_COUNTS)
+            .and_then(Value::as_u64_vec)
+            .ok_or(error::Tiff::MissingTag(STRIP_BYTE_COUNTS))?;
+        if offsets.len() != counts.len() {
+            return Err(error::Tiff::InvalidTag(STRIP_BYTE_COUNTS));
+        }
+
+        let capacity = counts.iter().sum::<u64>().min(self.data.len() as u64);
+        let mut strips = Vec::with_capacity(capacity as usize);
+        for (offset, count) in offsets.into_iter().zip(counts) {
+            let start = offset as usize;
+            let end = start.saturating_add(count as usize);
+            let strip = self
+                .data
+                .get(start..end)
+                .ok_or(error::Tiff::UnexpectedEof { offset: start })?;
+            strips.extend_from_slice(strip);
+        }
+
+        Ok(strips)
+    }
+}
+
+/// Reads values at offsets of the file in its byte order.
+struct ByteReader<'a> {
+    data: &'a [u8],
+    byte_order: ByteOrder,
+}
+
+impl ByteReader<'_> {
+    fn bytes<const N: usize>(&self, offset: usize) -> Result<[u8; N], error::Tiff> {
+        let bytes = self
+            .data
+            .get(offset..offset.saturating_add(N))
+            .ok_or(error::Tiff::UnexpectedEof { offset })?;
+        let mut bytes: [u8; N] = bytes.try_into().expect("slice has correct length");
+        if self.byte_order == ByteOrder::LittleEndian {
+            bytes.reverse();
+        }
+        Ok(bytes)
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16, error::Tiff> {
+        Ok(u16::from_be_bytes(self.bytes(offset)?))
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, error::Tiff> {
+        Ok(u32::from_be_bytes(self.bytes(offset)?))
+    }
+
+    fn u64(&self, offset: usize) -> Result<u64, error::Tiff> {
+        Ok(u64::from_be_bytes(self.bytes(offset)?))
+    }
+
+    /// # Returns
+    /// `(ifd, next ifd offset)`
+    fn ifd(&self, offset: usize) -> Result<(Ifd, usize), error::Tiff> {
+        const ENTRY_SIZE: usize = 12;
+
+        let count = self.u16(offset)? as usize;
+        let mut entries = BTreeMap::new();
+        for idx in 0..count {
+            let entry = offset + 2 + idx * ENTRY_SIZE;
+            let tag = self.u16(entry)?;
+            let kind = self.u16(entry + 2)?;
+            let count = self.u32(entry + 4)? as usize;
+            let Some(size) = type_size(kind) else {
+                // Unknown types must be skipped.
+                continue;
+            };
+
+            let len = size
+                .checked_mul(count)
+                .ok_or(error::Tiff::UnexpectedEof { offset: entry })?;
+            let value_offset = if len <= 4 {
+                entry + 8
+            } else {
+                self.u32(entry + 8)? as usize
+            };
+            let value = self.value(kind, count, value_offset)?;
+            entries.insert(tag, value);
+        }
+
+        let next = self.u32(offset + 2 + count * ENTRY_SIZE)? as usize;
+        Ok((Ifd { entries }, next))
+    }
+
+    fn value(&self, kind: u16, count: usize, offset: usize) -> Result<Value, error::Tiff> {
+        let size = type_size(kind).expect("type is known");
+        let raw = self
+            .data
+            .get(offset..offset.saturating_add(size * count))
+            .ok_or(error::Tiff::UnexpectedEof { offset })?;
+
+        let value = match kind {
+            1 => Value::Byte(raw.to_vec()),
+            2 => {
+                let end = raw.iter().position(|c| *c == 0).unwrap_or(raw.len());
+                Value::Ascii(String::from_utf8_lossy(&raw[..end]).into_owned())
+            }
+            3 => Value::Short(self.collect(count, offset, 2, |o| self.u16(o))?),
+            4 => Value::Long(self.collect(count, offset, 4, |o| self.u32(o))?),
+            5 => Value::Rational(
+                self.collect(count, offset, 8, |o| Ok((self.u32(o)?, self.u32(o + 4)?)))?,
+            ),
+            6 => Value::SByte(raw.iter().map(|v| *v as i8).collect()),
+            7 => Value::Undefined(raw.to_vec()),
+            8 => Value::SShort(self.collect(count, offset, 2, |o| Ok(self.u16(o)? as i16))?),
+            9 => Value::SLong(self.collect(count, offset, 4, |o| Ok(self.u32(o)? as i32))?),
+            10 => Value::SRational(self.collect(count, offset, 8, |o| {
+                Ok((self.u32(o)? as i32, self.u32(o + 4)? as i32))
+            })?),
+            11 => {
+                Value::Float(self.collect(count, offset, 4, |o| Ok(f32::from_bits(self.u32(o)?)))?)
+            }
+            12 => {
+                Value::Double(self.collect(count, offset, 8, |o| Ok(f64::from_bits(self.u64(o)?)))?)
+            }
+            _ => unreachable!("type is known"),
+        };
+
+        Ok(value)
+    }
+
+    fn collect<T>(
+        &self,
+        count: usize,
+        offset: usize,
+        size: usize,
+        read: impl Fn(usize) -> Result<T, error::Tiff>,
+    ) -> Result<Vec<T>, error::Tiff> {
+        (0..count).map(|idx| read(offset + idx * size)).collect()
+    }
+}
+
+/// Size in bytes of a single value of the given field type.
+fn type_size(kind: u16) -> Option<usize> {
+    match kind {
+        1 | 2 | 6 | 7 => Some(1),
+        3 | 8 => Some(2),
+        4 | 9 | 11 => Some(4),
+        5 | 10 | 12 => Some(8),
+        _ => None,
+    }
+}
+
+pub mod error {
+    use super::Tag;
+    use std::fmt;
+
+    #[derive(Debug)]
+    pub enum Tiff {
+        /// The file does not start with a TIFF header.
+        InvalidHeader,
+
+        /// A value extends past the end of the file.
+        UnexpectedEof { offset: usize },
+
+        /// The file has more IFDs than can be read, likely due to a cycle.
+        TooManyIfds,
+
+        /// The IFD with the given index does not exist.
+        IfdNotFound(usize),
+
+        /// A required tag is missing.
+        MissingTag(Tag),
+
+        /// A tag has an invalid value.
+        InvalidTag(Tag),
+
+        /// The image data is compressed.
+        UnsupportedCompression(u64),
+    }
+
+    impl fmt::Display for Tiff {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::InvalidHeader => write!(f, "invalid tiff header"),
+                Self::UnexpectedEof { offset } => {
+                    write!(f, "unexpected end of file reading offset {offset}")
+                }
+                Self::TooManyIfds => write!(f, "too many image file directories"),
+                Self::IfdNotFound(index) => write!(f, "image file directory {index} not found"),
+                Self::MissingTag(tag) => write!(f, "tag {tag:#06x} not found"),
+                Self::InvalidTag(tag) => write!(f, "invalid value of tag {tag:#06x}"),
+                Self::UnsupportedCompression(compression) => {
+                    write!(f, "unsupported compression {compression}")
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for Tiff {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tiff_parse_little_endian() {
+        // 2x1 image of u16 samples, with a double tag stored out of line.
+        let mut data = Vec::new();
+        data.extend_from_slice(b"II");
+        data.extend_from_slice(&42u16.to_le_bytes());
+        data.extend_from_slice(&8u32.to_le_bytes());
+
+        let entries: [(u16, u16, u32, u32); 5] = [
+            (IMAGE_WIDTH, 3, 1, 2),
+            (IMAGE_LENGTH, 3, 1, 1),
+            (STRIP_OFFSETS, 4, 1, 74),
+            (STRIP_BYTE_COUNTS, 4, 1, 4),
+            (0x8040, 12, 1, 78),
+        ];
+        data.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for (tag, kind, count, value) in entries {
+            data.extend_from_slice(&tag.to_le_bytes());
+            data.extend_from_slice(&kind.to_le_bytes());
+            data.extend_from_slice(&count.to_le_bytes());
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&0u32.to_le_bytes());
+        assert_eq!(data.len(), 74);
+        data.extend_from_slice(&[1, 0, 2, 0]);
+        data.extend_from_slice(&1.5f64.to_le_bytes());
+
+        let tiff = Tiff::parse(&data).unwrap();
+        assert_eq!(tiff.byte_order(), ByteOrder::LittleEndian);
+        assert_eq!(tiff.ifds().len(), 1);
+
+        let ifd = &tiff.ifds()[0];
+        assert_eq!(ifd.get_u64(IMAGE_WIDTH), Some(2));
+        assert_eq!(ifd.get_f64(0x8040), Some(1.5));
+        assert_eq!(tiff.strip_data(0).unwrap(), vec![1, 0, 2, 0]);
+    }
+
+    #[test]
+    fn tiff_parse_invalid() {
+        assert!(matches!(
+            Tiff::parse(b"PK\x03\x04"),
+            Err(error::Tiff::InvalidHeader)
+        ));
+        assert!(matches!(
+            Tiff::parse(b"MM\x00\x2a\x00\x00\x00\xff"),
+            Err(error::Tiff::UnexpectedEof { .. })
+        ));
+    }
+}
@@ -1,14 +1,31 @@
 //! Read various data produced by JPK AFM.
 
+#[cfg(feature = "analysis")]
+pub mod analysis;
 pub mod dataset;
+pub mod detect;
 
-// #[cfg(feature = "qi_map")]
-// pub mod qi_map;
+#[cfg(feature = "force_curve")]
+pub mod force_curve;
+#[cfg(feature = "jpk_export")]
+pub mod jpk_export;
+#[cfg(feature = "jpk_image")]
+pub mod jpk_image;
+#[cfg(feature = "qi_map")]
+pub mod qi_map;
 #[cfg(feature = "scope")]
 pub mod scope;
+#[cfg(any(
+    feature = "voltage_spectroscopy",
+    feature = "force_curve",
+    feature = "jpk_export"
+))]
+mod segment_series;
 #[cfg(feature = "voltage_spectroscopy")]
 pub mod voltage_spectroscopy;
 
+pub use detect::{JpkFile, open};
+
 pub trait ArchiveReader {
     /// List of files in the archive.
     fn files(&self) -> Vec<&str>;
@@ -1,14 +1,25 @@
-//! QI map data reader.
-//! (`.jpk-qi-data`)
-use properties::Properties;
+//! QI map and force map data reader.
+//! (`.jpk-qi-data`, `.jpk-force-map`)
+use crate::dataset::{
+    self, DatasetError,
+    preview::Preview,
+    properties::{Properties, error::Property as PropertyError},
+    v2_0::DatasetReader,
+};
 use std::{
     cmp,
     collections::HashMap,
-    fmt, fs, io,
+    fmt, io,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
-mod v2_0;
+pub mod v2_0;
+
+pub use crate::dataset::{
+    FormatVersion,
+    index::{IndexQuery, Pixel, PixelRect, PixelRectIter},
+};
 
 type Value = f64;
 type IndexType = u32;
@@ -16,51 +27,79 @@ type SegmentType = u8;
 type ChannelType = String;
 
 const DATASET_PROPERTIES_FILE_PATH: &str = "header.properties";
-const PROPERTIES_FILE_FORMAT_VERSION_KEY: &str = "file-format-version";
 
 pub trait QIMapReader {
     fn query_data(&mut self, query: &DataQuery) -> Result<Data, QueryError>;
     fn query_metadata(&mut self, query: &MetadataQuery) -> Result<Metadata, QueryError>;
+
+    /// Preview image of the map.
+    fn thumbnail(&mut self) -> Result<Option<Preview>, DatasetError>;
+
+    /// All preview images of the map.
+    fn previews(&mut self) -> Result<Vec<Preview>, DatasetError>;
 }
 
 pub struct Data {
     indices: Vec<DataIndex>,
     data: Vec<Vec<Value>>,
+    units: Vec<String>,
 }
 
 impl Data {
-    pub fn new(indices: Vec<DataIndex>, data: Vec<Vec<Value>>) -> Result<Self, InvalidDataIndices> {
-        if data.len() != indices.len() {
+    pub fn new(
+        indices: Vec<DataIndex>,
+        data: Vec<Vec<Value>>,
+        units: Vec<String>,
+    ) -> Result<Self, InvalidDataIndices> {
+        if data.len() != indices.len() || units.len() != indices.len() {
             return Err(InvalidDataIndices);
         }
-        let mut idx_map = (0..indices.len()).collect::<Vec<_>>();
-        idx_map.sort_unstable_by_key(|&idx| &indices[idx]);
-
-        let mut data = data.into_iter().enumerate().collect::<Vec<_>>();
-        let mut indices = indices.into_iter().enumerate().collect::<Vec<_>>();
-        data.sort_unstable_by_key(|(idx, _)| idx_map[*idx]);
-        indices.sort_unstable_by_key(|(idx, _)| idx_map[*idx]);
-        let data = data.into_iter().map(|(_, value)| value).collect::<Vec<_>>();
-        let indices = indices
+        let mut entries = indices
             .into_iter()
-            .map(|(_, value)| value)
+            .zip(data.into_iter().zip(units))
             .collect::<Vec<_>>();
-
-        Ok(Self { indices, data })
+        entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        let (indices, entries): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+        let (data, units) = entries.into_iter().unzip();
+
+        Ok(Self {
+            indices,
+            data,
+            units,
+        })
     }
 
     pub fn len(&self) -> usize {
         self.indices.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Indices of the data, ordered by `(index, segment, channel)`.
+    pub fn indices(&self) -> &Vec<DataIndex> {
+        &self.indices
+    }
+
     pub fn get(&self, index: &DataIndex) -> Option<&Vec<Value>> {
         let idx = self.indices.binary_search(index).ok()?;
         Some(&self.data[idx])
     }
 
-    pub fn into_parts(self) -> (Vec<DataIndex>, Vec<Vec<Value>>) {
-        let Data { indices, data } = self;
-        (indices, data)
+    /// Unit of the data at `index`.
+    pub fn unit(&self, index: &DataIndex) -> Option<&String> {
+        let idx = self.indices.binary_search(index).ok()?;
+        Some(&self.units[idx])
+    }
+
+    pub fn into_parts(self) -> (Vec<DataIndex>, Vec<Vec<Value>>, Vec<String>) {
+        let Data {
+            indices,
+            data,
+            units,
+        } = self;
+        (indices, data, units)
     }
 }
 
@@ -108,7 +147,8 @@ impl IntoIterator for Metadata {
 #[derive(Debug)]
 pub struct InvalidDataIndices;
 
-#[derive(Debug, PartialEq, Ord, Eq)]
+/// Ordered by `(index, segment, channel)`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct DataIndex {
     pub index: IndexType,
     pub segment: SegmentType,
@@ -125,25 +165,6 @@ impl DataIndex {
     }
 }
 
-impl PartialOrd for DataIndex {
-    /// Order by `(index, segment, channel)`.
-    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        if let Some(order) = self.index.partial_cmp(&other.index) {
-            if matches!(order, cmp::Ordering::Greater | cmp::Ordering::Less) {
-                return Some(order);
-            }
-        }
-
-        if let Some(order) = self.segment.partial_cmp(&other.segment) {
-            if matches!(order, cmp::Ordering::Greater | cmp::Ordering::Less) {
-                return Some(order);
-            }
-        }
-
-        self.channel.partial_cmp(&other.channel)
-    }
-}
-
 impl From<(IndexType, SegmentType, ChannelType)> for DataIndex {
     fn from(value: (IndexType, SegmentType, ChannelType)) -> Self {
         let (index, segment, channel) = value;
@@ -155,7 +176,7 @@ impl From<(IndexType, SegmentType, ChannelType)> for DataIndex {
     }
 }
 
-#[derive(Debug, PartialEq, Ord, Eq, Hash)]
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub enum MetadataIndex {
     Dataset,
     SharedData,
@@ -166,30 +187,26 @@ pub enum MetadataIndex {
     },
 }
 
-impl PartialOrd for MetadataIndex {
+impl Ord for MetadataIndex {
     /// Order hierarchically by `(dataset, shared data, index, segment)`.
-    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
         match (self, other) {
             (MetadataIndex::Dataset, MetadataIndex::Dataset)
-            | (MetadataIndex::SharedData, MetadataIndex::SharedData) => Some(cmp::Ordering::Equal),
+            | (MetadataIndex::SharedData, MetadataIndex::SharedData) => cmp::Ordering::Equal,
 
             (MetadataIndex::Dataset, MetadataIndex::SharedData)
             | (MetadataIndex::Dataset, MetadataIndex::Index(_))
             | (MetadataIndex::Dataset, MetadataIndex::Segment { .. })
             | (MetadataIndex::SharedData, MetadataIndex::Index(_))
-            | (MetadataIndex::SharedData, MetadataIndex::Segment { .. }) => {
-                Some(cmp::Ordering::Less)
-            }
+            | (MetadataIndex::SharedData, MetadataIndex::Segment { .. }) => cmp::Ordering::Less,
 
             (MetadataIndex::SharedData, MetadataIndex::Dataset)
             | (MetadataIndex::Index(_), MetadataIndex::Dataset)
             | (MetadataIndex::Index(_), MetadataIndex::SharedData)
             | (MetadataIndex::Segment { .. }, MetadataIndex::Dataset)
-            | (MetadataIndex::Segment { .. }, MetadataIndex::SharedData) => {
-                Some(cmp::Ordering::Greater)
-            }
+            | (MetadataIndex::Segment { .. }, MetadataIndex::SharedData) => cmp::Ordering::Greater,
 
-            (MetadataIndex::Index(a), MetadataIndex::Index(b)) => a.partial_cmp(b),
+            (MetadataIndex::Index(a), MetadataIndex::Index(b)) => a.cmp(b),
 
             (
                 MetadataIndex::Segment {
@@ -200,48 +217,32 @@ impl PartialOrd for MetadataIndex {
                     index: idx_b,
                     segment: segment_b,
                 },
-            ) => {
-                let mut ord = idx_a.partial_cmp(idx_b).unwrap();
-                if matches!(ord, cmp::Ordering::Equal) {
-                    ord = segment_a.partial_cmp(segment_b).unwrap();
-                }
-                Some(ord)
-            }
+            ) => idx_a.cmp(idx_b).then(segment_a.cmp(segment_b)),
 
-            (
-                MetadataIndex::Index(pa),
-                MetadataIndex::Segment {
-                    index: pb,
-                    segment: sb,
-                },
-            ) => {
-                let mut ord = pa.partial_cmp(pb).unwrap();
-                if matches!(ord, cmp::Ordering::Equal) {
-                    ord = cmp::Ordering::Less
-                }
-                Some(ord)
+            (MetadataIndex::Index(pa), MetadataIndex::Segment { index: pb, .. }) => {
+                pa.cmp(pb).then(cmp::Ordering::Less)
             }
-            (
-                MetadataIndex::Segment {
-                    index: pa,
-                    segment: sa,
-                },
-                MetadataIndex::Index(pb),
-            ) => {
-                let mut ord = pa.partial_cmp(pb).unwrap();
-                if matches!(ord, cmp::Ordering::Equal) {
-                    ord = cmp::Ordering::Greater
-                }
-                Some(ord)
+            (MetadataIndex::Segment { index: pa, .. }, MetadataIndex::Index(pb)) => {
+                pa.cmp(pb).then(cmp::Ordering::Greater)
             }
         }
     }
 }
 
+impl PartialOrd for MetadataIndex {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 pub struct DataQuery {
     pub index: IndexQuery,
     pub segment: SegmentQuery,
     pub channel: ChannelQuery,
+
+    /// Calibration slot to convert a channel's data to.
+    /// Channels not present are converted to their default slot.
+    pub calibration_slots: HashMap<ChannelType, String>,
 }
 
 impl DataQuery {
@@ -250,6 +251,7 @@ impl DataQuery {
             index: IndexQuery::All,
             segment: SegmentQuery::All,
             channel: ChannelQuery::All,
+            calibration_slots: HashMap::new(),
         }
     }
 }
@@ -266,78 +268,6 @@ pub enum MetadataQuery {
     },
 }
 
-pub enum IndexQuery {
-    All,
-    Index(IndexType),
-    PixelRect(PixelRect),
-    Pixel(Pixel),
-}
-
-pub struct PixelRect {
-    start: Pixel,
-    end: Pixel,
-}
-
-impl PixelRect {
-    pub fn new(start: Pixel, end: Pixel) -> Self {
-        let Pixel { i: xa, j: ya } = start;
-        let Pixel { i: xb, j: yb } = end;
-        let (x0, x1) = if xa < xb { (xa, xb) } else { (xb, xa) };
-        let (y0, y1) = if ya < yb { (ya, yb) } else { (yb, ya) };
-
-        let start = Pixel { i: x0, j: y0 };
-        let end = Pixel { i: x1, j: y1 };
-        Self { start, end }
-    }
-
-    pub fn rows(&self) -> IndexType {
-        self.end.j - self.start.j + 1
-    }
-
-    pub fn cols(&self) -> IndexType {
-        self.end.i - self.start.j + 1
-    }
-
-    pub fn iter(&self) -> PixelRectIter<'_> {
-        PixelRectIter::new(&self)
-    }
-}
-
-pub struct PixelRectIter<'a> {
-    inner: &'a PixelRect,
-    i: IndexType,
-    j: IndexType,
-}
-
-impl<'a> PixelRectIter<'a> {
-    pub fn new(inner: &'a PixelRect) -> Self {
-        Self {
-            inner,
-            i: inner.start.i,
-            j: inner.start.j,
-        }
-    }
-}
-
-impl<'a> std::iter::Iterator for PixelRectIter<'a> {
-    type Item = Pixel;
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.j > self.inner.end.j {
-            return None;
-        }
-
-        if self.i > self.inner.end.i {
-            self.i = self.inner.start.i;
-            self.j += 1;
-        }
-
-        Some(Pixel {
-            i: self.i,
-            j: self.j,
-        })
-    }
-}
-
 pub enum SegmentQuery {
     All,
     Indices(Vec<SegmentType>),
@@ -355,41 +285,6 @@ impl ChannelQuery {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Ord, Hash)]
-pub struct Pixel {
-    i: IndexType,
-    j: IndexType,
-}
-
-impl Pixel {
-    pub fn new(i: IndexType, j: IndexType) -> Self {
-        Self { i, j }
-    }
-
-    pub fn i(&self) -> IndexType {
-        self.i
-    }
-
-    pub fn j(&self) -> IndexType {
-        self.j
-    }
-
-    pub fn to_index(&self, cols: IndexType) -> IndexType {
-        self.j * cols + self.i
-    }
-}
-
-impl PartialOrd for Pixel {
-    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        let mut ord = self.i.partial_cmp(&other.j).unwrap();
-        if matches!(ord, cmp::Ordering::Equal) {
-            ord = self.j.partial_cmp(&other.j).unwrap();
-        }
-
-        Some(ord)
-    }
-}
-
 #[derive(Debug)]
 pub enum QueryError {
     /// The pixel coordinate is invalid.
@@ -397,38 +292,116 @@ pub enum QueryError {
 
     Zip(zip::result::ZipError),
 
-    /// Error reading the zip archive.
+    /// The archive at the given path could not be opened.
+    /// `error` is shared by every per-thread reader that failed to open it.
+    OpenArchive {
+        path: PathBuf,
+        error: Arc<io::Error>,
+    },
+
+    /// Error reading an entry of the archive.
+    ///
+    /// # Notes
+    /// + `archive` is the path of the archive, if read from a file.
+    /// + `path` is the path of the entry within the archive.
     ZipFile {
+        archive: Option<PathBuf>,
         path: PathBuf,
         error: zip::result::ZipError,
     },
 
     InvalidFormat {
+        archive: Option<PathBuf>,
         path: PathBuf,
         cause: String,
     },
 
+    /// A property of the file at the given path was missing or invalid.
+    Property {
+        archive: Option<PathBuf>,
+        path: PathBuf,
+        error: PropertyError,
+    },
+
+    /// The data file at the given path contained invalid data.
     InvalidData {
+        archive: Option<PathBuf>,
         path: PathBuf,
+        cause: String,
     },
+
+    /// The calibration slot does not exist for a channel.
+    InvalidCalibrationSlot(String),
+}
+
+impl QueryError {
+    /// Set the path of the archive the entry of the error belongs to.
+    pub(crate) fn in_archive(mut self, path: impl AsRef<Path>) -> Self {
+        match &mut self {
+            Self::ZipFile { archive, .. }
+            | Self::InvalidFormat { archive, .. }
+            | Self::Property { archive, .. }
+            | Self::InvalidData { archive, .. } => *archive = Some(path.as_ref().to_path_buf()),
+            Self::OutOfBounds(_)
+            | Self::Zip(_)
+            | Self::OpenArchive { .. }
+            | Self::InvalidCalibrationSlot(_) => {}
+        }
+        self
+    }
 }
 
 impl fmt::Display for QueryError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{self:?}")
-    }
-}
+        /// `path`, qualified by `archive` if known.
+        fn entry(archive: &Option<PathBuf>, path: &Path) -> String {
+            match archive {
+                Some(archive) => format!("`{}` in `{}`", path.display(), archive.display()),
+                None => format!("`{}`", path.display()),
+            }
+        }
 
-pub enum FormatVersion {
-    // 2.0
-    V2_0,
+        match self {
+            Self::OutOfBounds(pixel) => {
+                write!(f, "pixel ({}, {}) is out of bounds", pixel.i(), pixel.j())
+            }
+            Self::Zip(_) => write!(f, "could not read archive"),
+            Self::OpenArchive { path, .. } => {
+                write!(f, "could not open archive `{}`", path.display())
+            }
+            Self::ZipFile { archive, path, .. } => {
+                write!(f, "could not read {}", entry(archive, path))
+            }
+            Self::InvalidFormat {
+                archive,
+                path,
+                cause,
+            } => write!(f, "invalid format of {}: {cause}", entry(archive, path)),
+            Self::Property { archive, path, .. } => {
+                write!(f, "invalid property in {}", entry(archive, path))
+            }
+            Self::InvalidData {
+                archive,
+                path,
+                cause,
+            } => write!(f, "invalid data in {}: {cause}", entry(archive, path)),
+            Self::InvalidCalibrationSlot(slot) => {
+                write!(f, "calibration slot `{slot}` does not exist")
+            }
+        }
+    }
 }
 
-impl FormatVersion {
-    pub fn from_str(v: impl AsRef<str>) -> Option<FormatVersion> {
-        match v.as_ref() {
-            "2.0" => Some(Self::V2_0),
-            _ => None,
+impl std::error::Error for QueryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Zip(error) | Self::ZipFile { error, .. } => Some(error),
+            Self::OpenArchive { error, .. } => Some(error.as_ref()),
+            Self::Property { error, .. } => Some(error),
+            Self::OutOfBounds(_)
+            | Self::InvalidFormat { .. }
+            | Self::InvalidData { .. }
+            | Self::InvalidCalibrationSlot(_) => None,
         }
     }
 }
@@ -450,6 +423,18 @@ impl QIMapReader for VersionedFileReader {
             VersionedFileReader::V2_0(reader) => reader.query_metadata(query),
         }
     }
+
+    fn thumbnail(&mut self) -> Result<Option<Preview>, DatasetError> {
+        match self {
+            VersionedFileReader::V2_0(reader) => reader.thumbnail(),
+        }
+    }
+
+    fn previews(&mut self) -> Result<Vec<Preview>, DatasetError> {
+        match self {
+            VersionedFileReader::V2_0(reader) => reader.previews(),
+        }
+    }
 }
 
 impl crate::ArchiveReader for VersionedFileReader {
@@ -486,6 +471,18 @@ where
             VersionedReader::V2_0(reader) => reader.query_metadata(query),
         }
     }
+
+    fn thumbnail(&mut self) -> Result<Option<Preview>, DatasetError> {
+        match self {
+            VersionedReader::V2_0(reader) => reader.thumbnail(),
+        }
+    }
+
+    fn previews(&mut self) -> Result<Vec<Preview>, DatasetError> {
+        match self {
+            VersionedReader::V2_0(reader) => reader.previews(),
+        }
+    }
 }
 
 impl<R> crate::ArchiveReader for VersionedReader<R>
@@ -510,17 +507,11 @@ impl FileReader {
     /// Create a new reader based on the format version.
     pub fn new(path: impl Into<PathBuf>) -> Result<impl QIMapReader, Error> {
         let path = path.into();
-        let file = fs::File::open(&path).map_err(|err| match err.kind() {
-            io::ErrorKind::NotFound => Error::OpenArchive(zip::result::ZipError::FileNotFound),
-            _ => Error::OpenArchive(zip::result::ZipError::Io(err)),
+        let mut archive = dataset::open_archive(&path).map_err(|error| Error::OpenFile {
+            path: path.clone(),
+            error,
         })?;
-        let mut archive = zip::ZipArchive::new(file)?;
-        let format_version = Reader::format_version(&mut archive)?;
-        let Some(format_version) = FormatVersion::from_str(&format_version) else {
-            return Err(Error::FileFormatNotSupported {
-                version: format_version.clone(),
-            });
-        };
+        let format_version = Reader::detect_format_version(&mut archive)?;
 
         match format_version {
             FormatVersion::V2_0 => unsafe { v2_0::FileReader::new_with_archive(path, archive) },
@@ -530,17 +521,11 @@ impl FileReader {
     /// Get a new JPK reader based on the format version.
     pub fn new_versioned(path: impl Into<PathBuf>) -> Result<VersionedFileReader, Error> {
         let path = path.into();
-        let file = fs::File::open(&path).map_err(|err| match err.kind() {
-            io::ErrorKind::NotFound => Error::OpenArchive(zip::result::ZipError::FileNotFound),
-            _ => Error::OpenArchive(zip::result::ZipError::Io(err)),
+        let mut archive = dataset::open_archive(&path).map_err(|error| Error::OpenFile {
+            path: path.clone(),
+            error,
         })?;
-        let mut archive = zip::ZipArchive::new(file)?;
-        let format_version = Reader::format_version(&mut archive)?;
-        let Some(format_version) = FormatVersion::from_str(&format_version) else {
-            return Err(Error::FileFormatNotSupported {
-                version: format_version.clone(),
-            });
-        };
+        let format_version = Reader::detect_format_version(&mut archive)?;
 
         let reader = match format_version {
             FormatVersion::V2_0 => {
@@ -553,34 +538,12 @@ impl FileReader {
 
     /// Get the JPK version format of the archive.
     pub fn format_version(path: impl AsRef<Path>) -> Result<String, Error> {
-        let file = fs::File::open(path).map_err(|err| match err.kind() {
-            io::ErrorKind::NotFound => Error::OpenArchive(zip::result::ZipError::FileNotFound),
-            _ => Error::OpenArchive(zip::result::ZipError::Io(err)),
+        let path = path.as_ref();
+        let mut archive = dataset::open_archive(path).map_err(|error| Error::OpenFile {
+            path: path.to_path_buf(),
+            error,
         })?;
-        let mut archive = zip::ZipArchive::new(file)?;
-        let properties = {
-            let mut properties =
-                archive
-                    .by_path(DATASET_PROPERTIES_FILE_PATH)
-                    .map_err(|error| Error::Zip {
-                        path: PathBuf::from(DATASET_PROPERTIES_FILE_PATH),
-                        error,
-                    })?;
-
-            Properties::new(&mut properties).map_err(|_err| Error::InvalidFormat {
-                path: PathBuf::from(DATASET_PROPERTIES_FILE_PATH),
-                cause: "invalid format".to_string(),
-            })?
-        };
-
-        let Some(format_version) = properties.get(PROPERTIES_FILE_FORMAT_VERSION_KEY) else {
-            return Err(Error::InvalidFormat {
-                path: PathBuf::from(DATASET_PROPERTIES_FILE_PATH),
-                cause: format!("property `{PROPERTIES_FILE_FORMAT_VERSION_KEY}` not found"),
-            });
-        };
-
-        Ok(format_version.clone())
+        Reader::format_version(&mut archive)
     }
 }
 
@@ -592,15 +555,10 @@ impl Reader {
         R: io::Read + io::Seek,
     {
         let mut archive = zip::ZipArchive::new(reader)?;
-        let format_version = Self::format_version(&mut archive)?;
-        let Some(format_version) = FormatVersion::from_str(&format_version) else {
-            return Err(Error::FileFormatNotSupported {
-                version: format_version.clone(),
-            });
-        };
+        let format_version = Self::detect_format_version(&mut archive)?;
 
         match format_version {
-            FormatVersion::V2_0 => v2_0::Reader::new(archive).into(),
+            FormatVersion::V2_0 => v2_0::Reader::new(archive),
         }
     }
 
@@ -610,12 +568,7 @@ impl Reader {
         R: io::Read + io::Seek,
     {
         let mut archive = zip::ZipArchive::new(reader)?;
-        let format_version = Self::format_version(&mut archive)?;
-        let Some(format_version) = FormatVersion::from_str(&format_version) else {
-            return Err(Error::FileFormatNotSupported {
-                version: format_version.clone(),
-            });
-        };
+        let format_version = Self::detect_format_version(&mut archive)?;
 
         let reader = match format_version {
             FormatVersion::V2_0 => v2_0::Reader::new(archive)?.into(),
@@ -628,34 +581,36 @@ impl Reader {
     where
         R: io::Read + io::Seek,
     {
-        let properties = {
-            let mut properties =
-                archive
-                    .by_path(DATASET_PROPERTIES_FILE_PATH)
-                    .map_err(|error| Error::Zip {
-                        path: PathBuf::from(DATASET_PROPERTIES_FILE_PATH),
-                        error,
-                    })?;
-
-            Properties::new(&mut properties).map_err(|_err| Error::InvalidFormat {
-                path: PathBuf::from(DATASET_PROPERTIES_FILE_PATH),
-                cause: "invalid format".to_string(),
-            })?
-        };
-
-        let Some(format_version) = properties.get(PROPERTIES_FILE_FORMAT_VERSION_KEY) else {
-            return Err(Error::InvalidFormat {
-                path: PathBuf::from(DATASET_PROPERTIES_FILE_PATH),
-                cause: format!("property `{PROPERTIES_FILE_FORMAT_VERSION_KEY}` not found"),
-            });
-        };
+        Ok(DatasetReader::format_version(archive)?)
+    }
 
-        Ok(format_version.clone())
+    /// Get the format version to dispatch the reader on.
+    ///
+    /// # Errors
+    /// + [`Error::FileFormatNotSupported`] with the dataset type if no reader is
+    ///   available for the version, or the reader does not support the features of the file.
+    fn detect_format_version<R>(archive: &mut zip::ZipArchive<R>) -> Result<FormatVersion, Error>
+    where
+        R: io::Read + io::Seek,
+    {
+        let properties = DatasetReader::read_dataset_properties(archive)?;
+        let file_format = DatasetReader::<R>::file_format(&properties)?;
+        file_format
+            .supported_version()
+            .map_err(|error| Error::FileFormatNotSupported {
+                dataset_type: properties.dataset_type().map(String::from),
+                error,
+            })
     }
 }
 
 #[derive(derive_more::From, Debug)]
 pub enum Error {
+    /// The file at the given path could not be opened as an archive.
+    OpenFile {
+        path: PathBuf,
+        error: zip::result::ZipError,
+    },
     #[from]
     OpenArchive(zip::result::ZipError),
     Zip {
@@ -666,15 +621,64 @@ pub enum Error {
         path: PathBuf,
         cause: String,
     },
-    /// A reader for the format version is not available.
+    /// A property of the file at the given path was missing or invalid.
+    Property {
+        path: PathBuf,
+        error: PropertyError,
+    },
+    /// The file format version or features of the dataset type can not be read.
     FileFormatNotSupported {
-        version: String,
+        dataset_type: Option<String>,
+        error: dataset::format::UnsupportedFormat,
     },
 }
 
+impl From<DatasetError> for Error {
+    fn from(value: DatasetError) -> Self {
+        match value {
+            DatasetError::OpenFile { path, error } => Self::OpenFile { path, error },
+            DatasetError::OpenArchive(err) => Self::OpenArchive(err),
+            DatasetError::Zip { path, error } => Self::Zip { path, error },
+            DatasetError::InvalidFormat { path, cause } => Self::InvalidFormat { path, cause },
+            DatasetError::FileFormatNotSupported(error) => Self::FileFormatNotSupported {
+                dataset_type: None,
+                error,
+            },
+            DatasetError::Property { path, error } => Self::Property { path, error },
+        }
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // TODO: Clean up error messages.
-        write!(f, "{self:?}")
+        match self {
+            Self::OpenFile { path, .. } => write!(f, "could not open `{}`", path.display()),
+            Self::OpenArchive(_) => write!(f, "could not open archive"),
+            Self::Zip { path, .. } => write!(f, "could not read `{}`", path.display()),
+            Self::InvalidFormat { path, cause } => {
+                write!(f, "invalid format of `{}`: {cause}", path.display())
+            }
+            Self::Property { path, .. } => write!(f, "invalid property in `{}`", path.display()),
+            Self::FileFormatNotSupported {
+                dataset_type: Some(dataset_type),
+                ..
+            } => write!(f, "file format of `{dataset_type}` is not supported"),
+            Self::FileFormatNotSupported {
+                dataset_type: None, ..
+            } => write!(f, "file format is not supported"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::OpenFile { error, .. } | Self::OpenArchive(error) | Self::Zip { error, .. } => {
+                Some(error)
+            }
+            Self::Property { error, .. } => Some(error),
+            Self::FileFormatNotSupported { error, .. } => Some(error),
+            Self::InvalidFormat { .. } => None,
+        }
     }
 }
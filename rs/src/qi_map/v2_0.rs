@@ -1,92 +1,19 @@
-use super::{IndexType, SegmentType, Value};
-use crate::{
-    lcd_info,
-    properties::{self, Properties, PropertyError},
-    qi_map::v2_0::utils::SHARED_DATA_DIR,
+use super::{IndexType, SegmentType};
+use crate::dataset::{
+    DatasetError,
+    preview::Preview,
+    properties::{Properties, PropertyTree, error::Property as PropertyError},
+    v2_0::{self as dataset, DatasetReader, properties::index::IndexData, utils},
 };
 use rayon::prelude::*;
 use std::{
+    collections::HashMap,
     fmt, fs,
-    io::{self, Read},
+    io::{self},
     path::PathBuf,
+    sync::Arc,
 };
 
-const PROPERTIES_DATA_FILE_KEY: &str = "jpk-data-file";
-const PROPERTIES_DATA_FILE_VALUE: &str = "spm-quantitative-image-data-file";
-const PROPERTIES_DATA_TYPE_KEY: &str = "type";
-const PROPERTIES_DATA_TYPE_VALUE: &str = "quantitative-imaging-map";
-
-#[derive(derive_more::Deref, Debug)]
-struct IndexProperties {
-    inner: Properties,
-}
-
-#[derive(derive_more::Deref, Debug)]
-struct SegmentProperties {
-    inner: Properties,
-}
-
-impl SegmentProperties {
-    /// `channel.{channel}.data.file.name`
-    pub fn channel_data_file_name_key(channel: impl fmt::Display) -> String {
-        format!("channel.{channel}.data.file.name")
-    }
-
-    /// `channel.{channel}.data.file.format`
-    pub fn channel_data_file_format_key(channel: impl fmt::Display) -> String {
-        format!("channel.{channel}.data.file.format")
-    }
-
-    /// `channel.{channel}.data.num-points`
-    pub fn channel_data_num_points_key(channel: impl fmt::Display) -> String {
-        format!("channel.{channel}.data.num-points")
-    }
-
-    /// `channel.{channel}.lcd-info.*`
-    pub fn channel_shared_data_index_key(channel: impl fmt::Display) -> String {
-        format!("channel.{channel}.lcd-info.*")
-    }
-}
-
-#[derive(derive_more::Deref)]
-pub struct SharedData {
-    inner: Properties,
-}
-
-impl SharedData {
-    const LCD_INFOS_COUNT_KEY: &str = "lcd-infos.count";
-
-    /// `lcd-info.{index}.encoder.type`
-    pub fn lcd_info_encoder_type_key(index: usize) -> String {
-        format!("lcd-info.{index}.encoder.type")
-    }
-
-    /// `lcd-info.{index}.encoder.scaling.unit.unit``
-    pub fn lcd_info_encoder_unit_key(index: usize) -> String {
-        format!("lcd-info.{index}.encoder.scaling.unit.unit")
-    }
-
-    /// `lcd-info.{index}.encoder.scaling.type`
-    pub fn lcd_info_encoder_scaling_type_key(index: usize) -> String {
-        format!("lcd-info.{index}.encoder.scaling.type")
-    }
-
-    /// `lcd-info.{index}.encoder.scaling.style`
-    pub fn lcd_info_encoder_scaling_style_key(index: usize) -> String {
-        format!("lcd-info.{index}.encoder.scaling.style")
-    }
-
-    /// `lcd-info.{index}.encoder.scaling.offset`
-    pub fn lcd_info_encoder_scaling_offset_key(index: usize) -> String {
-        format!("lcd-info.{index}.encoder.scaling.offset")
-    }
-
-    /// `lcd-info.{index}.encoder.scaling.multiplier`
-    pub fn lcd_info_encoder_scaling_multiplier_key(index: usize) -> String {
-        format!("lcd-info.{index}.encoder.scaling.multiplier")
-    }
-}
-
 /// JPK reader optimized for files.
 /// Allows parallel reading of datasets, where as [`Reader`] must read things in series.
 pub struct FileReader {
@@ -97,11 +24,11 @@ pub struct FileReader {
 impl FileReader {
     pub fn new(path: impl Into<PathBuf>) -> Result<Self, super::Error> {
         let path = path.into();
-        let file = fs::File::open(&path).map_err(|err| match err.kind() {
-            io::ErrorKind::NotFound => zip::result::ZipError::FileNotFound,
-            _ => zip::result::ZipError::Io(err),
-        })?;
-        let archive = zip::ZipArchive::new(file)?;
+        let archive =
+            crate::dataset::open_archive(&path).map_err(|error| super::Error::OpenFile {
+                path: path.clone(),
+                error,
+            })?;
         let inner = Reader::new(archive)?;
         Ok(Self {
             inner,
@@ -124,229 +51,239 @@ impl FileReader {
             file_path: path,
         })
     }
+
+    pub fn dataset_info(&self) -> &DatasetInfo {
+        self.inner.dataset_info()
+    }
+
+    pub fn validate_dataset_type(&self) -> bool {
+        self.inner.validate_dataset_type()
+    }
+
+    /// Replace the scaling of a conversion of a channel for all subsequent queries.
+    /// The original and new values are recorded in the shared data metadata.
+    /// See [`DatasetReader::override_calibration`].
+    pub fn override_calibration(
+        &mut self,
+        channel: impl AsRef<str>,
+        slot: impl AsRef<str>,
+        multiplier: f64,
+        offset: f64,
+    ) -> Result<(), dataset::error::OverrideCalibration> {
+        self.inner
+            .override_calibration(channel, slot, multiplier, offset)
+    }
+
+    /// Open a new handle to the archive, sharing the already parsed metadata.
+    /// Used to initialize per-thread readers.
+    ///
+    /// # Errors
+    /// The error is shared so every query on the thread can report it.
+    fn thread_reader(&self) -> Result<DatasetReader<fs::File>, Arc<io::Error>> {
+        let file = fs::File::open(&self.file_path).map_err(Arc::new)?;
+        let metadata = self.inner.inner.archive().metadata();
+        let archive = unsafe { zip::ZipArchive::unsafe_new_with_metadata(file, metadata) };
+        Ok(unsafe { self.inner.inner.with_archive(archive) })
+    }
+
+    /// Error for a per-thread reader that could not be opened.
+    fn thread_reader_error(&self, error: &Arc<io::Error>) -> super::QueryError {
+        super::QueryError::OpenArchive {
+            path: self.file_path.clone(),
+            error: Arc::clone(error),
+        }
+    }
 }
 
 impl super::QIMapReader for FileReader {
     fn query_data(&mut self, query: &super::DataQuery) -> Result<super::Data, super::QueryError> {
-        let indices = self.inner._data_query_indices(query)?;
-        let data_idx = indices
+        self.data(query)
+            .map_err(|err| err.in_archive(&self.file_path))
+    }
+
+    fn query_metadata(
+        &mut self,
+        query: &super::MetadataQuery,
+    ) -> Result<super::Metadata, super::QueryError> {
+        let metadata = match query {
+            super::MetadataQuery::All => self.metadata_all(),
+            super::MetadataQuery::Dataset => self.inner.metadata_dataset(),
+            super::MetadataQuery::SharedData => self.inner.metadata_shared(),
+            super::MetadataQuery::Index(query) => self.metadata_index(query),
+            super::MetadataQuery::Segment { index, segment } => {
+                self.metadata_segment(index, segment)
+            }
+        };
+        metadata.map_err(|err| err.in_archive(&self.file_path))
+    }
+    fn thumbnail(&mut self) -> Result<Option<Preview>, DatasetError> {
+        self.inner.thumbnail()
+    }
+
+    fn previews(&mut self) -> Result<Vec<Preview>, DatasetError> {
+        self.inner.previews()
+    }
+}
+
+impl FileReader {
+    fn data(&mut self, query: &super::DataQuery) -> Result<super::Data, super::QueryError> {
+        let indices = self.inner.query_indices(&query.index)?;
+        let map_type = self.dataset_info().map_type();
+        let segments = indices
             .into_par_iter()
             .map_init(
-                {
-                    let metadata = self.inner.archive.metadata();
-                    let file_path = &self.file_path;
-                    move || {
-                        let file = fs::File::open(file_path).expect("could not open file");
-                        unsafe { zip::ZipArchive::unsafe_new_with_metadata(file, metadata.clone()) }
-                    }
-                },
-                |archive, idx| {
-                    let index_data = utils::index_data(archive, idx)?;
-                    let segments = match &query.segment {
-                        super::SegmentQuery::All => {
-                            (0..index_data.segment_count()).collect::<Vec<_>>()
-                        }
-                        super::SegmentQuery::Indices(indices) => indices.clone(),
-                    };
-
-                    let idx = segments
+                || self.thread_reader(),
+                |reader, index| {
+                    let reader = reader
+                        .as_mut()
+                        .map_err(|err| self.thread_reader_error(err))?;
+                    let segments = query_segments(reader, map_type, index, &query.segment)?;
+                    let segments = segments
                         .into_iter()
-                        .map(|segment| (idx, segment))
+                        .map(|segment| (index, segment))
                         .collect::<Vec<_>>();
-                    Ok(idx)
+                    Ok(segments)
                 },
             )
             .collect::<Result<Vec<_>, _>>()?;
-        let data_idx = data_idx.into_iter().flatten().collect::<Vec<_>>();
+        let segments = segments.into_iter().flatten().collect::<Vec<_>>();
 
-        let data_idx = data_idx
+        let data = segments
             .into_par_iter()
             .map_init(
-                {
-                    let metadata = self.inner.archive.metadata();
-                    let file_path = &self.file_path;
-                    move || {
-                        let file = fs::File::open(file_path).expect("could not open file");
-                        unsafe { zip::ZipArchive::unsafe_new_with_metadata(file, metadata.clone()) }
-                    }
-                },
-                |archive, (idx, segment)| {
-                    let segment_properties = utils::segment_properties(archive, idx, segment)?;
-                    let segment_data = utils::segment_data(&segment_properties, idx)?;
-                    let channels = match &query.channel {
-                        super::ChannelQuery::All => segment_data.channels().clone(),
-                        super::ChannelQuery::Include(channels) => {
-                            let mut channels = channels.clone();
-                            channels.retain(|channel| segment_data.channels().contains(channel));
-                            channels
-                        }
-                    };
-
-                    let idx = channels
-                        .into_iter()
-                        .map(|channel| {
-                            let channel_data =
-                                utils::channel_data(&segment_properties, &channel, idx, segment)?;
-
-                            Ok(((idx, segment, channel), channel_data))
-                        })
-                        .collect::<Result<Vec<_>, _>>()?;
-
-                    Ok(idx)
+                || self.thread_reader(),
+                |reader, (index, segment)| {
+                    let reader = reader
+                        .as_mut()
+                        .map_err(|err| self.thread_reader_error(err))?;
+                    query_segment_data(
+                        reader,
+                        index,
+                        segment,
+                        &query.channel,
+                        &query.calibration_slots,
+                    )
                 },
             )
             .collect::<Result<Vec<_>, _>>()?;
 
-        let data_idx = data_idx
+        let data = data.into_iter().flatten().collect::<Vec<_>>();
+        Ok(into_data(data))
+    }
+
+    fn metadata_all(&mut self) -> Result<super::Metadata, super::QueryError> {
+        use crate::ArchiveReader;
+
+        let files = self
+            .inner
+            .files()
             .into_iter()
-            .flatten()
-            .map(|((index, segment, channel), channel_data)| {
-                (
-                    super::DataIndex {
-                        index,
-                        segment,
-                        channel,
-                    },
-                    channel_data.shared_data_index(),
-                    channel_data.file_path().clone(),
-                )
-            })
+            .map(|file| file.to_string())
             .collect::<Vec<_>>();
 
-        let raw_data = data_idx
+        let properties = files
             .into_par_iter()
             .map_init(
-                {
-                    let metadata = self.inner.archive.metadata();
-                    let file_path = &self.file_path;
-                    move || {
-                        let file = fs::File::open(file_path).expect("could not open file");
-                        unsafe { zip::ZipArchive::unsafe_new_with_metadata(file, metadata.clone()) }
-                    }
-                },
-                |archive, (idx, shared_data_index, file_path)| {
-                    let data_file_path = {
-                        let path = utils::index_segment_path(idx.index, idx.segment);
-                        let path =
-                            format!("{}/{}", path.to_string_lossy(), file_path.to_string_lossy());
-                        PathBuf::from(path)
-                    };
-
-                    let mut data_file = archive.by_path(&data_file_path).map_err(|error| {
+                || self.thread_reader(),
+                |reader, file| {
+                    let reader = reader
+                        .as_mut()
+                        .map_err(|err| self.thread_reader_error(err))?;
+                    let index = metadata_index_from_file_path(&file).map_err(|error| {
                         super::QueryError::ZipFile {
-                            path: data_file_path.clone(),
+                            archive: None,
+                            path: PathBuf::from(&file),
                             error,
                         }
                     })?;
-                    let mut raw_data = Vec::with_capacity(data_file.size() as usize);
-                    data_file.read_to_end(&mut raw_data).map_err(|err| {
-                        super::QueryError::ZipFile {
-                            path: data_file_path.clone(),
-                            error: zip::result::ZipError::Io(err),
-                        }
-                    })?;
+                    let Some(index) = index else {
+                        return Ok(None);
+                    };
+
+                    // shared data may contain overridden calibrations
+                    if index == super::MetadataIndex::SharedData {
+                        let properties = Properties::clone(reader.shared_properties());
+                        return Ok(Some((index, properties)));
+                    }
 
-                    Ok((idx, raw_data, data_file_path, shared_data_index))
+                    let properties = reader
+                        .properties(&file)
+                        .map_err(|err| properties_error(&file, err))?;
+                    Ok(Some((index, properties)))
                 },
             )
             .collect::<Result<Vec<_>, _>>()?;
 
-        let lcd_info = &self.inner.lcd_info;
-        let data = raw_data
-            .into_par_iter()
-            .map_with(
-                lcd_info,
-                |lcd_info, (idx, raw_data, data_file_path, shared_data_index)| {
-                    let lcd_info = &lcd_info[shared_data_index];
-                    let ch_data = lcd_info.convert_data(&raw_data).map_err(|_err| {
-                        super::QueryError::InvalidData {
-                            path: data_file_path.clone(),
-                        }
-                    })?;
+        let (indices, data) = properties
+            .into_iter()
+            .flatten()
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+
+        Ok(super::Metadata::from_parts(indices, data).expect("indices and data are compatible"))
+    }
 
-                    Ok((idx, ch_data))
+    fn metadata_index(
+        &mut self,
+        query: &super::IndexQuery,
+    ) -> Result<super::Metadata, super::QueryError> {
+        let indices = self.inner.query_indices(query)?;
+        let properties = indices
+            .into_par_iter()
+            .map_init(
+                || self.thread_reader(),
+                |reader, index| {
+                    let reader = reader
+                        .as_mut()
+                        .map_err(|err| self.thread_reader_error(err))?;
+                    index_metadata(reader, index)
                 },
             )
             .collect::<Result<Vec<_>, _>>()?;
 
-        let (idx, data) = data.into_iter().unzip();
-        let data = super::Data::new(idx, data).unwrap();
-        Ok(data)
+        let (indices, data) = properties.into_iter().unzip::<_, _, Vec<_>, Vec<_>>();
+        Ok(super::Metadata::from_parts(indices, data).expect("indices and data are compatible"))
     }
 
-    fn query_metadata(
+    fn metadata_segment(
         &mut self,
-        query: &super::MetadataQuery,
+        index_query: &super::IndexQuery,
+        segment_query: &super::SegmentQuery,
     ) -> Result<super::Metadata, super::QueryError> {
-        match query {
-            super::MetadataQuery::All => self.metadata_all(),
-            super::MetadataQuery::Dataset => self.inner.metadata_dataset(),
-            super::MetadataQuery::SharedData => self.inner.metadata_shared(),
-            super::MetadataQuery::Index(index_query) => match index_query {
-                super::IndexQuery::All => todo!("FileReader::query_metadata(IndexQuery::All)"),
-                super::IndexQuery::Index(index) => {
-                    todo!("FileReader::query_metadata(IndexQuery::Index)")
-                }
-                super::IndexQuery::PixelRect(pixel_rect) => {
-                    todo!("FileReader::query_metadata(IndexQuery::PixelRect)")
-                }
-                super::IndexQuery::Pixel(pixel) => self.inner.metadata_index_pixel(pixel),
-            },
-            super::MetadataQuery::Segment { index, segment } => {
-                todo!("FileReader::query_metadata(SegmentQuery)")
-            }
-        }
-    }
-}
-
-impl FileReader {
-    fn metadata_all(&mut self) -> Result<super::Metadata, super::QueryError> {
-        let properties = (0..self.inner.archive.len())
+        let indices = self.inner.query_indices(index_query)?;
+        let map_type = self.dataset_info().map_type();
+        let segments = indices
             .into_par_iter()
             .map_init(
-                {
-                    let metadata = self.inner.archive.metadata();
-                    let file_path = &self.file_path;
-                    move || {
-                        let file = fs::File::open(file_path).expect("could not open file");
-                        unsafe { zip::ZipArchive::unsafe_new_with_metadata(file, metadata.clone()) }
-                    }
-                },
-                |archive, idx| {
-                    let mut file = archive
-                        .by_index(idx)
-                        .map_err(|err| super::QueryError::Zip(err))?;
-
-                    metadata_index_from_file_path(
-                        file.name(),
-                        &self.inner.dataset_info.position_pattern,
-                    )
-                    .map_err(|err| super::QueryError::ZipFile {
-                        path: PathBuf::from(file.name()),
-                        error: err,
-                    })
-                    .map(|maybe_index| {
-                        maybe_index
-                            .map(|index| {
-                                super::Properties::new(&mut file)
-                                    .map(|property| (index, property))
-                                    .map_err(|_| super::QueryError::InvalidFormat {
-                                        path: PathBuf::from(file.name()),
-                                        cause: "file could not be read as properties".to_string(),
-                                    })
-                            })
-                            .transpose()
-                    })
-                    .flatten()
+                || self.thread_reader(),
+                |reader, index| {
+                    let reader = reader
+                        .as_mut()
+                        .map_err(|err| self.thread_reader_error(err))?;
+                    let segments = query_segments(reader, map_type, index, segment_query)?;
+                    let segments = segments
+                        .into_iter()
+                        .map(|segment| (index, segment))
+                        .collect::<Vec<_>>();
+                    Ok(segments)
                 },
             )
             .collect::<Result<Vec<_>, _>>()?;
+        let segments = segments.into_iter().flatten().collect::<Vec<_>>();
 
-        let (indices, data) = properties
-            .into_iter()
-            .filter_map(|index| index)
-            .unzip::<_, _, Vec<_>, Vec<_>>();
+        let properties = segments
+            .into_par_iter()
+            .map_init(
+                || self.thread_reader(),
+                |reader, (index, segment)| {
+                    let reader = reader
+                        .as_mut()
+                        .map_err(|err| self.thread_reader_error(err))?;
+                    segment_metadata(reader, index, segment)
+                },
+            )
+            .collect::<Result<Vec<_>, _>>()?;
 
+        let (indices, data) = properties.into_iter().unzip::<_, _, Vec<_>, Vec<_>>();
         Ok(super::Metadata::from_parts(indices, data).expect("indices and data are compatible"))
     }
 }
@@ -362,161 +299,86 @@ impl crate::ArchiveReader for FileReader {
 }
 
 pub struct Reader<R> {
-    archive: zip::ZipArchive<R>,
+    inner: DatasetReader<R>,
     dataset_info: DatasetInfo,
-    lcd_info: Vec<lcd_info::LcdInfo>,
 }
 
 impl<R> Reader<R>
 where
     R: io::Read + io::Seek,
 {
-    pub fn new(mut archive: zip::ZipArchive<R>) -> Result<Self, super::Error> {
-        let dataset_info = {
-            let path = PathBuf::from(utils::DATASET_PROPERTIES_FILE);
-            let mut properties = archive.by_path(&path).map_err(|error| super::Error::Zip {
-                path: path.clone(),
-                error,
-            })?;
-
-            let properties =
-                Properties::new(&mut properties).map_err(|_err| super::Error::InvalidFormat {
-                    path: path.clone(),
-                    cause: "invalid properties file".to_string(),
-                })?;
-
-            Self::_init_dataset_info(&properties)?
-        };
-
-        let shared_data = {
-            let path = utils::shared_data_properties_path();
-            let mut properties = archive.by_path(&path).map_err(|error| super::Error::Zip {
-                path: path.clone(),
-                error,
-            })?;
-
-            let properties =
-                Properties::new(&mut properties).map_err(|_err| super::Error::InvalidFormat {
-                    path: path.clone(),
-                    cause: "invalid properties file".to_string(),
-                })?;
-            SharedData { inner: properties }
-        };
-        let lcd_info = Self::_init_lcd_infos(&shared_data)?;
-
+    pub fn new(archive: zip::ZipArchive<R>) -> Result<Self, super::Error> {
+        let inner = DatasetReader::new(archive)?;
+        let dataset_info = Self::_init_dataset_info(inner.dataset_properties())?;
         Ok(Self {
-            archive,
+            inner,
             dataset_info,
-            lcd_info,
         })
     }
 
     fn _init_dataset_info(properties: &Properties) -> Result<DatasetInfo, super::Error> {
-        let Some(index_type) = properties.get(DatasetProperties::INDEX_TYPE_KEY) else {
-            return Err(super::Error::InvalidFormat {
-                path: PathBuf::from(utils::DATASET_PROPERTIES_FILE),
-                cause: format!(
-                    "expected key `{}` to exist",
-                    DatasetProperties::INDEX_TYPE_KEY
-                ),
-            });
+        let property_error = |error| super::Error::Property {
+            path: PathBuf::from(utils::DATASET_PROPERTIES_FILE),
+            error,
         };
-        let index = match index_type.as_str() {
-            "range" => {
-                let Some(min) = properties.get(DatasetProperties::INDEX_MIN_KEY) else {
-                    return Err(super::Error::InvalidFormat {
-                        path: PathBuf::from(utils::DATASET_PROPERTIES_FILE),
-                        cause: format!(
-                            "expected key `{}` to exist",
-                            DatasetProperties::INDEX_MIN_KEY
-                        ),
-                    });
-                };
-                let Ok(min) = min.parse::<IndexType>() else {
-                    return Err(super::Error::InvalidFormat {
-                        path: PathBuf::from(utils::DATASET_PROPERTIES_FILE),
-                        cause: format!("invalid value of `{}`", DatasetProperties::INDEX_MIN_KEY),
-                    });
-                };
-
-                let Some(max) = properties.get(DatasetProperties::INDEX_MAX_KEY) else {
-                    return Err(super::Error::InvalidFormat {
-                        path: PathBuf::from(utils::DATASET_PROPERTIES_FILE),
-                        cause: format!(
-                            "expected key `{}` to exist",
-                            DatasetProperties::INDEX_MAX_KEY
-                        ),
-                    });
-                };
-                let Ok(max) = max.parse::<IndexType>() else {
-                    return Err(super::Error::InvalidFormat {
-                        path: PathBuf::from(utils::DATASET_PROPERTIES_FILE),
-                        cause: format!("invalid value of `{}`", DatasetProperties::INDEX_MAX_KEY),
-                    });
-                };
 
+        let map_type = properties
+            .tree()
+            .get_with(
+                dataset::properties::Dataset::DATASET_TYPE_KEY,
+                MapType::from_str,
+            )
+            .map_err(property_error)?;
+
+        let tree = properties.subtree(map_type.prefix());
+        let indexes = tree.subtree(DatasetProperties::INDEXES_KEY);
+        let index_type = indexes
+            .get_str(DatasetProperties::INDEX_TYPE_KEY)
+            .map_err(property_error)?;
+        let index = match index_type {
+            "range" => {
+                let min = indexes
+                    .parse::<IndexType>(DatasetProperties::INDEX_MIN_KEY)
+                    .map_err(property_error)?;
+                let max = indexes
+                    .parse::<IndexType>(DatasetProperties::INDEX_MAX_KEY)
+                    .map_err(property_error)?;
                 Index::Range { min, max }
             }
 
             _ => {
-                return Err(super::Error::InvalidFormat {
-                    path: PathBuf::from(utils::DATASET_PROPERTIES_FILE),
-                    cause: format!("invalid value of `{}`", DatasetProperties::INDEX_TYPE_KEY),
-                });
+                return Err(property_error(PropertyError::InvalidValue(
+                    indexes.key(DatasetProperties::INDEX_TYPE_KEY),
+                )));
             }
         };
 
-        let position_pattern =
-            PositionPattern::from_properties(properties).map_err(|err| match err {
-                PropertyError::NotFound(key) => super::Error::InvalidFormat {
-                    path: PathBuf::from(utils::DATASET_PROPERTIES_FILE),
-                    cause: format!("`property {key}` not found"),
-                },
-                PropertyError::InvalidValue(key) => super::Error::InvalidFormat {
-                    path: PathBuf::from(utils::DATASET_PROPERTIES_FILE),
-                    cause: format!("invalid value of `{key}`"),
-                },
-            })?;
+        let position_pattern = PositionPattern::from_properties(
+            &tree.subtree(DatasetProperties::POSITION_PATTERN_KEY),
+        )
+        .map_err(property_error)?;
 
         Ok(DatasetInfo {
+            map_type,
             index,
             position_pattern,
         })
     }
+}
 
-    fn _init_lcd_infos(properties: &SharedData) -> Result<Vec<lcd_info::LcdInfo>, super::Error> {
-        let infos_count =
-            properties::extract_value!(properties, SharedData::LCD_INFOS_COUNT_KEY, parse usize)
-                .map_err(|err| match err {
-                    PropertyError::NotFound(key) => super::Error::InvalidFormat {
-                        path: utils::shared_data_properties_path(),
-                        cause: format!(" property `{key}` not found",),
-                    },
-                    PropertyError::InvalidValue(key) => super::Error::InvalidFormat {
-                        path: utils::shared_data_properties_path(),
-                        cause: format!("invalid value for property `{key}`",),
-                    },
-                })?;
-
-        (0..infos_count)
-            .map(|idx| Self::_init_lcd_info(properties, idx))
-            .collect()
-    }
-
-    fn _init_lcd_info(
-        properties: &SharedData,
-        index: usize,
-    ) -> Result<lcd_info::LcdInfo, super::Error> {
-        lcd_info::LcdInfo::from_properties(properties, index).map_err(|err| match err {
-            PropertyError::NotFound(key) => super::Error::InvalidFormat {
-                path: utils::shared_data_properties_path(),
-                cause: format!("property `{key}` not found"),
-            },
-            PropertyError::InvalidValue(key) => super::Error::InvalidFormat {
-                path: utils::shared_data_properties_path(),
-                cause: format!("invalid property value of `{key}`"),
-            },
-        })
+impl<R> Reader<R> {
+    pub fn dataset_info(&self) -> &DatasetInfo {
+        &self.dataset_info
+    }
+
+    /// # Returns
+    /// If the data file property matches the expected value for the map type.
+    pub fn validate_dataset_type(&self) -> bool {
+        self.inner
+            .dataset_properties()
+            .data_file()
+            .map(|data_file| data_file == self.dataset_info.map_type.data_file())
+            .unwrap_or(false)
     }
 }
 
@@ -524,77 +386,66 @@ impl<R> Reader<R>
 where
     R: io::Read + io::Seek,
 {
+    /// Replace the scaling of a conversion of a channel for all subsequent queries.
+    /// The original and new values are recorded in the shared data metadata.
+    /// See [`DatasetReader::override_calibration`].
+    pub fn override_calibration(
+        &mut self,
+        channel: impl AsRef<str>,
+        slot: impl AsRef<str>,
+        multiplier: f64,
+        offset: f64,
+    ) -> Result<(), dataset::error::OverrideCalibration> {
+        self.inner
+            .override_calibration(channel, slot, multiplier, offset)
+    }
+
     pub fn get_data_index_segment_channel(
         &mut self,
         index: IndexType,
         segment: SegmentType,
         channel: impl fmt::Display,
-    ) -> Result<Vec<Value>, DataError> {
+        calibration_slot: Option<&str>,
+    ) -> Result<dataset::ChannelData, DataError> {
+        use dataset::error::{ChannelData, Properties as PropertiesError};
+
+        let segment_path = utils::index_segment_path(index, segment);
         let segment_properties_path = utils::index_segment_properties_path(index, segment);
-        let segment_properties = {
-            let mut segment_properties =
-                self.archive
-                    .by_path(&segment_properties_path)
-                    .map_err(|error| DataError::Zip {
+        self.inner
+            .channel_data(&segment_path, channel.to_string(), calibration_slot)
+            .map_err(|err| match err {
+                ChannelData::Zip(error)
+                | ChannelData::SegmentProperties(PropertiesError::Zip(error)) => DataError::Zip {
+                    path: segment_path.clone(),
+                    error,
+                },
+                ChannelData::SegmentProperties(PropertiesError::InvalidFormat(error)) => {
+                    DataError::InvalidFormat {
                         path: segment_properties_path.clone(),
-                        error,
-                    })?;
-            let segment_properties =
-                Properties::new(&mut segment_properties).map_err(|_| DataError::InvalidFormat {
-                    path: segment_properties_path.clone(),
-                    cause: "file could not be read as properties".to_string(),
-                })?;
-            SegmentProperties {
-                inner: segment_properties,
-            }
-        };
-
-        let channel_data = channel_data::ChannelData::from(&segment_properties, &channel).map_err(
-            |err| match err {
-                PropertyError::NotFound(key) => DataError::InvalidFormat {
+                        cause: error.to_string(),
+                    }
+                }
+                ChannelData::Property(error) => DataError::Property {
                     path: segment_properties_path.clone(),
-                    cause: format!("property `{key}` not found"),
+                    error,
+                },
+                error @ ChannelData::InvalidDataLength => DataError::InvalidData {
+                    path: segment_path.clone(),
+                    cause: error.to_string(),
                 },
-                PropertyError::InvalidValue(key) => DataError::InvalidFormat {
+                ChannelData::UnsupportedRasterLayout(layout) => DataError::InvalidFormat {
+                    path: segment_path.clone(),
+                    cause: format!("unsupported raster layout: {layout}"),
+                },
+                ChannelData::InvalidCalibrationSlot(slot) => {
+                    DataError::InvalidCalibrationSlot(slot)
+                }
+                error @ (ChannelData::LcdInfoNotFound(_)
+                | ChannelData::UnsupportedGenerator { .. }) => DataError::InvalidFormat {
                     path: segment_properties_path.clone(),
-                    cause: format!("invalid value of `{key}`"),
+                    cause: error.to_string(),
                 },
-            },
-        )?;
-        let lcd_info = &self.lcd_info[channel_data.shared_data_index()];
-
-        let data_file_path = {
-            let path = utils::index_segment_path(index, segment);
-            let path = format!(
-                "{}/{}",
-                path.to_string_lossy(),
-                channel_data.file_path().to_string_lossy()
-            );
-            PathBuf::from(path)
-        };
-
-        let mut data_file =
-            self.archive
-                .by_path(&data_file_path)
-                .map_err(|error| DataError::Zip {
-                    path: data_file_path.clone(),
-                    error,
-                })?;
-        let mut data = Vec::with_capacity(data_file.size() as usize);
-        data_file
-            .read_to_end(&mut data)
-            .map_err(|err| DataError::Zip {
-                path: data_file_path.clone(),
-                error: zip::result::ZipError::Io(err),
-            })?;
-
-        let data = lcd_info
-            .convert_data(&data)
-            .map_err(|_err| DataError::InvalidData {
-                path: data_file_path.clone(),
-            })?;
-
-        Ok(data)
+            })
     }
 }
 
@@ -603,81 +454,24 @@ where
     R: io::Read + io::Seek,
 {
     fn query_data(&mut self, query: &super::DataQuery) -> Result<super::Data, super::QueryError> {
-        let indices = self._data_query_indices(query)?;
-        let mut data_idx = Vec::with_capacity(indices.len());
+        let indices = self.query_indices(&query.index)?;
+        let map_type = self.dataset_info.map_type();
+        let mut data = Vec::with_capacity(indices.len());
         for index in indices {
-            let index_data = utils::index_data(&mut self.archive, index)?;
-            let segments = match &query.segment {
-                super::SegmentQuery::All => (0..index_data.segment_count()).collect::<Vec<_>>(),
-                super::SegmentQuery::Indices(indices) => indices.clone(),
-            };
-
+            let segments = query_segments(&mut self.inner, map_type, index, &query.segment)?;
             for segment in segments {
-                let segment_properties =
-                    utils::segment_properties(&mut self.archive, index, segment)?;
-                let segment_data = utils::segment_data(&segment_properties, index)?;
-                let channels = match &query.channel {
-                    super::ChannelQuery::All => segment_data.channels().clone(),
-                    super::ChannelQuery::Include(channels) => {
-                        let mut channels = channels.clone();
-                        channels.retain(|channel| segment_data.channels().contains(channel));
-                        channels
-                    }
-                };
-
-                for channel in channels {
-                    let channel_data =
-                        utils::channel_data(&segment_properties, &channel, index, segment)?;
-
-                    data_idx.push((
-                        super::DataIndex {
-                            index,
-                            segment,
-                            channel,
-                        },
-                        index,
-                        channel_data.shared_data_index(),
-                        channel_data.file_path().clone(),
-                    ))
-                }
+                let segment_data = query_segment_data(
+                    &mut self.inner,
+                    index,
+                    segment,
+                    &query.channel,
+                    &query.calibration_slots,
+                )?;
+                data.extend(segment_data);
             }
         }
 
-        let mut data = Vec::with_capacity(data_idx.len());
-        for (idx, index, shared_data_index, file_path) in data_idx {
-            let lcd_info = &self.lcd_info[shared_data_index];
-            let data_file_path = {
-                let path = utils::index_segment_path(index, idx.segment);
-                let path = format!("{}/{}", path.to_string_lossy(), file_path.to_string_lossy());
-                PathBuf::from(path)
-            };
-
-            let mut data_file = self.archive.by_path(&data_file_path).map_err(|error| {
-                super::QueryError::ZipFile {
-                    path: data_file_path.clone(),
-                    error,
-                }
-            })?;
-            let mut raw_data = Vec::with_capacity(data_file.size() as usize);
-            data_file
-                .read_to_end(&mut raw_data)
-                .map_err(|err| super::QueryError::ZipFile {
-                    path: data_file_path.clone(),
-                    error: zip::result::ZipError::Io(err),
-                })?;
-
-            let ch_data = lcd_info.convert_data(&raw_data).map_err(|_err| {
-                super::QueryError::InvalidData {
-                    path: data_file_path.clone(),
-                }
-            })?;
-
-            data.push((idx, ch_data));
-        }
-
-        let (idx, data) = data.into_iter().unzip();
-        let data = super::Data::new(idx, data).unwrap();
-        Ok(data)
+        Ok(into_data(data))
     }
 
     fn query_metadata(
@@ -688,21 +482,20 @@ where
             super::MetadataQuery::All => self.metadata_all(),
             super::MetadataQuery::Dataset => self.metadata_dataset(),
             super::MetadataQuery::SharedData => self.metadata_shared(),
-            super::MetadataQuery::Index(query) => match query {
-                super::IndexQuery::All => todo!("Reader::query_metadata(IndexQuery::All)"),
-                super::IndexQuery::Index(index) => {
-                    todo!("Reader::query_metadata(IndexQuery::Index)")
-                }
-                super::IndexQuery::PixelRect(rect) => {
-                    todo!("Reader::query_metadata(IndexQuery::PixelRect)")
-                }
-                super::IndexQuery::Pixel(pixel) => self.metadata_index_pixel(pixel),
-            },
+            super::MetadataQuery::Index(query) => self.metadata_index(query),
             super::MetadataQuery::Segment { index, segment } => {
-                todo!("Reader::query_metadata(SegmentQuery)")
+                self.metadata_segment(index, segment)
             }
         }
     }
+
+    fn thumbnail(&mut self) -> Result<Option<Preview>, DatasetError> {
+        self.inner.thumbnail()
+    }
+
+    fn previews(&mut self) -> Result<Vec<Preview>, DatasetError> {
+        self.inner.previews()
+    }
 }
 
 impl<R> crate::ArchiveReader for Reader<R>
@@ -710,11 +503,11 @@ where
     R: io::Read + io::Seek,
 {
     fn files(&self) -> Vec<&str> {
-        self.archive.file_names().collect()
+        self.inner.archive().file_names().collect()
     }
 
     fn len(&self) -> usize {
-        self.archive.len()
+        self.inner.archive().len()
     }
 }
 
@@ -722,11 +515,12 @@ impl<R> Reader<R>
 where
     R: io::Read + io::Seek,
 {
-    fn _data_query_indices(
-        &mut self,
-        query: &super::DataQuery,
+    /// Indices selected by `query`.
+    fn query_indices(
+        &self,
+        query: &super::IndexQuery,
     ) -> Result<Vec<IndexType>, super::QueryError> {
-        match &query.index {
+        match query {
             super::IndexQuery::All => match self.dataset_info.index {
                 Index::Range { min, max } => Ok((min..=max).collect::<Vec<_>>()),
             },
@@ -760,29 +554,38 @@ where
     R: io::Read + io::Seek,
 {
     fn metadata_all(&mut self) -> Result<super::Metadata, super::QueryError> {
-        let mut metadata = super::Metadata::with_capacity(self.archive.len() / 2);
-        for idx in 0..self.archive.len() {
-            let mut file = self
-                .archive
-                .by_index(idx)
-                .map_err(|err| super::QueryError::Zip(err))?;
-
-            let index =
-                metadata_index_from_file_path(file.name(), &self.dataset_info.position_pattern)
-                    .map_err(|err| super::QueryError::ZipFile {
-                        path: PathBuf::from(file.name()),
-                        error: err,
-                    })?;
+        use crate::ArchiveReader;
+
+        let files = self
+            .files()
+            .into_iter()
+            .map(|file| file.to_string())
+            .collect::<Vec<_>>();
+
+        let mut metadata = super::Metadata::with_capacity(files.len() / 2);
+        for file in files {
+            let index = metadata_index_from_file_path(&file).map_err(|error| {
+                super::QueryError::ZipFile {
+                    archive: None,
+                    path: PathBuf::from(&file),
+                    error,
+                }
+            })?;
             let Some(index) = index else {
                 continue;
             };
 
-            let properties = super::Properties::new(&mut file).map_err(|_| {
-                super::QueryError::InvalidFormat {
-                    path: PathBuf::from(file.name()),
-                    cause: "file could not be read as properties".to_string(),
-                }
-            })?;
+            // shared data may contain overridden calibrations
+            if index == super::MetadataIndex::SharedData {
+                let properties = Properties::clone(self.inner.shared_properties());
+                metadata.insert(index, properties);
+                continue;
+            }
+
+            let properties = self
+                .inner
+                .properties(&file)
+                .map_err(|err| properties_error(&file, err))?;
 
             metadata.insert(index, properties);
         }
@@ -791,85 +594,271 @@ where
     }
 
     fn metadata_dataset(&mut self) -> Result<super::Metadata, super::QueryError> {
-        let mut properties = self
-            .archive
-            .by_path(utils::DATASET_PROPERTIES_FILE)
-            .map_err(|error| super::QueryError::ZipFile {
-                path: PathBuf::from(utils::DATASET_PROPERTIES_FILE),
-                error,
-            })?;
-
-        let properties =
-            Properties::new(&mut properties).map_err(|_| super::QueryError::InvalidFormat {
-                path: PathBuf::from(utils::DATASET_PROPERTIES_FILE),
-                cause: "file could not be read as properties".to_string(),
-            })?;
-
+        let properties = Properties::clone(self.inner.dataset_properties());
         let indices = vec![super::MetadataIndex::Dataset];
         let data = vec![properties];
-        Ok(super::Metadata::from_parts(indices, data).unwrap())
+        Ok(super::Metadata::from_parts(indices, data).expect("indices and data are compatible"))
     }
 
     fn metadata_shared(&mut self) -> Result<super::Metadata, super::QueryError> {
-        let data_path = utils::shared_data_properties_path();
-        let mut properties =
-            self.archive
-                .by_path(&data_path)
-                .map_err(|error| super::QueryError::ZipFile {
-                    path: data_path.clone(),
-                    error,
-                })?;
-
-        let properties =
-            Properties::new(&mut properties).map_err(|_| super::QueryError::InvalidFormat {
-                path: data_path.clone(),
-                cause: "file could not be read as properties".to_string(),
-            })?;
-
+        let properties = Properties::clone(self.inner.shared_properties());
         let indices = vec![super::MetadataIndex::SharedData];
         let data = vec![properties];
-        Ok(super::Metadata::from_parts(indices, data).unwrap())
+        Ok(super::Metadata::from_parts(indices, data).expect("indices and data are compatible"))
     }
 
-    fn metadata_index_pixel(
+    fn metadata_index(
         &mut self,
-        pixel: &super::Pixel,
+        query: &super::IndexQuery,
     ) -> Result<super::Metadata, super::QueryError> {
-        let Some(index) = self.dataset_info.position_pattern.pixel_to_index(pixel) else {
-            return Err(super::QueryError::OutOfBounds(pixel.clone()));
-        };
-        let data_path = utils::index_properties_path(index);
-        let mut properties =
-            self.archive
-                .by_path(&data_path)
-                .map_err(|error| super::QueryError::ZipFile {
-                    path: data_path.clone(),
-                    error,
-                })?;
-        let properties =
-            Properties::new(&mut properties).map_err(|_| super::QueryError::InvalidFormat {
-                path: data_path.clone(),
-                cause: "file could not be read as properties".to_string(),
-            })?;
+        let indices = self.query_indices(query)?;
+        let mut metadata = super::Metadata::with_capacity(indices.len());
+        for index in indices {
+            let (idx, properties) = index_metadata(&mut self.inner, index)?;
+            metadata.insert(idx, properties);
+        }
 
-        let idx = vec![super::MetadataIndex::Index(index)];
-        let data = vec![properties];
-        Ok(super::Metadata::from_parts(idx, data).unwrap())
+        Ok(metadata)
+    }
+
+    fn metadata_segment(
+        &mut self,
+        index_query: &super::IndexQuery,
+        segment_query: &super::SegmentQuery,
+    ) -> Result<super::Metadata, super::QueryError> {
+        let indices = self.query_indices(index_query)?;
+        let map_type = self.dataset_info.map_type();
+        let mut metadata = super::Metadata::with_capacity(indices.len());
+        for index in indices {
+            let segments = query_segments(&mut self.inner, map_type, index, segment_query)?;
+            for segment in segments {
+                let (idx, properties) = segment_metadata(&mut self.inner, index, segment)?;
+                metadata.insert(idx, properties);
+            }
+        }
+
+        Ok(metadata)
+    }
+}
+
+/// Properties of `index`.
+fn index_metadata<R>(
+    reader: &mut DatasetReader<R>,
+    index: IndexType,
+) -> Result<(super::MetadataIndex, Properties), super::QueryError>
+where
+    R: io::Read + io::Seek,
+{
+    let path = utils::index_properties_path(index);
+    let properties = reader
+        .properties(&path)
+        .map_err(|err| properties_error(&path, err))?;
+
+    Ok((super::MetadataIndex::Index(index), properties))
+}
+
+/// Properties of `segment` of `index`.
+fn segment_metadata<R>(
+    reader: &mut DatasetReader<R>,
+    index: IndexType,
+    segment: SegmentType,
+) -> Result<(super::MetadataIndex, Properties), super::QueryError>
+where
+    R: io::Read + io::Seek,
+{
+    let path = utils::index_segment_properties_path(index, segment);
+    let properties = reader
+        .properties(&path)
+        .map_err(|err| properties_error(&path, err))?;
+
+    Ok((super::MetadataIndex::Segment { index, segment }, properties))
+}
+
+/// Segments of `index` selected by `query`.
+fn query_segments<R>(
+    reader: &mut DatasetReader<R>,
+    map_type: MapType,
+    index: IndexType,
+    query: &super::SegmentQuery,
+) -> Result<Vec<SegmentType>, super::QueryError>
+where
+    R: io::Read + io::Seek,
+{
+    match query {
+        super::SegmentQuery::All => {
+            let index_properties_path = utils::index_properties_path(index);
+            let properties = reader
+                .properties(&index_properties_path)
+                .map_err(|err| properties_error(&index_properties_path, err))?;
+            let index_data =
+                IndexData::from_properties(&properties.subtree(map_type.index_prefix()))
+                    .map_err(|err| property_error(&index_properties_path, err))?;
+
+            Ok((0..index_data.segment_count()).collect())
+        }
+        super::SegmentQuery::Indices(indices) => Ok(indices.clone()),
+    }
+}
+
+/// Data of the channels of a segment selected by `query`,
+/// converted to the channel's calibration slot in `calibration_slots` if present.
+fn query_segment_data<R>(
+    reader: &mut DatasetReader<R>,
+    index: IndexType,
+    segment: SegmentType,
+    query: &super::ChannelQuery,
+    calibration_slots: &HashMap<super::ChannelType, String>,
+) -> Result<Vec<(super::DataIndex, dataset::ChannelData)>, super::QueryError>
+where
+    R: io::Read + io::Seek,
+{
+    let segment_path = utils::index_segment_path(index, segment);
+    let segment_properties_path = utils::index_segment_properties_path(index, segment);
+    let properties = reader
+        .segment_properties(&segment_path)
+        .map_err(|err| properties_error(&segment_properties_path, err))?;
+    let segment_channels = properties
+        .channel_list()
+        .map_err(|err| property_error(&segment_properties_path, err))?;
+    let channels = match query {
+        super::ChannelQuery::All => segment_channels
+            .into_iter()
+            .map(|channel| channel.to_string())
+            .collect::<Vec<_>>(),
+        super::ChannelQuery::Include(channels) => {
+            let mut channels = channels.clone();
+            channels.retain(|channel| segment_channels.contains(&channel.as_str()));
+            channels
+        }
+    };
+
+    channels
+        .into_iter()
+        .map(|channel| {
+            let channel_info = properties
+                .channel_info(&channel)
+                .map_err(|err| channel_info_error(&segment_properties_path, err))?;
+            let calibration_slot = calibration_slots.get(&channel).map(|slot| slot.as_str());
+            let data = reader
+                .read_channel_data(&segment_path, &channel_info, calibration_slot)
+                .map_err(|err| channel_data_error(&segment_path, err))?;
+
+            Ok((super::DataIndex::new(index, segment, channel), data))
+        })
+        .collect()
+}
+
+fn into_data(data: Vec<(super::DataIndex, dataset::ChannelData)>) -> super::Data {
+    let (idx, data): (Vec<_>, Vec<_>) = data.into_iter().unzip();
+    let (data, units) = data
+        .into_iter()
+        .map(dataset::ChannelData::into_parts)
+        .unzip();
+    super::Data::new(idx, data, units).expect("indices and data are compatible")
+}
+
+/// Convert an error reading a properties file into a query error.
+fn properties_error(
+    path: impl Into<PathBuf>,
+    error: dataset::error::Properties,
+) -> super::QueryError {
+    match error {
+        dataset::error::Properties::Zip(error) => super::QueryError::ZipFile {
+            archive: None,
+            path: path.into(),
+            error,
+        },
+        dataset::error::Properties::InvalidFormat(error) => super::QueryError::InvalidFormat {
+            archive: None,
+            path: path.into(),
+            cause: error.to_string(),
+        },
+    }
+}
+
+/// Convert an error extracting a property into a query error.
+fn property_error(path: impl Into<PathBuf>, error: PropertyError) -> super::QueryError {
+    super::QueryError::Property {
+        archive: None,
+        path: path.into(),
+        error,
+    }
+}
+
+fn channel_info_error(
+    path: impl Into<PathBuf>,
+    error: dataset::properties::channel::error::Info,
+) -> super::QueryError {
+    match error {
+        dataset::properties::channel::error::Info::Property(error) => property_error(path, error),
+        error @ dataset::properties::channel::error::Info::UnsupportedGenerator { .. } => {
+            super::QueryError::InvalidFormat {
+                archive: None,
+                path: path.into(),
+                cause: error.to_string(),
+            }
+        }
+    }
+}
+
+/// Convert an error reading channel data into a query error.
+///
+/// # Notes
+/// + `path` is the segment path.
+fn channel_data_error(
+    path: impl Into<PathBuf>,
+    error: dataset::error::ChannelData,
+) -> super::QueryError {
+    match error {
+        dataset::error::ChannelData::Zip(error) => super::QueryError::ZipFile {
+            archive: None,
+            path: path.into(),
+            error,
+        },
+        dataset::error::ChannelData::SegmentProperties(error) => properties_error(path, error),
+        dataset::error::ChannelData::Property(error) => property_error(path, error),
+        error @ dataset::error::ChannelData::InvalidDataLength => super::QueryError::InvalidData {
+            archive: None,
+            path: path.into(),
+            cause: error.to_string(),
+        },
+        dataset::error::ChannelData::UnsupportedRasterLayout(layout) => {
+            super::QueryError::InvalidFormat {
+                archive: None,
+                path: path.into(),
+                cause: format!("unsupported raster layout: {layout}"),
+            }
+        }
+        dataset::error::ChannelData::InvalidCalibrationSlot(slot) => {
+            super::QueryError::InvalidCalibrationSlot(slot)
+        }
+        error @ (dataset::error::ChannelData::LcdInfoNotFound(_)
+        | dataset::error::ChannelData::UnsupportedGenerator { .. }) => {
+            super::QueryError::InvalidFormat {
+                archive: None,
+                path: path.into(),
+                cause: error.to_string(),
+            }
+        }
     }
 }
 
 fn metadata_index_from_file_path(
     filename: &str,
-    position_pattern: &PositionPattern,
 ) -> Result<Option<super::MetadataIndex>, zip::result::ZipError> {
     const INDEX_PREFIX: &str = "index/";
-    const SEGMENT_PREFIX: &str = "segments/";
 
     if filename == super::DATASET_PROPERTIES_FILE_PATH {
-        return Ok(Some(super::MetadataIndex::Dataset));
-    } else if filename == format!("{}/{}", SHARED_DATA_DIR, utils::SHARED_DATA_PROPERTIES_FILE) {
-        return Ok(Some(super::MetadataIndex::SharedData));
-    } else if filename.ends_with(utils::SEGMENT_PROPERTIES_FILE) {
+        Ok(Some(super::MetadataIndex::Dataset))
+    } else if filename
+        == format!(
+            "{}/{}",
+            utils::SHARED_DATA_DIR,
+            utils::SHARED_DATA_PROPERTIES_FILE
+        )
+    {
+        Ok(Some(super::MetadataIndex::SharedData))
+    } else if filename.ends_with(dataset::SEGMENT_PROPERTIES_FILE) {
         let Some((index_str, _)) = filename[INDEX_PREFIX.len()..].split_once("/") else {
             return Err(zip::result::ZipError::InvalidArchive(
                 std::borrow::Cow::Borrowed("invalid file path"),
@@ -882,8 +871,9 @@ fn metadata_index_from_file_path(
             ));
         };
 
-        let Some((_, segment_str)) =
-            filename[..filename.len() - utils::SEGMENT_PROPERTIES_FILE.len() - 1].rsplit_once("/")
+        let Some((_, segment_str)) = filename
+            [..filename.len() - dataset::SEGMENT_PROPERTIES_FILE.len() - 1]
+            .rsplit_once("/")
         else {
             return Err(zip::result::ZipError::InvalidArchive(
                 std::borrow::Cow::Borrowed("invalid file path"),
@@ -896,7 +886,7 @@ fn metadata_index_from_file_path(
             ));
         };
 
-        return Ok(Some(super::MetadataIndex::Segment { index, segment }));
+        Ok(Some(super::MetadataIndex::Segment { index, segment }))
     } else if filename.starts_with(INDEX_PREFIX)
         && filename.ends_with(&format!("/{}", utils::INDEX_PROPERTIES_FILE))
     {
@@ -912,42 +902,114 @@ fn metadata_index_from_file_path(
             ));
         };
 
-        return Ok(Some(super::MetadataIndex::Index(index)));
+        Ok(Some(super::MetadataIndex::Index(index)))
     } else {
-        return Ok(None);
+        Ok(None)
     }
 }
 
 struct DatasetProperties;
 impl DatasetProperties {
-    const INDEX_TYPE_KEY: &str = "quantitative-imaging-map.indexes.type";
-    const INDEX_MIN_KEY: &str = "quantitative-imaging-map.indexes.min";
-    const INDEX_MAX_KEY: &str = "quantitative-imaging-map.indexes.max";
+    /// `{prefix}.indexes`
+    const INDEXES_KEY: &str = "indexes";
+    /// `{prefix}.indexes.type`
+    const INDEX_TYPE_KEY: &str = "type";
+    /// `{prefix}.indexes.min`
+    const INDEX_MIN_KEY: &str = "min";
+    /// `{prefix}.indexes.max`
+    const INDEX_MAX_KEY: &str = "max";
+    /// `{prefix}.position-pattern`
+    const POSITION_PATTERN_KEY: &str = "position-pattern";
+}
+
+/// Kind of map held by the dataset.
+/// Determines the namespace of the map properties.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MapType {
+    /// Quantitative imaging map. (`.jpk-qi-data`)
+    QuantitativeImaging,
+
+    /// Force volume map. (`.jpk-force-map`)
+    ForceScan,
+}
+
+impl MapType {
+    pub fn from_str(input: impl AsRef<str>) -> Option<Self> {
+        match input.as_ref() {
+            "quantitative-imaging-map" => Some(Self::QuantitativeImaging),
+            "force-scan-map" => Some(Self::ForceScan),
+            _ => None,
+        }
+    }
+
+    /// Prefix of the map properties in the dataset properties.
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Self::QuantitativeImaging => "quantitative-imaging-map",
+            Self::ForceScan => "force-scan-map",
+        }
+    }
+
+    /// Prefix of the index properties.
+    pub fn index_prefix(&self) -> &'static str {
+        match self {
+            Self::QuantitativeImaging => "quantitative-imaging-series",
+            Self::ForceScan => "force-scan-series",
+        }
+    }
+
+    /// Expected value of the `jpk-data-file` property.
+    pub fn data_file(&self) -> &'static str {
+        match self {
+            Self::QuantitativeImaging => "spm-quantitative-image-data-file",
+            Self::ForceScan => "spm-forcefile",
+        }
+    }
 }
 
 pub struct DatasetInfo {
+    map_type: MapType,
     index: Index,
     position_pattern: PositionPattern,
 }
 
-enum Index {
+impl DatasetInfo {
+    pub fn map_type(&self) -> MapType {
+        self.map_type
+    }
+
+    pub fn index(&self) -> &Index {
+        &self.index
+    }
+
+    pub fn position_pattern(&self) -> &PositionPattern {
+        &self.position_pattern
+    }
+}
+
+pub enum Index {
     Range { min: IndexType, max: IndexType },
 }
 
-struct PositionPattern {
+pub struct PositionPattern {
     numbering: Numbering,
     kind: PositionPatternType,
 }
 
 impl PositionPattern {
-    const TYPE_KEY: &str = "quantitative-imaging-map.position-pattern.type";
-    const NUMBERING_KEY: &str = "quantitative-imaging-map.position-pattern.numbering";
+    /// `{prefix}.position-pattern.type`
+    const TYPE_KEY: &str = "type";
+    /// `{prefix}.position-pattern.numbering`
+    const NUMBERING_KEY: &str = "numbering";
+    /// `{prefix}.position-pattern.grid`
+    const GRID_KEY: &str = "grid";
 }
 
 impl PositionPattern {
-    pub fn from_properties(properties: &Properties) -> Result<Self, PropertyError> {
-        let numbering =
-            properties::extract_value!(properties, Self::NUMBERING_KEY, from_str Numbering)?;
+    /// # Arguments
+    /// + `properties`: The `{prefix}.position-pattern` tree.
+    pub fn from_properties(properties: &PropertyTree) -> Result<Self, PropertyError> {
+        let numbering = properties.get_with(Self::NUMBERING_KEY, Numbering::from_str)?;
 
         let kind = PositionPatternType::from_properties(properties)?;
 
@@ -961,11 +1023,13 @@ impl PositionPattern {
     pub fn pixel_to_index(&self, pixel: &super::Pixel) -> Option<IndexType> {
         match &self.kind {
             PositionPatternType::Grid(grid) => {
-                if pixel.i >= grid.i_length as IndexType || pixel.j >= grid.j_length as IndexType {
+                if pixel.i() >= grid.i_length as IndexType
+                    || pixel.j() >= grid.j_length as IndexType
+                {
                     return None;
                 }
 
-                Some(pixel.j * grid.i_length as IndexType + pixel.i)
+                Some(pixel.to_index(grid.i_length as IndexType))
             }
         }
     }
@@ -980,13 +1044,21 @@ impl PositionPattern {
                     return None;
                 }
                 let x = index % grid.i_length as IndexType;
-                return Some(super::Pixel { i: x, j: y });
+                Some(super::Pixel::new(x, y))
             }
         }
     }
+
+    pub fn numbering(&self) -> &Numbering {
+        &self.numbering
+    }
+
+    pub fn kind(&self) -> &PositionPatternType {
+        &self.kind
+    }
 }
 
-enum Numbering {
+pub enum Numbering {
     LeftToRight,
 }
 
@@ -999,366 +1071,185 @@ impl Numbering {
     }
 }
 
-enum PositionPatternType {
+pub enum PositionPatternType {
     Grid(Grid),
 }
 
 impl PositionPatternType {
-    pub fn from_properties(properties: &Properties) -> Result<Self, PropertyError> {
-        let kind = properties::extract_value!(properties, PositionPattern::TYPE_KEY)?;
-        match kind.as_str() {
+    /// # Arguments
+    /// + `properties`: The `{prefix}.position-pattern` tree.
+    pub fn from_properties(properties: &PropertyTree) -> Result<Self, PropertyError> {
+        let kind = properties.get_str(PositionPattern::TYPE_KEY)?;
+        match kind {
             "grid-position-pattern" => {
-                let grid = Grid::from_properties(properties)?;
+                let grid = Grid::from_properties(&properties.subtree(PositionPattern::GRID_KEY))?;
                 Ok(Self::Grid(grid))
             }
             _ => Err(PropertyError::InvalidValue(
-                PositionPattern::TYPE_KEY.to_string(),
+                properties.key(PositionPattern::TYPE_KEY),
             )),
         }
     }
 }
 
-struct Grid {
+pub struct Grid {
     x_center: f64,
     y_center: f64,
     u_length: f64,
     v_length: f64,
+    theta: f64,
+    reflect: bool,
     unit: String,
     i_length: u16,
     j_length: u16,
 }
 
 impl Grid {
-    /// `quantitative-imaging-map.position-pattern.grid.xcenter`
-    const X_CENTER_KEY: &str = "quantitative-imaging-map.position-pattern.grid.xcenter";
-    /// `quantitative-imaging-map.position-pattern.grid.ycenter`
-    const Y_CENTER_KEY: &str = "quantitative-imaging-map.position-pattern.grid.ycenter";
-    /// `quantitative-imaging-map.position-pattern.grid.ulength`
-    const U_LENGTH_KEY: &str = "quantitative-imaging-map.position-pattern.grid.ulength";
-    /// `quantitative-imaging-map.position-pattern.grid.vlength`
-    const V_LENGTH_KEY: &str = "quantitative-imaging-map.position-pattern.grid.vlength";
-    /// `quantitative-imaging-map.position-pattern.grid.theta`
-    const THETA_KEY: &str = "quantitative-imaging-map.position-pattern.grid.theta";
-    /// `quantitative-imaging-map.position-pattern.grid.reflect`
-    const REFLECT_KEY: &str = "quantitative-imaging-map.position-pattern.grid.reflect";
-    /// `quantitative-imaging-map.position-pattern.grid.unit.unit`
-    const UNIT_KEY: &str = "quantitative-imaging-map.position-pattern.grid.unit.unit";
-    /// `quantitative-imaging-map.position-pattern.grid.ilength`
-    const I_LENGTH_KEY: &str = "quantitative-imaging-map.position-pattern.grid.ilength";
-    /// `quantitative-imaging-map.position-pattern.grid.jlength`
-    const J_LENGTH_KEY: &str = "quantitative-imaging-map.position-pattern.grid.jlength";
+    /// `{prefix}.position-pattern.grid.xcenter`
+    const X_CENTER_KEY: &str = "xcenter";
+    /// `{prefix}.position-pattern.grid.ycenter`
+    const Y_CENTER_KEY: &str = "ycenter";
+    /// `{prefix}.position-pattern.grid.ulength`
+    const U_LENGTH_KEY: &str = "ulength";
+    /// `{prefix}.position-pattern.grid.vlength`
+    const V_LENGTH_KEY: &str = "vlength";
+    /// `{prefix}.position-pattern.grid.theta`
+    const THETA_KEY: &str = "theta";
+    /// `{prefix}.position-pattern.grid.reflect`
+    const REFLECT_KEY: &str = "reflect";
+    /// `{prefix}.position-pattern.grid.unit.unit`
+    const UNIT_KEY: &str = "unit.unit";
+    /// `{prefix}.position-pattern.grid.ilength`
+    const I_LENGTH_KEY: &str = "ilength";
+    /// `{prefix}.position-pattern.grid.jlength`
+    const J_LENGTH_KEY: &str = "jlength";
 }
 
 impl Grid {
-    pub fn from_properties(properties: &Properties) -> Result<Self, PropertyError> {
-        let x_center = properties::extract_value!(properties, Self::X_CENTER_KEY, parse f64)?;
-        let y_center = properties::extract_value!(properties, Self::Y_CENTER_KEY, parse f64)?;
-        let u_length = properties::extract_value!(properties, Self::U_LENGTH_KEY, parse f64)?;
-        let v_length = properties::extract_value!(properties, Self::V_LENGTH_KEY, parse f64)?;
-        let unit = properties::extract_value!(properties, Self::UNIT_KEY)?;
-        let i_length = properties::extract_value!(properties, Self::I_LENGTH_KEY, parse u16)?;
-        let j_length = properties::extract_value!(properties, Self::J_LENGTH_KEY, parse u16)?;
+    /// # Arguments
+    /// + `properties`: The `{prefix}.position-pattern.grid` tree.
+    pub fn from_properties(properties: &PropertyTree) -> Result<Self, PropertyError> {
+        let x_center = properties.get_f64(Self::X_CENTER_KEY)?;
+        let y_center = properties.get_f64(Self::Y_CENTER_KEY)?;
+        let u_length = properties.get_f64(Self::U_LENGTH_KEY)?;
+        let v_length = properties.get_f64(Self::V_LENGTH_KEY)?;
+        let theta = properties.get_f64(Self::THETA_KEY)?;
+        let reflect = properties.get_bool(Self::REFLECT_KEY)?;
+        let unit = properties.get_str(Self::UNIT_KEY)?;
+        let i_length = properties.parse::<u16>(Self::I_LENGTH_KEY)?;
+        let j_length = properties.parse::<u16>(Self::J_LENGTH_KEY)?;
 
         Ok(Self {
             x_center,
             y_center,
             u_length,
             v_length,
-            unit: unit.clone(),
+            theta,
+            reflect,
+            unit: unit.to_string(),
             i_length,
             j_length,
         })
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum DataFileFormat {
-    Raw,
-}
-
-impl DataFileFormat {
-    pub fn from_str(input: impl AsRef<str>) -> Option<Self> {
-        match input.as_ref() {
-            "raw" => Some(Self::Raw),
-            _ => None,
-        }
+impl Grid {
+    /// `x` coordinate of the grid center.
+    pub fn x_center(&self) -> f64 {
+        self.x_center
     }
-}
-
-#[derive(Debug)]
-pub enum DataError {
-    Zip {
-        path: PathBuf,
-        error: zip::result::ZipError,
-    },
-
-    /// The file at the given path had an invalid format.
-    InvalidFormat { path: PathBuf, cause: String },
-
-    /// A channel with the given name does not exist.
-    ChannelNotFound(String),
-
-    /// The data file at the given path contained invalid data.
-    InvalidData { path: PathBuf },
-}
-
-mod utils {
-    use super::{super::DATASET_PROPERTIES_FILE_PATH, IndexType, SegmentType};
-    use std::{fmt, io, path::PathBuf};
-    use zip::ZipArchive;
 
-    pub const INDEX_DIR: &str = "index";
-    pub const SEGMENT_DIR: &str = "segments";
-    pub const SHARED_DATA_DIR: &str = "shared-data";
-    pub const DATASET_PROPERTIES_FILE: &str = "header.properties";
-    pub const INDEX_PROPERTIES_FILE: &str = "header.properties";
-    pub const SEGMENT_PROPERTIES_FILE: &str = "segment-header.properties";
-    pub const SHARED_DATA_PROPERTIES_FILE: &str = "header.properties";
-    pub const PROPERTIES_KEY_SEGMENT_CHANNELS_LIST: &str = "channels.list";
-
-    pub fn properties_path() -> PathBuf {
-        PathBuf::from(DATASET_PROPERTIES_FILE_PATH)
+    /// `y` coordinate of the grid center.
+    pub fn y_center(&self) -> f64 {
+        self.y_center
     }
 
-    pub fn index_properties_path(index: IndexType) -> PathBuf {
-        let path = format!("{INDEX_DIR}/{index}/{INDEX_PROPERTIES_FILE}");
-        PathBuf::from(path)
+    /// Length of the grid along the `i` axis.
+    pub fn u_length(&self) -> f64 {
+        self.u_length
     }
 
-    pub fn index_segment_path(index: IndexType, segment: SegmentType) -> PathBuf {
-        let path = format!("{INDEX_DIR}/{index}/{SEGMENT_DIR}/{segment}");
-        PathBuf::from(path)
+    /// Length of the grid along the `j` axis.
+    pub fn v_length(&self) -> f64 {
+        self.v_length
     }
 
-    pub fn index_segment_properties_path(index: IndexType, segment: SegmentType) -> PathBuf {
-        let path = format!("{INDEX_DIR}/{index}/{SEGMENT_DIR}/{segment}/{SEGMENT_PROPERTIES_FILE}");
-        PathBuf::from(path)
+    /// Rotation of the grid in radians.
+    pub fn theta(&self) -> f64 {
+        self.theta
     }
 
-    pub fn shared_data_properties_path() -> PathBuf {
-        let path = format!("{SHARED_DATA_DIR}/{SHARED_DATA_PROPERTIES_FILE}");
-        PathBuf::from(path)
+    pub fn reflect(&self) -> bool {
+        self.reflect
     }
 
-    pub fn index_data<R>(
-        archive: &mut ZipArchive<R>,
-        index: IndexType,
-    ) -> Result<super::index_data::IndexData, super::super::QueryError>
-    where
-        R: io::Read + io::Seek,
-    {
-        use super::{super::QueryError, PropertyError, index_data, properties};
-
-        let index_properties_path = index_properties_path(index);
-        let mut file =
-            archive
-                .by_path(&index_properties_path)
-                .map_err(|error| QueryError::ZipFile {
-                    path: index_properties_path.clone(),
-                    error,
-                })?;
-        let properties =
-            properties::Properties::new(&mut file).map_err(|_err| QueryError::InvalidFormat {
-                path: index_properties_path.clone(),
-                cause: "invalid property file".to_string(),
-            })?;
-
-        index_data::IndexData::from_properties(&properties).map_err(|err| match err {
-            PropertyError::NotFound(key) => QueryError::InvalidFormat {
-                path: index_properties_path.clone(),
-                cause: format!("property `{key}` not found"),
-            },
-            PropertyError::InvalidValue(key) => QueryError::InvalidFormat {
-                path: index_properties_path.clone(),
-                cause: format!("invalid value for `{key}`"),
-            },
-        })
+    /// Unit of the grid lengths and center.
+    pub fn unit(&self) -> &String {
+        &self.unit
     }
 
-    pub fn segment_properties<R>(
-        archive: &mut zip::ZipArchive<R>,
-        index: IndexType,
-        segment: SegmentType,
-    ) -> Result<super::SegmentProperties, super::super::QueryError>
-    where
-        R: io::Read + io::Seek,
-    {
-        use super::{super::QueryError, properties};
-
-        let segment_properties_path = index_segment_properties_path(index, segment);
-        let mut properties =
-            archive
-                .by_path(&segment_properties_path)
-                .map_err(|error| QueryError::ZipFile {
-                    path: segment_properties_path.clone(),
-                    error,
-                })?;
-        let properties = properties::Properties::new(&mut properties).map_err(|_| {
-            QueryError::InvalidFormat {
-                path: segment_properties_path.clone(),
-                cause: "file could not be read as properties".to_string(),
-            }
-        })?;
-
-        Ok(super::SegmentProperties { inner: properties })
-    }
-
-    /// # Notes
-    /// + `index` only used for error reporting.
-    pub fn segment_data(
-        properties: &super::SegmentProperties,
-        index: IndexType,
-    ) -> Result<super::segment_data::SegmentData, super::super::QueryError> {
-        use super::{super::QueryError, PropertyError, segment_data};
-
-        segment_data::SegmentData::from(properties).map_err(|err| match err {
-            PropertyError::NotFound(key) => QueryError::InvalidFormat {
-                path: index_properties_path(index),
-                cause: format!("property `{key}` not found"),
-            },
-            PropertyError::InvalidValue(key) => QueryError::InvalidFormat {
-                path: index_properties_path(index),
-                cause: format!("invalid value for `{key}`"),
-            },
-        })
+    /// Number of pixels along the `i` axis.
+    pub fn i_length(&self) -> u16 {
+        self.i_length
     }
 
-    /// # Notes
-    /// + `index` and `segment` only used for error reporting.
-    pub fn channel_data(
-        segment_properties: &super::SegmentProperties,
-        channel: impl fmt::Display,
-        index: IndexType,
-        segment: SegmentType,
-    ) -> Result<super::channel_data::ChannelData, super::super::QueryError> {
-        use super::{super::QueryError, PropertyError, channel_data};
-
-        channel_data::ChannelData::from(segment_properties, &channel).map_err(|err| match err {
-            PropertyError::NotFound(key) => QueryError::InvalidFormat {
-                path: index_segment_properties_path(index, segment),
-                cause: format!("property `{key}` not found"),
-            },
-            PropertyError::InvalidValue(key) => QueryError::InvalidFormat {
-                path: index_segment_properties_path(index, segment),
-                cause: format!("invalid value of `{key}`"),
-            },
-        })
+    /// Number of pixels along the `j` axis.
+    pub fn j_length(&self) -> u16 {
+        self.j_length
     }
 }
 
-mod index_data {
-    use super::SegmentType;
-    use super::properties::{self, Properties, PropertyError};
+#[derive(Debug)]
+pub enum DataError {
+    Zip {
+        path: PathBuf,
+        error: zip::result::ZipError,
+    },
 
-    pub struct IndexData {
-        segment_count: SegmentType,
-    }
+    /// The file at the given path had an invalid format.
+    InvalidFormat { path: PathBuf, cause: String },
 
-    impl IndexData {
-        const SEGMENT_COUNT_KEY: &str = "quantitative-imaging-series.force-segments.count";
-    }
+    /// A property of the file at the given path was missing or invalid.
+    Property { path: PathBuf, error: PropertyError },
 
-    impl IndexData {
-        pub fn from_properties(properties: &Properties) -> Result<Self, PropertyError> {
-            let segment_count =
-                properties::extract_value!(properties, Self::SEGMENT_COUNT_KEY, parse SegmentType)?;
+    /// A channel with the given name does not exist.
+    ChannelNotFound(String),
 
-            Ok(Self { segment_count })
-        }
-    }
+    /// The data file at the given path contained invalid data.
+    InvalidData { path: PathBuf, cause: String },
 
-    impl IndexData {
-        pub fn segment_count(&self) -> SegmentType {
-            self.segment_count
-        }
-    }
+    /// The calibration slot does not exist for the channel.
+    InvalidCalibrationSlot(String),
 }
 
-mod segment_data {
-    use super::SegmentProperties;
-    use super::properties::{self, PropertyError};
-
-    pub struct SegmentData {
-        channels: Vec<String>,
-    }
-
-    impl SegmentData {
-        const CHANNEL_LIST_KEY: &str = "channels.list";
-    }
-
-    impl SegmentData {
-        pub fn from(properties: &SegmentProperties) -> Result<Self, PropertyError> {
-            let channels = properties::extract_value!(properties, Self::CHANNEL_LIST_KEY)?;
-            let channels = channels
-                .split_ascii_whitespace()
-                .map(|channel| channel.to_string())
-                .collect();
-
-            Ok(Self { channels })
-        }
-
-        pub fn channels(&self) -> &Vec<String> {
-            &self.channels
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Zip { path, .. } => write!(f, "could not read `{}`", path.display()),
+            Self::InvalidFormat { path, cause } => {
+                write!(f, "invalid format of `{}`: {cause}", path.display())
+            }
+            Self::Property { path, .. } => write!(f, "invalid property in `{}`", path.display()),
+            Self::ChannelNotFound(channel) => write!(f, "channel `{channel}` not found"),
+            Self::InvalidData { path, cause } => {
+                write!(f, "invalid data in `{}`: {cause}", path.display())
+            }
+            Self::InvalidCalibrationSlot(slot) => {
+                write!(f, "calibration slot `{slot}` does not exist")
+            }
         }
     }
 }
 
-mod channel_data {
-    use super::{
-        DataFileFormat,
-        properties::{self, PropertyError},
-    };
-    use crate::qi_map::v2_0::SegmentProperties;
-    use std::{fmt, path::PathBuf};
-
-    #[derive(Debug)]
-    pub struct ChannelData {
-        file_path: PathBuf,
-        file_format: DataFileFormat,
-        num_points: usize,
-        shared_data_index: usize,
-    }
-
-    impl ChannelData {
-        #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
-        pub fn from(
-            properties: &SegmentProperties,
-            channel: impl fmt::Display,
-        ) -> Result<Self, PropertyError> {
-            let file_path = properties::extract_value!(
-                properties,
-                SegmentProperties::channel_data_file_name_key(&channel)
-            )?;
-            let file_format = properties::extract_value!(properties,SegmentProperties::channel_data_file_format_key(&channel), from_str DataFileFormat )?;
-            let num_points = properties::extract_value!(properties, SegmentProperties::channel_data_num_points_key(&channel), parse usize)?;
-            let shared_data_index = properties::extract_value!(properties, SegmentProperties::channel_shared_data_index_key(&channel), parse usize)?;
-
-            let data = Self {
-                file_path: PathBuf::from(file_path),
-                file_format,
-                num_points,
-                shared_data_index,
-            };
-            #[cfg(feature = "tracing")]
-            tracing::trace!(?data);
-
-            Ok(data)
-        }
-    }
-
-    impl ChannelData {
-        pub fn file_path(&self) -> &PathBuf {
-            &self.file_path
-        }
-
-        pub fn file_format(&self) -> DataFileFormat {
-            self.file_format
-        }
-        pub fn num_points(&self) -> usize {
-            self.num_points
-        }
-        pub fn shared_data_index(&self) -> usize {
-            self.shared_data_index
+impl std::error::Error for DataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Zip { error, .. } => Some(error),
+            Self::Property { error, .. } => Some(error),
+            Self::InvalidFormat { .. }
+            | Self::ChannelNotFound(_)
+            | Self::InvalidData { .. }
+            | Self::InvalidCalibrationSlot(_) => None,
         }
     }
 }
@@ -1,95 +1,635 @@
 //! Real time oscilliscope data reader.
 //! (`.out`)
+//!
+//! Files start with a header of `# key: value` lines,
+//! followed by whitespace separated rows of values.
 
-use polars::{
-    error::{ErrString, PolarsError},
-    prelude::*,
-};
+use polars::{error::ErrString, prelude::*};
+use rayon::prelude::*;
 use std::{
+    any::Any,
     fs::File,
-    io::{self, BufRead, BufReader, Seek},
-    path::Path,
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 
+const COMMENT_PREFIX: &str = "#";
+const COMMENT_PREFIX_BYTE: u8 = b'#';
+const HEADER_KEY_SEPARATOR: char = ':';
+/// Approximate size of the chunks parsed in parallel, in bytes.
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// Load the data of a scope file.
+///
+/// See [`ScopeFile`] to also read the header metadata.
 pub fn load_data(uri: impl AsRef<Path>) -> PolarsResult<LazyFrame> {
-    const COMMENT_PREFIX: &str = "#";
-    const COLUMNS_LINE_KEY: &str = "# columns: ";
-    const FIELD_SEPARATOR: char = ' ';
-    const FIELD_SEPARATOR_BYTE: u8 = b' ';
-
-    let uri: &Path = uri.as_ref();
-    let mut file = File::open(uri)?;
-    let reader = BufReader::new(&file);
-    let mut labels = None;
-    for line in reader.lines() {
-        let line = line?;
-        if let Some(col_line) = line.strip_prefix(COLUMNS_LINE_KEY) {
-            let _ = labels.insert(
-                col_line
-                    .split_ascii_whitespace()
-                    .map(|col| col.to_string())
-                    .collect::<Vec<_>>(),
-            );
-            break;
+    let file = ScopeFile::open(uri.as_ref()).map_err(error::Scope::into_polars)?;
+    file.load_data().map_err(error::Scope::into_polars)
+}
+
+/// Scope file with its header metadata.
+pub struct ScopeFile {
+    path: PathBuf,
+    metadata: Metadata,
+    /// Names of the columns.
+    columns: Vec<String>,
+    /// Byte offset of the first data row.
+    data_start: u64,
+    /// Line number of the first data row.
+    data_line: usize,
+}
+
+impl ScopeFile {
+    /// Read the header of the scope file at `path`.
+    /// If the header does not name the columns, the first row is used as the column names.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, error::Scope> {
+        let path = path.into();
+        let io_error = |error| error::Scope::Io {
+            path: path.clone(),
+            error,
+        };
+
+        let mut reader = BufReader::new(File::open(&path).map_err(io_error)?);
+        let metadata = Metadata::from_reader(&mut reader).map_err(io_error)?;
+        let columns = if metadata.columns.is_empty() {
+            let mut line = String::new();
+            reader.read_line(&mut line).map_err(io_error)?;
+            line.split_ascii_whitespace().map(String::from).collect()
+        } else {
+            metadata
+                .columns
+                .iter()
+                .map(|column| column.name.clone())
+                .collect()
+        };
+        let data_start = reader.stream_position().map_err(io_error)?;
+
+        let mut header = Vec::new();
+        File::open(&path)
+            .and_then(|file| file.take(data_start).read_to_end(&mut header))
+            .map_err(io_error)?;
+        let data_line = header.iter().filter(|c| **c == b'\n').count() + 1;
+        Ok(Self {
+            path,
+            metadata,
+            columns,
+            data_start,
+            data_line,
+        })
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Lazily scan the data of the file.
+    /// Columns are named by the `columns` header.
+    /// If the header does not name the columns, the first row is used as the column names.
+    ///
+    /// Selecting columns and slicing rows of the returned frame is pushed down to the scan,
+    /// so only the selected data is parsed.
+    /// Values may be separated by any amount of whitespace.
+    ///
+    /// # Errors
+    /// Malformed rows, including rows missing values, are reported when the frame is collected.
+    pub fn load_data(&self) -> Result<LazyFrame, error::Scope> {
+        let scan = RowScan {
+            path: self.path.clone(),
+            num_columns: self.columns.len(),
+            data_start: self.data_start,
+            data_line: self.data_line,
+        };
+        let df = LazyFrame::anonymous_scan(
+            Arc::new(scan),
+            ScanArgsAnonymous {
+                schema: Some(Arc::new(self.schema())),
+                name: "scope",
+                ..Default::default()
+            },
+        )?;
+        Ok(df)
+    }
+
+    /// All columns are `f64`.
+    fn schema(&self) -> Schema {
+        self.columns
+            .iter()
+            .map(|name| Field::new(name.as_str().into(), DataType::Float64))
+            .collect()
+    }
+}
+
+/// Scan of the data rows of a scope file.
+/// Supports projection and row limit pushdown.
+struct RowScan {
+    path: PathBuf,
+    num_columns: usize,
+    /// Byte offset of the first data row.
+    data_start: u64,
+    /// Line number of the first data row.
+    data_line: usize,
+}
+
+impl AnonymousScan for RowScan {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn scan(&self, scan_opts: AnonymousScanArgs) -> PolarsResult<DataFrame> {
+        let schema = scan_opts.schema;
+        let projection = match scan_opts.with_columns {
+            Some(columns) => columns
+                .iter()
+                .map(|name| schema.try_index_of(name))
+                .collect::<PolarsResult<Vec<_>>>()?,
+            None => (0..self.num_columns).collect(),
+        };
+
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        reader.seek(SeekFrom::Start(self.data_start))?;
+        let (height, values) = parse_rows(
+            reader,
+            self.data_line,
+            self.num_columns,
+            &projection,
+            scan_opts.n_rows,
+        )
+        .map_err(error::Scope::into_polars)?;
+        if projection.is_empty() {
+            return Ok(DataFrame::empty_with_height(height));
         }
+
+        let columns = std::iter::zip(projection, values)
+            .map(|(idx, values)| {
+                let (name, _) = schema.get_at_index(idx).expect("column is in schema");
+                polars::prelude::Column::new(name.clone(), values)
+            })
+            .collect::<Vec<_>>();
+        DataFrame::new(columns)
+    }
+
+    fn allows_projection_pushdown(&self) -> bool {
+        true
     }
 
-    let Some(labels) = labels else {
-        let uri_str = uri.as_os_str().to_str().unwrap();
-        let reader = LazyCsvReader::new(PlPath::new(uri_str))
-            .with_comment_prefix(Some(PlSmallStr::from_str(COMMENT_PREFIX)))
-            .with_separator(FIELD_SEPARATOR_BYTE);
+    fn allows_slice_pushdown(&self) -> bool {
+        true
+    }
+}
 
-        return Ok(reader.finish().unwrap());
-    };
+/// Metadata of a scope file.
+#[derive(Debug, Default)]
+pub struct Metadata {
+    header: Vec<(String, String)>,
+    columns: Vec<Column>,
+    date: Option<String>,
+    time_base: Option<TimeBase>,
+}
 
-    let mut cols = vec![vec![]; labels.len()];
-    file.seek(io::SeekFrom::Start(0))?;
-    let reader = BufReader::new(&file);
-    for (idx, line) in reader.lines().enumerate() {
-        let line = line?;
-        let line = line.trim();
-        if line.len() == 0 || line.starts_with(COMMENT_PREFIX) {
-            continue;
+impl Metadata {
+    /// `# columns: ...`
+    pub const COLUMNS_KEY: &str = "columns";
+    /// `# fancyNames: ...`
+    pub const FANCY_NAMES_KEY: &str = "fancyNames";
+    /// `# units: ...`
+    pub const UNITS_KEY: &str = "units";
+    /// `# date: ...`
+    pub const DATE_KEY: &str = "date";
+    /// Sample rate in Hz.
+    pub const SAMPLE_RATE_KEYS: [&str; 2] = ["sampleRate", "sample-rate"];
+    /// Unit of a time column.
+    const TIME_UNIT: &str = "s";
+}
+
+impl Metadata {
+    /// Read the header lines of a scope file.
+    /// Reading stops at the first data row, which is not consumed.
+    pub fn from_reader(mut reader: impl BufRead) -> io::Result<Self> {
+        let mut header = Vec::new();
+        let mut line = String::new();
+        while let Some(&COMMENT_PREFIX_BYTE | b'\r' | b'\n') = reader.fill_buf()?.first() {
+            line.clear();
+            reader.read_line(&mut line)?;
+            let Some(entry) = line.trim().strip_prefix(COMMENT_PREFIX) else {
+                continue;
+            };
+            if let Some((key, value)) = entry.split_once(HEADER_KEY_SEPARATOR) {
+                header.push((key.trim().to_string(), value.trim().to_string()));
+            }
         }
 
-        dbg!(line.split(FIELD_SEPARATOR).collect::<Vec<_>>());
+        Ok(Self::from_header(header))
+    }
+
+    /// # Arguments
+    /// + `header`: `(key, value)` pairs of the header.
+    pub fn from_header(header: Vec<(String, String)>) -> Self {
+        let get = |key: &str| {
+            header
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.as_str())
+        };
 
-        let values = line
-            .split(FIELD_SEPARATOR)
-            .filter(|v| v.trim() != "")
-            .map(|v| {
-                v.parse::<f64>().map_err(|_| {
-                    eprintln!("could not parse line {idx}, element {v}");
-                    PolarsError::ComputeError(ErrString::new_static("could not parse value"))
-                })
+        let names = get(Self::COLUMNS_KEY)
+            .map(|value| value.split_ascii_whitespace().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let fancy_names = get(Self::FANCY_NAMES_KEY)
+            .map(split_quoted)
+            .unwrap_or_default();
+        let units = get(Self::UNITS_KEY)
+            .map(|value| value.split_ascii_whitespace().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let columns = names
+            .into_iter()
+            .enumerate()
+            .map(|(idx, name)| Column {
+                name: name.to_string(),
+                fancy_name: fancy_names.get(idx).cloned(),
+                unit: units.get(idx).map(|unit| unit.to_string()),
             })
-            .collect::<Result<Vec<_>, _>>()?;
-        if values.len() != cols.len() {
-            todo!("handle unexpected number of columns");
+            .collect::<Vec<_>>();
+
+        let sample_rate = Self::SAMPLE_RATE_KEYS
+            .iter()
+            .find_map(|key| get(key))
+            .and_then(|value| value.split_ascii_whitespace().next()?.parse::<f64>().ok());
+        let time_base = match sample_rate {
+            Some(rate) => Some(TimeBase::SampleRate(rate)),
+            None => columns
+                .iter()
+                .find(|column| column.unit.as_deref() == Some(Self::TIME_UNIT))
+                .map(|column| TimeBase::Column(column.name.clone())),
+        };
+
+        let date = get(Self::DATE_KEY).map(String::from);
+        Self {
+            header,
+            columns,
+            date,
+            time_base,
         }
+    }
+}
+
+impl Metadata {
+    /// All `(key, value)` pairs of the header, in file order.
+    pub fn header(&self) -> &Vec<(String, String)> {
+        &self.header
+    }
+
+    /// Value of a header entry.
+    pub fn get(&self, key: impl AsRef<str>) -> Option<&str> {
+        let key = key.as_ref();
+        self.header
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn columns(&self) -> &Vec<Column> {
+        &self.columns
+    }
+
+    pub fn column(&self, name: impl AsRef<str>) -> Option<&Column> {
+        let name = name.as_ref();
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    pub fn date(&self) -> Option<&String> {
+        self.date.as_ref()
+    }
+
+    pub fn time_base(&self) -> Option<&TimeBase> {
+        self.time_base.as_ref()
+    }
+}
+
+/// Column of a scope file.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Column {
+    name: String,
+    fancy_name: Option<String>,
+    unit: Option<String>,
+}
+
+impl Column {
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Display name of the column.
+    pub fn fancy_name(&self) -> Option<&String> {
+        self.fancy_name.as_ref()
+    }
+
+    pub fn unit(&self) -> Option<&String> {
+        self.unit.as_ref()
+    }
+}
+
+/// How sample times are determined.
+#[derive(Clone, PartialEq, Debug)]
+pub enum TimeBase {
+    /// Samples are evenly spaced at the rate, in Hz.
+    SampleRate(f64),
+
+    /// Sample times are recorded in the column, in seconds.
+    Column(String),
+}
+
+/// Parse the data rows of a scope file in parallel.
+/// The data is read in chunks of about [`CHUNK_SIZE`] bytes,
+/// and reading stops once `num_rows` rows are parsed.
+///
+/// # Arguments
+/// + `reader`: Reader positioned at the first data row. Comment lines are skipped.
+/// + `first_line`: Line number of the first data row in the file.
+/// + `num_columns`: Number of values in each row.
+/// + `projection`: Indices of the columns to collect.
+/// + `num_rows`: Maximum number of rows to parse.
+///
+/// # Returns
+/// Number of rows, and the values of each projected column.
+fn parse_rows(
+    mut reader: impl BufRead,
+    first_line: usize,
+    num_columns: usize,
+    projection: &[usize],
+    num_rows: Option<usize>,
+) -> Result<(usize, Vec<Vec<f64>>), error::Scope> {
+    let mut slots = vec![None; num_columns];
+    for (slot, &column) in projection.iter().enumerate() {
+        slots[column] = Some(slot);
+    }
 
-        for (idx, value) in values.into_iter().enumerate() {
-            cols[idx].push(value)
+    let batch_size = rayon::current_num_threads().max(1);
+    let mut line = first_line;
+    let mut height = 0;
+    let mut values = vec![Vec::new(); projection.len()];
+    loop {
+        let mut batch = Vec::with_capacity(batch_size);
+        for _ in 0..batch_size {
+            let chunk = read_chunk(&mut reader).map_err(error::Scope::Polars)?;
+            if chunk.is_empty() {
+                break;
+            }
+            batch.push(chunk);
+        }
+        if batch.is_empty() {
+            break;
+        }
+
+        let parsed = batch
+            .par_iter()
+            .map(|chunk| parse_chunk(chunk, &slots))
+            .collect::<Vec<_>>();
+        for chunk in parsed {
+            let chunk = chunk.map_err(|err| err.into_scope(line))?;
+            line += chunk.lines;
+            height += chunk.rows;
+            for (values, chunk) in values.iter_mut().zip(chunk.values) {
+                values.extend(chunk);
+            }
+        }
+        if num_rows.is_some_and(|num_rows| height >= num_rows) {
+            break;
+        }
+    }
+
+    if let Some(num_rows) = num_rows {
+        height = height.min(num_rows);
+        values
+            .iter_mut()
+            .for_each(|values| values.truncate(num_rows));
+    }
+    Ok((height, values))
+}
+
+/// Read about [`CHUNK_SIZE`] bytes, extended to the end of the line.
+///
+/// # Returns
+/// Whole lines, or an empty chunk at the end of the data.
+fn read_chunk(reader: &mut impl BufRead) -> PolarsResult<Vec<u8>> {
+    let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+    reader
+        .by_ref()
+        .take(CHUNK_SIZE as u64)
+        .read_to_end(&mut chunk)?;
+    if chunk.last().is_some_and(|c| *c != b'\n') {
+        reader.read_until(b'\n', &mut chunk)?;
+    }
+    Ok(chunk)
+}
+
+/// Values parsed from a chunk of rows.
+struct Chunk {
+    /// Number of lines in the chunk, including skipped lines.
+    lines: usize,
+    rows: usize,
+    values: Vec<Vec<f64>>,
+}
+
+/// Error parsing a row, located by its line index in the chunk.
+enum RowError {
+    InvalidRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidValue {
+        line: usize,
+        column: usize,
+        value: String,
+    },
+}
+
+impl RowError {
+    /// # Arguments
+    /// + `first_line`: Line number of the first line of the chunk.
+    fn into_scope(self, first_line: usize) -> error::Scope {
+        match self {
+            Self::InvalidRow {
+                line,
+                expected,
+                found,
+            } => error::Scope::InvalidRow {
+                line: first_line + line,
+                expected,
+                found,
+            },
+            Self::InvalidValue {
+                line,
+                column,
+                value,
+            } => error::Scope::InvalidValue {
+                line: first_line + line,
+                column,
+                value,
+            },
         }
     }
+}
 
-    let cols = std::iter::zip(labels, cols)
-        .map(|(label, data)| Column::new(label.into(), data))
-        .collect::<Vec<_>>();
+/// # Arguments
+/// + `chunk`: Whole lines.
+/// + `slots`: Index of each column in the output, or `None` if it is not collected.
+fn parse_chunk(chunk: &[u8], slots: &[Option<usize>]) -> Result<Chunk, RowError> {
+    let num_slots = slots.iter().flatten().count();
+    let mut lines = 0;
+    let mut rows = 0;
+    let mut values = vec![Vec::with_capacity(chunk.len() / 32); num_slots];
+    for (index, line) in chunk.split(|c| *c == b'\n').enumerate() {
+        lines = index;
+        let line = line.trim_ascii();
+        if line.is_empty() || line[0] == COMMENT_PREFIX_BYTE {
+            continue;
+        }
+
+        let mut found = 0;
+        for field in line
+            .split(|c| c.is_ascii_whitespace())
+            .filter(|field| !field.is_empty())
+        {
+            if let Some(Some(slot)) = slots.get(found) {
+                let value = std::str::from_utf8(field)
+                    .ok()
+                    .and_then(|field| field.parse::<f64>().ok())
+                    .ok_or_else(|| RowError::InvalidValue {
+                        line: index,
+                        column: found,
+                        value: String::from_utf8_lossy(field).into_owned(),
+                    })?;
+                values[*slot].push(value);
+            }
+            found += 1;
+        }
+        if found != slots.len() {
+            return Err(RowError::InvalidRow {
+                line: index,
+                expected: slots.len(),
+                found,
+            });
+        }
+        rows += 1;
+    }
 
-    let df = DataFrame::new(cols).unwrap();
-    Ok(df.lazy())
+    Ok(Chunk {
+        lines,
+        rows,
+        values,
+    })
+}
+
+/// Split a list of double quoted values.
+/// Unquoted values are split on whitespace.
+fn split_quoted(input: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut rest = input.trim_start();
+    while !rest.is_empty() {
+        let (value, remainder) = match rest.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((value, remainder)) => (value, remainder),
+                None => (quoted, ""),
+            },
+            None => rest.split_once(char::is_whitespace).unwrap_or((rest, "")),
+        };
+        values.push(value.to_string());
+        rest = remainder.trim_start();
+    }
+    values
+}
+
+pub mod error {
+    use polars::error::PolarsError;
+    use std::{fmt, io, path::PathBuf};
+
+    #[derive(derive_more::From, Debug)]
+    pub enum Scope {
+        /// The file at the given path could not be read.
+        #[from(ignore)]
+        Io {
+            path: PathBuf,
+            error: io::Error,
+        },
+
+        /// A row does not have a value for each column.
+        #[from(ignore)]
+        InvalidRow {
+            line: usize,
+            expected: usize,
+            found: usize,
+        },
+
+        /// A value could not be parsed as a number.
+        #[from(ignore)]
+        InvalidValue {
+            line: usize,
+            column: usize,
+            value: String,
+        },
+
+        Polars(PolarsError),
+    }
+
+    impl Scope {
+        pub(super) fn into_polars(self) -> PolarsError {
+            match self {
+                Self::Polars(err) => err,
+                Self::Io { error, .. } => PolarsError::from(error),
+                err => PolarsError::ComputeError(super::ErrString::from(err.to_string())),
+            }
+        }
+    }
+
+    impl fmt::Display for Scope {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Io { path, .. } => write!(f, "could not read `{}`", path.display()),
+                Self::InvalidRow {
+                    line,
+                    expected,
+                    found,
+                } => write!(f, "line {line}: expected {expected} values, found {found}"),
+                Self::InvalidValue {
+                    line,
+                    column,
+                    value,
+                } => write!(f, "line {line}, column {column}: invalid value `{value}`"),
+                Self::Polars(_) => write!(f, "could not build data frame"),
+            }
+        }
+    }
+
+    impl std::error::Error for Scope {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::Io { error, .. } => Some(error),
+                Self::Polars(err) => Some(err),
+                Self::InvalidRow { .. } | Self::InvalidValue { .. } => None,
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::path::PathBuf;
 
     const DATA_DIR: &str = "../data/scope";
     const DATA_FILE: &str = "time-current-deflection.out";
 
+    const HEADER: &str = "# date: 2026-01-08 16:35:28 CET
+# columns: t current vDeflection
+# fancyNames: \"Time\" \"Current\" \"Vertical Deflection\"
+# units: s A V
+";
+
     #[test]
     fn load_data_test() {
         let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -98,4 +638,90 @@ mod test {
 
         let _df = load_data(data_path).unwrap();
     }
+
+    #[test]
+    fn metadata_from_reader() {
+        let metadata = Metadata::from_reader(HEADER.as_bytes()).unwrap();
+        assert_eq!(metadata.date().unwrap(), "2026-01-08 16:35:28 CET");
+        assert_eq!(metadata.columns().len(), 3);
+
+        let column = metadata.column("vDeflection").unwrap();
+        assert_eq!(column.fancy_name().unwrap(), "Vertical Deflection");
+        assert_eq!(column.unit().unwrap(), "V");
+        assert_eq!(
+            metadata.time_base(),
+            Some(&TimeBase::Column("t".to_string()))
+        );
+
+        let header = format!("{HEADER}# sampleRate: 1000.0\n");
+        let metadata = Metadata::from_reader(header.as_bytes()).unwrap();
+        assert_eq!(metadata.time_base(), Some(&TimeBase::SampleRate(1000.0)));
+    }
+
+    #[test]
+    fn metadata_from_reader_keeps_data() {
+        let data = format!("{HEADER}\n0.0 1e-9 0.5\n");
+        let mut reader = data.as_bytes();
+        Metadata::from_reader(&mut reader).unwrap();
+        assert_eq!(reader, b"0.0 1e-9 0.5\n");
+    }
+
+    #[test]
+    fn parse_rows_test() {
+        let data = format!("{HEADER}0.0 1e-9 0.5\n\n 0.001  2e-9\t0.6 \n0.002 3e-9 0.7");
+        let (height, values) = parse_rows(data.as_bytes(), 1, 3, &[2, 1], None).unwrap();
+        assert_eq!(height, 3);
+        assert_eq!(values, vec![vec![0.5, 0.6, 0.7], vec![1e-9, 2e-9, 3e-9]]);
+
+        let (height, values) = parse_rows(data.as_bytes(), 1, 3, &[], Some(2)).unwrap();
+        assert_eq!(height, 2);
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn parse_rows_invalid() {
+        let data = format!("{HEADER}0.0 1e-9 0.5\n0.001 2e-9\n");
+        let err = parse_rows(data.as_bytes(), 1, 3, &[0], None).unwrap_err();
+        assert!(matches!(
+            err,
+            error::Scope::InvalidRow {
+                line: 6,
+                expected: 3,
+                found: 2
+            }
+        ));
+
+        let data = format!("{HEADER}0.0 x 0.5\n");
+        let err = parse_rows(data.as_bytes(), 1, 3, &[1], None).unwrap_err();
+        assert!(matches!(
+            err,
+            error::Scope::InvalidValue {
+                line: 5,
+                column: 1,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_rows_chunks() {
+        let row = "0.001 2e-9 0.6\n";
+        let num_rows = CHUNK_SIZE / row.len() + 10;
+        let data = format!("{HEADER}{}0.0 x 0.5\n", row.repeat(num_rows));
+        let mut reader = data.as_bytes();
+        let chunk = read_chunk(&mut reader).unwrap();
+        assert!(chunk.len() > CHUNK_SIZE);
+        assert!(chunk.ends_with(b"\n"));
+
+        let err = parse_rows(data.as_bytes(), 1, 3, &[1], None).unwrap_err();
+        assert!(matches!(
+            err,
+            error::Scope::InvalidValue { line, column: 1, .. } if line == num_rows + 5
+        ));
+
+        let data = format!("{HEADER}{}", row.repeat(num_rows));
+        let (height, values) = parse_rows(data.as_bytes(), 1, 3, &[2], Some(3)).unwrap();
+        assert_eq!(height, 3);
+        assert_eq!(values, vec![vec![0.6; 3]]);
+    }
 }
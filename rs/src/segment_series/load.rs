@@ -0,0 +1,208 @@
+//! Loading of segment series datasets.
+use super::{SegmentColumns, combine_segments, concat_columns};
+use crate::dataset::v2_0::{self as dataset, DatasetReader};
+use polars::prelude::{self as pl, IntoColumn};
+use std::{fs, io, path::PathBuf};
+
+const SEGMENT_HEADER_PROPERTY_KEY: &str = "force-segment-header";
+const NUM_POINTS_PROPERTY_KEY: &str = "num-points";
+
+/// Loads data from all segments and all channels.
+///
+/// # Returns
+/// Data frame with a `segment` column and a column for each channel.
+/// Channels that are not present in a segment are null for that segment.
+pub(crate) fn load_data_all<R>(
+    reader: &mut DatasetReader<R>,
+    segments_count: dataset::SegmentType,
+) -> Result<pl::DataFrame, error::DataFile>
+where
+    R: io::Read + io::Seek,
+{
+    if segments_count == 0 {
+        return Ok(pl::DataFrame::empty());
+    }
+
+    let mut segments = Vec::with_capacity(segments_count as usize);
+    for segment in 0..segments_count {
+        let segment_path = dataset::utils::segment_path(segment);
+        let properties = reader.segment_properties(&segment_path)?;
+        let length = properties
+            .subtree(SEGMENT_HEADER_PROPERTY_KEY)
+            .parse::<usize>(NUM_POINTS_PROPERTY_KEY)?;
+        let channels = properties.channel_list()?;
+        let mut columns = Vec::with_capacity(channels.len());
+        for channel in channels {
+            let info = properties.channel_info(channel)?;
+            let data = reader
+                .read_channel_data(&segment_path, &info, None)?
+                .into_data();
+            if data.len() != length {
+                return Err(error::DataFile::InvalidDataLength {
+                    segment,
+                    channel: channel.to_string(),
+                });
+            }
+
+            columns.push(pl::Float64Chunked::from_vec(channel.into(), data).into_column());
+        }
+
+        segments.push(SegmentColumns {
+            segment,
+            length,
+            columns,
+        });
+    }
+
+    Ok(combine_segments(segments)?)
+}
+
+/// Stack the data of multiple datasets, adding their `(x, y)` position as columns.
+pub(crate) fn stack_with_position(
+    data: Vec<((f64, f64), pl::DataFrame)>,
+) -> Result<pl::DataFrame, pl::PolarsError> {
+    if data.is_empty() {
+        return Ok(pl::DataFrame::empty());
+    }
+
+    let (idx, df) = data.into_iter().unzip::<_, _, Vec<_>, Vec<_>>();
+    let (xcols, ycols) = idx
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (x, y))| {
+            let length = df[idx].height();
+            let xcol = pl::Column::new_scalar(
+                "x".into(),
+                pl::Scalar::new(pl::DataType::Float64, x.into()),
+                length,
+            );
+            let ycol = pl::Column::new_scalar(
+                "y".into(),
+                pl::Scalar::new(pl::DataType::Float64, y.into()),
+                length,
+            );
+            (xcol, ycol)
+        })
+        .unzip::<_, _, Vec<_>, Vec<_>>();
+
+    let xcol = concat_columns(xcols)?.expect("at least one x col should exist");
+    let ycol = concat_columns(ycols)?.expect("at least one y col should exist");
+    let mut df = df.into_iter();
+    let mut acc = df.next().expect("at least one data frame should exist");
+    for elm in df {
+        acc.vstack_mut_owned(elm)?;
+    }
+
+    acc.with_column(xcol)?;
+    acc.with_column(ycol)?;
+    Ok(acc)
+}
+
+/// Files in `dir` with the extension `ext`.
+/// Does not recurse into children folders.
+pub(crate) fn files_with_extension(dir: &PathBuf, ext: &str) -> io::Result<Vec<PathBuf>> {
+    let dir_walker = fs::read_dir(dir)?;
+    let files = dir_walker
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let path_ext = path.extension()?.to_str()?;
+            (path.is_file() && path_ext == ext).then_some(path)
+        })
+        .collect();
+    Ok(files)
+}
+
+pub mod error {
+    use std::{fmt, io, path::PathBuf};
+
+    use crate::dataset;
+
+    #[derive(derive_more::From, Debug)]
+    pub enum DataFile {
+        Property(dataset::properties::error::Property),
+        Properties(dataset::v2_0::error::Properties),
+        ChannelInfo(dataset::v2_0::properties::channel::error::Info),
+        ChannelData(dataset::v2_0::error::ChannelData),
+        Polars(polars::error::PolarsError),
+
+        /// The number of data points of the channel does not match the segment header.
+        #[from(ignore)]
+        InvalidDataLength {
+            segment: dataset::v2_0::SegmentType,
+            channel: String,
+        },
+    }
+
+    impl fmt::Display for DataFile {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Property(err) => write!(f, "{err}"),
+                Self::Properties(_) => write!(f, "could not read segment properties"),
+                Self::ChannelInfo(_) => write!(f, "invalid channel info"),
+                Self::ChannelData(_) => write!(f, "could not read channel data"),
+                Self::Polars(_) => write!(f, "could not build data frame"),
+                Self::InvalidDataLength { segment, channel } => write!(
+                    f,
+                    "channel `{channel}` of segment {segment} does not match the number of points"
+                ),
+            }
+        }
+    }
+
+    impl std::error::Error for DataFile {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::Property(err) => err.source(),
+                Self::Properties(err) => Some(err),
+                Self::ChannelInfo(err) => Some(err),
+                Self::ChannelData(err) => Some(err),
+                Self::Polars(err) => Some(err),
+                Self::InvalidDataLength { .. } => None,
+            }
+        }
+    }
+
+    #[derive(derive_more::From, Debug)]
+    pub enum DataCollection {
+        Io(io::Error),
+        Dataset {
+            path: PathBuf,
+            error: dataset::DatasetError,
+        },
+        DataFile {
+            path: PathBuf,
+            error: DataFile,
+        },
+
+        /// The data of the files could not be combined.
+        Polars(polars::error::PolarsError),
+    }
+
+    impl fmt::Display for DataCollection {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Io(_) => write!(f, "could not read directory"),
+                Self::Dataset { path, .. } => {
+                    write!(f, "could not read dataset `{}`", path.display())
+                }
+                Self::DataFile { path, .. } => {
+                    write!(f, "could not load data of `{}`", path.display())
+                }
+                Self::Polars(_) => write!(f, "could not combine data frames"),
+            }
+        }
+    }
+
+    impl std::error::Error for DataCollection {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::Io(err) => Some(err),
+                Self::Dataset { error, .. } => Some(error),
+                Self::DataFile { error, .. } => Some(error),
+                Self::Polars(err) => Some(err),
+            }
+        }
+    }
+}
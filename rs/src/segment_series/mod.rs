@@ -0,0 +1,105 @@
+//! Shared handling of segment series data.
+//! (`.jpk-voltage-ramp`, `.jpk-force`)
+//!
+//! A segment series stores its segments at `segments/{segment}`,
+//! each with a `segment-header.properties` and its channel data.
+//!
+//! Combining segments into data frames is shared with exports (`.txt`),
+//! loading segments from datasets only with the dataset readers.
+#[cfg(any(feature = "voltage_spectroscopy", feature = "force_curve"))]
+mod load;
+
+#[cfg(any(feature = "voltage_spectroscopy", feature = "force_curve"))]
+pub(crate) use load::{error, files_with_extension, load_data_all, stack_with_position};
+
+use crate::dataset::v2_0 as dataset;
+use polars::prelude::{self as pl, ChunkFull, IntoColumn};
+use std::iter;
+
+/// Name of the segment index column.
+pub(crate) const SEGMENT_COLUMN: &str = "segment";
+
+/// Channel data of a segment.
+pub(crate) struct SegmentColumns {
+    pub segment: dataset::SegmentType,
+
+    /// Number of data points.
+    pub length: usize,
+
+    /// `f64` column for each channel, each of `length`.
+    pub columns: Vec<pl::Column>,
+}
+
+/// Combine the channels of segments into a single data frame.
+///
+/// # Returns
+/// Data frame with a `segment` column and a column for each channel, ordered by name.
+/// Channels that are not present in a segment are null for that segment.
+pub(crate) fn combine_segments(
+    mut segments: Vec<SegmentColumns>,
+) -> Result<pl::DataFrame, pl::PolarsError> {
+    if segments.is_empty() {
+        return Ok(pl::DataFrame::empty());
+    }
+
+    let mut headers = segments
+        .iter()
+        .flat_map(|segment| segment.columns.iter().map(|col| col.name().to_string()))
+        .collect::<Vec<_>>();
+    headers.sort();
+    headers.dedup();
+
+    let seg_col = segments
+        .iter()
+        .map(|segment| {
+            pl::UInt8Chunked::full(SEGMENT_COLUMN.into(), segment.segment, segment.length)
+                .into_column()
+        })
+        .collect::<Vec<_>>();
+
+    let mut cols = Vec::with_capacity(headers.len());
+    for header in headers.iter() {
+        let mut data_cols = Vec::with_capacity(segments.len());
+        for segment in segments.iter_mut() {
+            let col_idx = segment.columns.iter().position(|col| col.name() == header);
+            let col = match col_idx {
+                None => pl::Column::new_scalar(
+                    header.into(),
+                    pl::Scalar::null(pl::DataType::Float64),
+                    segment.length,
+                ),
+                Some(idx) => segment.columns.swap_remove(idx),
+            };
+            data_cols.push(col);
+        }
+        cols.push(data_cols)
+    }
+
+    let seg_col = concat_columns(seg_col)?.expect("at least one segment should exist");
+    let data_cols = cols
+        .into_iter()
+        .map(|dcols| {
+            concat_columns(dcols).map(|col| col.expect("at least on data column should exist"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let df = iter::once(seg_col).chain(data_cols).collect();
+    pl::DataFrame::new(df)
+}
+
+/// Append `columns` into a single column.
+///
+/// # Returns
+/// `None` if `columns` is empty.
+pub(crate) fn concat_columns(
+    columns: impl IntoIterator<Item = pl::Column>,
+) -> Result<Option<pl::Column>, pl::PolarsError> {
+    let mut columns = columns.into_iter();
+    let Some(mut acc) = columns.next() else {
+        return Ok(None);
+    };
+    for elm in columns {
+        acc.append_owned(elm)?;
+    }
+    Ok(Some(acc))
+}
@@ -2,23 +2,33 @@
 //! (`.jpk-voltage-ramp`)
 
 pub mod v2_0 {
-    use crate::dataset::{
-        DatasetError,
-        properties::{self, extract_value},
-        v2_0 as dataset,
-        v2_0::DatasetReader,
+    use crate::{
+        dataset::{
+            DatasetError,
+            index::{IndexQuery, Pixel},
+            preview::Preview,
+            properties,
+            v2_0::{self as dataset, DatasetReader, IndexType},
+        },
+        segment_series,
     };
-    use polars::prelude::{self as pl, ChunkFull, IntoColumn};
+    use polars::prelude as pl;
     use rayon::prelude::*;
-    use std::{fs, io, iter, path::PathBuf};
+    use std::{collections::BTreeMap, fs, io, path::PathBuf};
 
     const VOLTAGE_SPECTROSCOPY_FILE_EXT: &str = "jpk-voltage-ramp";
     const DATASET_TYPE_PROPERTY_VALUE: &str = "voltage-spectroscopy-segment-series";
-    const SEGMENT_COUNTS_PROPERTY_KEY: &str =
-        "voltage-spectroscopy-segment-series.force-segments.count";
-    const SEGMENT_NUM_POINTS_PROPERTY_KEY: &str = "force-segment-header.num-points";
-    const POSITION_X_PROPERTY_KEY: &str = "voltage-spectroscopy-segment-series.header.position.x";
-    const POSITION_Y_PROPERTY_KEY: &str = "voltage-spectroscopy-segment-series.header.position.y";
+    const SEGMENT_SERIES_PROPERTY_KEY: &str = "voltage-spectroscopy-segment-series";
+    const SEGMENTS_PROPERTY_KEY: &str = "force-segments";
+    const POSITION_PROPERTY_KEY: &str = "header.position";
+    const POSITION_INDEX_PROPERTY_KEY: &str = "header.position-index";
+    /// Grid lines closer than this fraction of the largest gap between lines are merged.
+    const GRID_LINE_TOLERANCE: f64 = 0.1;
+    /// Grid lines closer than this, in meters, are always merged.
+    const GRID_LINE_MIN_TOLERANCE: f64 = 1e-9;
+    const INDEX_COLUMN: &str = "index";
+    const I_COLUMN: &str = "i";
+    const J_COLUMN: &str = "j";
 
     #[derive(derive_more::Deref)]
     pub struct Reader<R> {
@@ -42,13 +52,44 @@ pub mod v2_0 {
             self.inner.segment_properties(segment_path)
         }
 
+        /// Preview image of the ramp.
+        pub fn thumbnail(&mut self) -> Result<Option<Preview>, DatasetError> {
+            self.inner.thumbnail()
+        }
+
+        /// All preview images of the ramp.
+        pub fn previews(&mut self) -> Result<Vec<Preview>, DatasetError> {
+            self.inner.previews()
+        }
+
+        /// Replace the scaling of a conversion of a channel for all subsequent reads.
+        /// The original and new values are recorded in the shared properties.
+        /// See [`DatasetReader::override_calibration`].
+        pub fn override_calibration(
+            &mut self,
+            channel: impl AsRef<str>,
+            slot: impl AsRef<str>,
+            multiplier: f64,
+            offset: f64,
+        ) -> Result<(), dataset::error::OverrideCalibration> {
+            self.inner
+                .override_calibration(channel, slot, multiplier, offset)
+        }
+
+        /// Read the data of a channel.
+        ///
+        /// # Arguments
+        /// + `calibration_slot`: Calibration slot to convert the data to.
+        ///   If `None` the default slot is used.
         pub fn channel_data(
             &mut self,
             segment: dataset::SegmentType,
             channel: impl AsRef<str>,
-        ) -> Result<Vec<dataset::DataValue>, dataset::error::ChannelData> {
+            calibration_slot: Option<&str>,
+        ) -> Result<dataset::ChannelData, dataset::error::ChannelData> {
             let segment_path = dataset::utils::segment_path(segment);
-            self.inner.channel_data(segment_path, channel)
+            self.inner
+                .channel_data(segment_path, channel, calibration_slot)
         }
     }
 
@@ -65,31 +106,51 @@ pub mod v2_0 {
 
         /// Number of segments in the dataset as indicated in the dataset properties.
         pub fn segments_count(&self) -> Result<dataset::SegmentType, properties::error::Property> {
-            extract_value!(self.inner.dataset_properties(), SEGMENT_COUNTS_PROPERTY_KEY, parse dataset::SegmentType)
+            self.inner
+                .dataset_properties()
+                .subtree(SEGMENT_SERIES_PROPERTY_KEY)
+                .subtree(SEGMENTS_PROPERTY_KEY)
+                .parse::<dataset::SegmentType>("count")
         }
 
         /// `(x, y)` coordinate.
         pub fn position(&self) -> Result<(f64, f64), properties::error::Property> {
-            let properties = self.inner.dataset_properties();
-            let x = extract_value!(properties, POSITION_X_PROPERTY_KEY, parse f64)?;
-            let y = extract_value!(properties, POSITION_Y_PROPERTY_KEY, parse f64)?;
+            let position = self
+                .inner
+                .dataset_properties()
+                .subtree(SEGMENT_SERIES_PROPERTY_KEY)
+                .subtree(POSITION_PROPERTY_KEY);
+            let x = position.get_f64("x")?;
+            let y = position.get_f64("y")?;
             Ok((x, y))
         }
+
+        /// Index of the position in the map the ramp was recorded in.
+        pub fn position_index(&self) -> Result<IndexType, properties::error::Property> {
+            self.inner
+                .dataset_properties()
+                .subtree(SEGMENT_SERIES_PROPERTY_KEY)
+                .parse::<IndexType>(POSITION_INDEX_PROPERTY_KEY)
+        }
     }
 
     /// Read a single voltage spectroscopy (`.jpk-voltage-ramp`) file.
-    #[derive(derive_more::Deref)]
+    #[derive(derive_more::Deref, derive_more::DerefMut)]
     pub struct FileReader {
         path: PathBuf,
         #[deref]
+        #[deref_mut]
         inner: Reader<fs::File>,
     }
 
     impl FileReader {
         pub fn new(path: impl Into<PathBuf>) -> Result<Self, DatasetError> {
             let path = path.into();
-            let file = fs::File::open(&path).map_err(|err| ::zip::result::ZipError::Io(err))?;
-            let archive = ::zip::ZipArchive::new(file)?;
+            let archive =
+                crate::dataset::open_archive(&path).map_err(|error| DatasetError::OpenFile {
+                    path: path.clone(),
+                    error,
+                })?;
             let inner = Reader::new(archive)?;
             Ok(Self { path, inner })
         }
@@ -101,84 +162,7 @@ pub mod v2_0 {
         /// Loads data from all segments and all channels.
         pub fn load_data_all(&mut self) -> Result<pl::DataFrame, error::DataFile> {
             let segments_count = self.segments_count()?;
-            if segments_count == 0 {
-                return Ok(pl::DataFrame::empty());
-            }
-
-            let mut seg_cols = Vec::with_capacity(segments_count as usize);
-            let mut headers = Vec::new();
-            for segment in 0..segments_count {
-                let properties = self.inner.segment_properties(segment)?;
-                let channels = properties.channel_list()?;
-                let mut scols = Vec::with_capacity(channels.len() + 1);
-                for channel in channels {
-                    let data = self.inner.channel_data(segment, channel)?;
-                    let col = pl::Float64Chunked::from_vec(channel.into(), data).into_column();
-                    scols.push(col);
-                    headers.push(channel.to_string());
-                }
-
-                let length =
-                    extract_value!(properties, SEGMENT_NUM_POINTS_PROPERTY_KEY, parse usize)?;
-                let seg = pl::UInt8Chunked::full("segment".into(), segment, length).into_column();
-                scols.push(seg);
-
-                seg_cols.push(scols);
-            }
-            headers.sort();
-            headers.dedup();
-            let mut seg_col = Vec::with_capacity(segments_count as usize);
-            for scols in seg_cols.iter_mut() {
-                seg_col.push(scols.pop().expect("segment should have segment id column"));
-            }
-
-            let mut cols = Vec::with_capacity(headers.len());
-            for header in headers.iter() {
-                let mut data_cols = Vec::with_capacity(segments_count as usize);
-                for (sidx, scols) in seg_cols.iter_mut().enumerate() {
-                    let col_idx = scols.iter().position(|col| col.name() == header);
-                    let col = match col_idx {
-                        None => pl::Column::new_scalar(
-                            header.into(),
-                            pl::Scalar::null(pl::DataType::Float64),
-                            seg_col[sidx].len(),
-                        ),
-                        Some(idx) => scols.swap_remove(idx),
-                    };
-                    assert_eq!(
-                        col.len(),
-                        seg_col[sidx].len(),
-                        "data and segment index have different lengths"
-                    );
-
-                    data_cols.push(col);
-                }
-                cols.push(data_cols)
-            }
-
-            let seg_col = seg_col
-                .into_iter()
-                .reduce(|mut acc, elm| {
-                    acc.append_owned(elm).unwrap();
-                    acc
-                })
-                .expect("at least one segment should exist");
-
-            let data_cols = cols
-                .into_iter()
-                .map(|dcols| {
-                    dcols
-                        .into_iter()
-                        .reduce(|mut acc, elm| {
-                            acc.append_owned(elm).unwrap();
-                            acc
-                        })
-                        .expect("at least on data column should exist")
-                })
-                .collect::<Vec<_>>();
-
-            let df = iter::once(seg_col).chain(data_cols).collect();
-            Ok(pl::DataFrame::new(df)?)
+            segment_series::load_data_all(&mut self.inner.inner, segments_count)
         }
     }
 
@@ -193,24 +177,18 @@ pub mod v2_0 {
             Self { path: path.into() }
         }
 
+        /// Paths of the voltage spectroscopy files in the directory.
+        pub fn files(&self) -> io::Result<Vec<PathBuf>> {
+            segment_series::files_with_extension(&self.path, VOLTAGE_SPECTROSCOPY_FILE_EXT)
+        }
+
         pub fn load_data_all(&self) -> Result<pl::DataFrame, error::DataCollection> {
-            let dir_walker = fs::read_dir(&self.path)?;
-            let files = dir_walker
-                .into_iter()
-                .filter_map(|entry| entry.ok())
-                .filter_map(|entry| {
-                    let path = entry.path();
-                    let ext = path.extension()?.to_str()?;
-                    (path.is_file() && ext == VOLTAGE_SPECTROSCOPY_FILE_EXT).then_some(path)
-                })
-                .collect::<Vec<_>>();
+            let files = self.files()?;
             let readers = files
                 .into_par_iter()
                 .map(|path| {
-                    FileReader::new(path.clone()).map_err(|err| error::DataCollection::Dataset {
-                        path: path,
-                        error: err,
-                    })
+                    FileReader::new(path.clone())
+                        .map_err(|error| error::DataCollection::Dataset { path, error })
                 })
                 .collect::<Result<Vec<_>, _>>()?;
 
@@ -234,85 +212,613 @@ pub mod v2_0 {
                     Result::<_, error::DataCollection>::Ok((xy, data))
                 })
                 .collect::<Result<Vec<_>, _>>()?;
-            if data.len() == 0 {
-                return Ok(pl::DataFrame::empty());
-            }
 
-            let (idx, df) = data.into_iter().unzip::<_, _, Vec<_>, Vec<_>>();
-            let (xcols, ycols) = idx
-                .into_iter()
-                .enumerate()
-                .map(|(idx, (x, y))| {
-                    let length = df[idx].height();
-                    let xcol = pl::Column::new_scalar(
-                        "x".into(),
-                        pl::Scalar::new(pl::DataType::Float64, x.into()),
-                        length,
-                    );
-                    let ycol = pl::Column::new_scalar(
-                        "y".into(),
-                        pl::Scalar::new(pl::DataType::Float64, y.into()),
-                        length,
-                    );
-                    (xcol, ycol)
-                })
-                .unzip::<_, _, Vec<_>, Vec<_>>();
+            Ok(segment_series::stack_with_position(data)?)
+        }
+    }
 
-            let xcol = xcols
-                .into_iter()
-                .reduce(|mut acc, elm| {
-                    acc.append_owned(elm).unwrap();
-                    acc
+    /// Map of voltage spectroscopy files (`.jpk-voltage-ramp`) in a directory.
+    ///
+    /// Files are ordered by their position index.
+    /// The grid is reconstructed from the positions of the files.
+    /// Rows are determined by the position index, columns by the `x` coordinate.
+    /// If multiple files have the same position index, the last by file name is used,
+    /// as file names contain the time of the recording.
+    pub struct VoltageSpectroscopyMap {
+        path: PathBuf,
+        grid: Grid,
+        /// Ordered by index.
+        positions: BTreeMap<IndexType, MapPosition>,
+        /// Index at each pixel, in row major order.
+        pixels: Vec<Option<IndexType>>,
+    }
+
+    struct MapPosition {
+        path: PathBuf,
+        pixel: Pixel,
+        xy: (f64, f64),
+    }
+
+    impl VoltageSpectroscopyMap {
+        /// Read the positions of the voltage spectroscopy files in the directory at `path`.
+        pub fn new(path: impl Into<PathBuf>) -> Result<Self, error::Map> {
+            let path = path.into();
+            let mut files =
+                segment_series::files_with_extension(&path, VOLTAGE_SPECTROSCOPY_FILE_EXT)?;
+            files.sort();
+            let files = files
+                .into_par_iter()
+                .map(|path| {
+                    let reader =
+                        FileReader::new(path.clone()).map_err(|error| error::Map::Dataset {
+                            path: path.clone(),
+                            error,
+                        })?;
+                    let property_error = |error| error::Map::Property {
+                        path: path.clone(),
+                        error,
+                    };
+                    let index = reader.position_index().map_err(property_error)?;
+                    let xy = reader.position().map_err(property_error)?;
+                    Ok((index, path, xy))
                 })
-                .expect("at least one x col should exist");
+                .collect::<Result<Vec<_>, error::Map>>()?;
+            if files.is_empty() {
+                return Err(error::Map::Empty(path));
+            }
 
-            let ycol = ycols
-                .into_iter()
-                .reduce(|mut acc, elm| {
-                    acc.append_owned(elm).unwrap();
-                    acc
+            let positions = files
+                .iter()
+                .map(|(index, _, xy)| (*index, *xy))
+                .collect::<Vec<_>>();
+            let grid = Grid::from_positions(&positions);
+            let mut positions = BTreeMap::new();
+            for (index, path, xy) in files {
+                let Some(pixel) = grid.index_to_pixel(index, xy) else {
+                    return Err(error::Map::OutsideGrid { path, index });
+                };
+                positions.insert(index, MapPosition { path, pixel, xy });
+            }
+
+            let pixels = pixel_indices(&grid, &positions)?;
+            Ok(Self {
+                path,
+                grid,
+                positions,
+                pixels,
+            })
+        }
+    }
+
+    /// Index at each pixel of the grid, in row major order.
+    ///
+    /// # Errors
+    /// If multiple indices are at the same pixel.
+    fn pixel_indices(
+        grid: &Grid,
+        positions: &BTreeMap<IndexType, MapPosition>,
+    ) -> Result<Vec<Option<IndexType>>, error::Map> {
+        let mut pixels = vec![None; grid.i_length as usize * grid.j_length as usize];
+        for (index, position) in positions.iter() {
+            let pixel = &mut pixels[position.pixel.to_index(grid.i_length) as usize];
+            if let Some(other) = pixel.replace(*index) {
+                return Err(error::Map::DuplicatePixel {
+                    pixel: position.pixel.clone(),
+                    indices: (other, *index),
+                });
+            }
+        }
+        Ok(pixels)
+    }
+
+    impl VoltageSpectroscopyMap {
+        pub fn path(&self) -> &PathBuf {
+            &self.path
+        }
+
+        pub fn grid(&self) -> &Grid {
+            &self.grid
+        }
+
+        /// Number of positions with a file.
+        pub fn len(&self) -> usize {
+            self.positions.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.positions.is_empty()
+        }
+
+        /// Position indices with a file, in ascending order.
+        pub fn indices(&self) -> impl Iterator<Item = IndexType> + '_ {
+            self.positions.keys().copied()
+        }
+
+        /// Path of the file recorded at `index`.
+        pub fn file(&self, index: IndexType) -> Option<&PathBuf> {
+            self.positions.get(&index).map(|position| &position.path)
+        }
+
+        /// `(x, y)` coordinate of the file recorded at `index`.
+        pub fn position(&self, index: IndexType) -> Option<(f64, f64)> {
+            self.positions.get(&index).map(|position| position.xy)
+        }
+
+        /// # Returns
+        /// `None` if no file was recorded at `index`.
+        pub fn index_to_pixel(&self, index: IndexType) -> Option<Pixel> {
+            self.positions
+                .get(&index)
+                .map(|position| position.pixel.clone())
+        }
+
+        /// # Returns
+        /// `None` if the pixel is outside the grid or no file was recorded at the pixel.
+        pub fn pixel_to_index(&self, pixel: &Pixel) -> Option<IndexType> {
+            if pixel.i() >= self.grid.i_length || pixel.j() >= self.grid.j_length {
+                return None;
+            }
+            self.pixels[pixel.to_index(self.grid.i_length) as usize]
+        }
+
+        /// Indices selected by `query`.
+        /// Pixels of a rect without a file are skipped.
+        pub fn query_indices(&self, query: &IndexQuery) -> Result<Vec<IndexType>, error::Map> {
+            match query {
+                IndexQuery::All => Ok(self.indices().collect()),
+
+                IndexQuery::Index(index) => {
+                    if !self.positions.contains_key(index) {
+                        return Err(error::Map::IndexNotFound(*index));
+                    }
+                    Ok(vec![*index])
+                }
+
+                IndexQuery::PixelRect(rect) => {
+                    let mut indices = Vec::new();
+                    for pixel in rect.iter() {
+                        if pixel.i() >= self.grid.i_length || pixel.j() >= self.grid.j_length {
+                            return Err(error::Map::OutOfBounds(pixel));
+                        }
+                        indices.extend(self.pixel_to_index(&pixel));
+                    }
+                    Ok(indices)
+                }
+
+                IndexQuery::Pixel(pixel) => {
+                    let index = self
+                        .pixel_to_index(pixel)
+                        .ok_or(error::Map::OutOfBounds(pixel.clone()))?;
+                    Ok(vec![index])
+                }
+            }
+        }
+
+        /// Loads data from all segments and all channels of the files selected by `query`.
+        ///
+        /// # Returns
+        /// Data frame with the columns of [`FileReader::load_data_all`],
+        /// and `index`, `i`, `j`, `x`, and `y` columns of each file's position.
+        pub fn load_data(&self, query: &IndexQuery) -> Result<pl::DataFrame, error::Map> {
+            let indices = self.query_indices(query)?;
+            let data = indices
+                .into_par_iter()
+                .map(|index| {
+                    let position = &self.positions[&index];
+                    let mut reader = FileReader::new(position.path.clone()).map_err(|error| {
+                        error::Map::Dataset {
+                            path: position.path.clone(),
+                            error,
+                        }
+                    })?;
+                    let mut data =
+                        reader
+                            .load_data_all()
+                            .map_err(|error| error::Map::DataFile {
+                                path: position.path.clone(),
+                                error,
+                            })?;
+
+                    let length = data.height();
+                    let scalar = |name: &str, value: IndexType| {
+                        pl::Column::new_scalar(
+                            name.into(),
+                            pl::Scalar::new(pl::DataType::UInt32, value.into()),
+                            length,
+                        )
+                    };
+                    data.with_column(scalar(INDEX_COLUMN, index))?;
+                    data.with_column(scalar(I_COLUMN, position.pixel.i()))?;
+                    data.with_column(scalar(J_COLUMN, position.pixel.j()))?;
+                    Ok((position.xy, data))
                 })
-                .expect("at least one y col should exist");
+                .collect::<Result<Vec<_>, error::Map>>()?;
+
+            Ok(segment_series::stack_with_position(data)?)
+        }
+    }
+
+    /// Axis aligned grid reconstructed from the positions of a map.
+    /// Ramp files do not record the grid of the map,
+    /// so rotated or reflected grids are not supported.
+    #[derive(Clone, PartialEq, Debug)]
+    pub struct Grid {
+        x_origin: f64,
+        y_origin: f64,
+        x_step: f64,
+        y_step: f64,
+        i_length: IndexType,
+        j_length: IndexType,
+    }
 
-            let mut df = df
+    impl Grid {
+        /// Reconstruct the grid from the positions of a map.
+        /// Columns are reconstructed from the `x` coordinates,
+        /// rows from the position indices.
+        ///
+        /// # Arguments
+        /// + `positions`: `(index, (x, y))` of each position. Must not be empty.
+        fn from_positions(positions: &[(IndexType, (f64, f64))]) -> Self {
+            let (x_origin, x_step, i_length) = grid_lines(positions.iter().map(|(_, (x, _))| *x));
+
+            // (sum, count) of the y coordinates of each row
+            let mut rows = BTreeMap::<IndexType, (f64, usize)>::new();
+            for (index, (_, y)) in positions {
+                let (sum, count) = rows.entry(index / i_length).or_default();
+                *sum += y;
+                *count += 1;
+            }
+            let rows = rows
                 .into_iter()
-                .reduce(|mut acc, elm| {
-                    acc.vstack_mut_owned(elm).unwrap();
-                    acc
-                })
-                .expect("at least one data frame should exist");
+                .map(|(j, (sum, count))| (j, sum / count as f64))
+                .collect::<Vec<_>>();
+            let (first_j, first_y) = rows[0];
+            let (last_j, last_y) = rows[rows.len() - 1];
+            let y_step = if last_j == first_j {
+                0.0
+            } else {
+                (last_y - first_y) / (last_j - first_j) as f64
+            };
+
+            Self {
+                x_origin,
+                y_origin: first_y - first_j as f64 * y_step,
+                x_step,
+                y_step,
+                i_length,
+                j_length: last_j + 1,
+            }
+        }
 
-            df.with_column(xcol).unwrap();
-            df.with_column(ycol).unwrap();
+        /// Pixel of the position recorded at `index`.
+        /// The row is determined by the index, the column by the `x` coordinate.
+        ///
+        /// # Returns
+        /// `None` if the position is outside the grid.
+        fn index_to_pixel(&self, index: IndexType, (x, _): (f64, f64)) -> Option<Pixel> {
+            let i = grid_line(x, self.x_origin, self.x_step, self.i_length)?;
+            let j = index / self.i_length;
+            (j < self.j_length).then(|| Pixel::new(i, j))
+        }
+
+        /// Pixel nearest to the position.
+        ///
+        /// # Returns
+        /// `None` if the position is outside the grid.
+        pub fn position_to_pixel(&self, (x, y): (f64, f64)) -> Option<Pixel> {
+            let i = grid_line(x, self.x_origin, self.x_step, self.i_length)?;
+            let j = grid_line(y, self.y_origin, self.y_step, self.j_length)?;
+            Some(Pixel::new(i, j))
+        }
 
-            Ok(df)
+        /// `(x, y)` coordinate of the pixel center.
+        pub fn pixel_to_position(&self, pixel: &Pixel) -> (f64, f64) {
+            (
+                self.x_origin + pixel.i() as f64 * self.x_step,
+                self.y_origin + pixel.j() as f64 * self.y_step,
+            )
         }
     }
 
-    pub mod error {
-        use std::{io, path::PathBuf};
+    impl Grid {
+        /// `x` coordinate of pixel `(0, 0)`.
+        pub fn x_origin(&self) -> f64 {
+            self.x_origin
+        }
 
-        use crate::dataset;
+        /// `y` coordinate of pixel `(0, 0)`.
+        pub fn y_origin(&self) -> f64 {
+            self.y_origin
+        }
 
-        #[derive(derive_more::From, Debug)]
-        pub enum DataFile {
-            Property(dataset::properties::error::Property),
-            Properties(dataset::v2_0::error::Properties),
-            ChannelData(dataset::v2_0::error::ChannelData),
-            Polars(polars::error::PolarsError),
+        /// Distance between columns.
+        pub fn x_step(&self) -> f64 {
+            self.x_step
+        }
+
+        /// Distance between rows.
+        pub fn y_step(&self) -> f64 {
+            self.y_step
+        }
+
+        /// Number of columns.
+        pub fn i_length(&self) -> IndexType {
+            self.i_length
+        }
+
+        /// Number of rows.
+        pub fn j_length(&self) -> IndexType {
+            self.j_length
+        }
+
+        /// Width of the scanned area.
+        pub fn u_length(&self) -> f64 {
+            self.x_step * self.i_length as f64
+        }
+
+        /// Height of the scanned area.
+        pub fn v_length(&self) -> f64 {
+            self.y_step * self.j_length as f64
+        }
+
+        pub fn x_center(&self) -> f64 {
+            self.x_origin + self.x_step * (self.i_length - 1) as f64 / 2.0
+        }
+
+        pub fn y_center(&self) -> f64 {
+            self.y_origin + self.y_step * (self.j_length - 1) as f64 / 2.0
         }
+    }
+
+    /// Grid line nearest to `value`.
+    ///
+    /// # Returns
+    /// `None` if the value is outside the grid.
+    fn grid_line(value: f64, origin: f64, step: f64, length: IndexType) -> Option<IndexType> {
+        let line = if step == 0.0 {
+            0.0
+        } else {
+            ((value - origin) / step).round()
+        };
+        (line >= 0.0 && line < length as f64).then_some(line as IndexType)
+    }
+
+    /// Reconstruct the grid lines along an axis.
+    ///
+    /// # Arguments
+    /// + `values`: Coordinates along the axis. Must not be empty.
+    ///
+    /// # Returns
+    /// `(origin, step, length)` of the grid lines.
+    /// Lines without a position, e.g. of an aborted map, are included.
+    fn grid_lines(values: impl Iterator<Item = f64>) -> (f64, f64, IndexType) {
+        let mut values = values.collect::<Vec<_>>();
+        values.sort_by(f64::total_cmp);
+        let max_gap = values
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .fold(0.0, f64::max);
+        let tolerance = (max_gap * GRID_LINE_TOLERANCE).max(GRID_LINE_MIN_TOLERANCE);
+
+        // (sum, count) of the values of each line
+        let mut lines: Vec<(f64, usize)> = Vec::new();
+        let mut previous = None;
+        for value in values {
+            match (lines.last_mut(), previous) {
+                (Some((sum, count)), Some(previous)) if value - previous <= tolerance => {
+                    *sum += value;
+                    *count += 1;
+                }
+                _ => lines.push((value, 1)),
+            }
+            previous = Some(value);
+        }
+
+        let lines = lines
+            .into_iter()
+            .map(|(sum, count)| sum / count as f64)
+            .collect::<Vec<_>>();
+        let origin = lines[0];
+        let step = lines
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .fold(f64::INFINITY, f64::min);
+        if !step.is_finite() {
+            return (origin, 0.0, 1);
+        }
+
+        let length = ((lines[lines.len() - 1] - origin) / step).round() as IndexType + 1;
+        (origin, step, length)
+    }
+
+    pub mod error {
+        use crate::dataset::{self, index::Pixel, properties, v2_0::IndexType};
+        use std::{fmt, io, path::PathBuf};
+
+        pub use crate::segment_series::error::{DataCollection, DataFile};
 
         #[derive(derive_more::From, Debug)]
-        pub enum DataCollection {
+        pub enum Map {
+            /// The directory could not be read.
             Io(io::Error),
+
             Dataset {
                 path: PathBuf,
                 error: dataset::DatasetError,
             },
+
+            /// A property of the file could not be read.
+            Property {
+                path: PathBuf,
+                error: properties::error::Property,
+            },
+
             DataFile {
                 path: PathBuf,
                 error: DataFile,
             },
+
+            /// The directory at the given path does not contain any files.
+            #[from(ignore)]
+            Empty(PathBuf),
+
+            /// No file was recorded at the index.
+            #[from(ignore)]
+            IndexNotFound(IndexType),
+
+            /// The pixel is outside the grid or has no file.
+            #[from(ignore)]
+            OutOfBounds(Pixel),
+
+            /// The position of the file is outside the grid.
+            #[from(ignore)]
+            OutsideGrid {
+                path: PathBuf,
+                index: IndexType,
+            },
+
+            /// Files at different indices were recorded at the same pixel.
+            #[from(ignore)]
+            DuplicatePixel {
+                pixel: Pixel,
+                indices: (IndexType, IndexType),
+            },
+
+            /// The data of the files could not be combined.
+            Polars(polars::error::PolarsError),
+        }
+
+        impl fmt::Display for Map {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    Self::Io(_) => write!(f, "could not read directory"),
+                    Self::Dataset { path, .. } => {
+                        write!(f, "could not read dataset `{}`", path.display())
+                    }
+                    Self::Property { path, .. } => {
+                        write!(f, "invalid property in `{}`", path.display())
+                    }
+                    Self::DataFile { path, .. } => {
+                        write!(f, "could not load data of `{}`", path.display())
+                    }
+                    Self::Empty(path) => write!(
+                        f,
+                        "`{}` does not contain voltage spectroscopy files",
+                        path.display()
+                    ),
+                    Self::IndexNotFound(index) => write!(f, "index {index} is not in the map"),
+                    Self::OutOfBounds(pixel) => {
+                        write!(f, "pixel ({}, {}) is out of bounds", pixel.i(), pixel.j())
+                    }
+                    Self::OutsideGrid { path, index } => write!(
+                        f,
+                        "position {index} of `{}` is outside the grid",
+                        path.display()
+                    ),
+                    Self::DuplicatePixel {
+                        pixel,
+                        indices: (a, b),
+                    } => write!(
+                        f,
+                        "indices {a} and {b} are both at pixel ({}, {})",
+                        pixel.i(),
+                        pixel.j()
+                    ),
+                    Self::Polars(_) => write!(f, "could not combine data frames"),
+                }
+            }
+        }
+
+        impl std::error::Error for Map {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                match self {
+                    Self::Io(err) => Some(err),
+                    Self::Dataset { error, .. } => Some(error),
+                    Self::Property { error, .. } => Some(error),
+                    Self::DataFile { error, .. } => Some(error),
+                    Self::Polars(err) => Some(err),
+                    Self::Empty(_)
+                    | Self::IndexNotFound(_)
+                    | Self::OutOfBounds(_)
+                    | Self::OutsideGrid { .. }
+                    | Self::DuplicatePixel { .. } => None,
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn grid_from_positions_single_row() {
+            let positions = [
+                (0, (0.0, 1e-6)),
+                (1, (1e-6, 1e-6 + 1.8e-12)),
+                (2, (2e-6, 1e-6)),
+                (3, (3e-6 + 1.8e-12, 1e-6 + 1.8e-12)),
+            ];
+            let grid = Grid::from_positions(&positions);
+            assert_eq!((grid.i_length(), grid.j_length()), (4, 1));
+            assert_eq!(grid.y_step(), 0.0);
+            assert_eq!(
+                grid.index_to_pixel(3, positions[3].1),
+                Some(Pixel::new(3, 0))
+            );
+        }
+
+        #[test]
+        fn grid_from_positions_rows_by_index() {
+            // meandering rows with y jitter larger than the row spacing tolerance
+            let positions = [
+                (0, (0.0, 0.0)),
+                (1, (1e-6, 3e-7)),
+                (2, (1e-6, 1e-6)),
+                (3, (0.0, 1e-6 - 3e-7)),
+            ];
+            let grid = Grid::from_positions(&positions);
+            assert_eq!((grid.i_length(), grid.j_length()), (2, 2));
+            assert_eq!(
+                grid.index_to_pixel(2, positions[2].1),
+                Some(Pixel::new(1, 1))
+            );
+            assert_eq!(
+                grid.index_to_pixel(3, positions[3].1),
+                Some(Pixel::new(0, 1))
+            );
+            assert_eq!(grid.index_to_pixel(4, (0.0, 2e-6)), None);
+        }
+
+        #[test]
+        fn pixel_indices_duplicate() {
+            let positions = [(0, (0.0, 0.0)), (1, (1e-6, 0.0)), (2, (1e-6, 0.0))];
+            let grid = Grid {
+                x_origin: 0.0,
+                y_origin: 0.0,
+                x_step: 1e-6,
+                y_step: 1e-6,
+                i_length: 3,
+                j_length: 1,
+            };
+            let positions = positions
+                .into_iter()
+                .map(|(index, xy)| {
+                    let position = MapPosition {
+                        path: PathBuf::from(format!("{index}.jpk-voltage-ramp")),
+                        pixel: grid.index_to_pixel(index, xy).unwrap(),
+                        xy,
+                    };
+                    (index, position)
+                })
+                .collect::<BTreeMap<_, _>>();
+            assert!(matches!(
+                pixel_indices(&grid, &positions),
+                Err(error::Map::DuplicatePixel {
+                    indices: (1, 2),
+                    ..
+                })
+            ));
         }
     }
 }
@@ -0,0 +1,146 @@
+use jpk_reader::{
+    JpkFile,
+    dataset::format::UnsupportedFormat,
+    detect::{self, FileType, error},
+    qi_map, voltage_spectroscopy,
+};
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::PathBuf,
+};
+
+const VOLTAGE_SPECTROSCOPY_FILE: &str =
+    "../data/voltage-spectroscopy/voltage-spectroscopy.jpk-voltage-ramp";
+const IMAGE_FILE: &str = "../data/qi_data/qi_data-2_0-xs/data-image.jpk-qi-image";
+
+#[test]
+fn detect_voltage_spectroscopy() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(VOLTAGE_SPECTROSCOPY_FILE);
+    let detected = detect::detect(&path).unwrap();
+    assert_eq!(detected.file_type(), FileType::VoltageSpectroscopy);
+    assert_eq!(detected.format_version().unwrap(), "2.0");
+
+    let mut file = jpk_reader::open(path).unwrap();
+    assert_eq!(file.file_type(), FileType::VoltageSpectroscopy);
+    assert!(file.thumbnail().unwrap().is_none());
+    assert!(file.previews().unwrap().is_empty());
+    let JpkFile::VoltageSpectroscopy(reader) = file else {
+        panic!("expected a voltage spectroscopy reader");
+    };
+    assert!(reader.validate_dataset_type());
+}
+
+#[test]
+fn detect_image() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(IMAGE_FILE);
+    let mut file = jpk_reader::open(&path).unwrap();
+    let thumbnail = file.thumbnail().unwrap().unwrap();
+    assert_eq!(thumbnail.path(), &path);
+    assert_eq!(file.previews().unwrap().len(), 1);
+
+    let JpkFile::Image(image) = file else {
+        panic!("expected an image");
+    };
+    assert_eq!(image.channels().len(), 5);
+}
+
+#[test]
+fn detect_unknown_format() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let err = jpk_reader::open(path).err().unwrap();
+    assert!(matches!(err, error::Open::UnknownFormat(_)));
+}
+
+#[test]
+fn detect_format_not_supported() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("detect_format_not_supported.jpk-voltage-ramp");
+    fs::write(
+        &path,
+        archive_with_header("file-format-version=2.0", "file-format-version=3.0").into_inner(),
+    )
+    .unwrap();
+
+    let err = jpk_reader::open(&path).err().unwrap();
+    let error::Open::FileFormatNotSupported { file_type, error } = &err else {
+        panic!("expected an unsupported format error, found {err}");
+    };
+    assert_eq!(*file_type, FileType::VoltageSpectroscopy);
+    assert!(matches!(error, UnsupportedFormat::Version(version) if version == "3.0"));
+
+    let archive = archive_with_header("file-format-version=2.0", "file-format-version=3.0");
+    let Err(err) = qi_map::Reader::new(archive) else {
+        panic!("expected an unsupported format error");
+    };
+    let qi_map::Error::FileFormatNotSupported {
+        dataset_type,
+        error: UnsupportedFormat::Version(version),
+    } = &err
+    else {
+        panic!("expected an unsupported format error, found {err}");
+    };
+    assert_eq!(
+        dataset_type.as_deref(),
+        Some("voltage-spectroscopy-segment-series")
+    );
+    assert_eq!(version, "3.0");
+    assert!(
+        err.to_string()
+            .contains("voltage-spectroscopy-segment-series")
+    );
+}
+
+#[test]
+fn detect_unknown_feature() {
+    // Sets feature 84, which the reader is not validated against.
+    let archive = archive_with_header(
+        "file-format-features=100000400000104002013",
+        "file-format-features=1100000400000104002013",
+    );
+    let path =
+        PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("detect_unknown_feature.jpk-voltage-ramp");
+    fs::write(&path, archive.get_ref()).unwrap();
+
+    let detected = detect::detect(&path).unwrap();
+    let unknown = detected.file_format().unwrap().unknown_features().unwrap();
+    assert_eq!(unknown.iter().collect::<Vec<_>>(), vec![84]);
+
+    let file = jpk_reader::open(&path).unwrap();
+    assert_eq!(file.file_type(), FileType::VoltageSpectroscopy);
+    assert!(
+        voltage_spectroscopy::v2_0::Reader::new(zip::ZipArchive::new(archive).unwrap()).is_ok()
+    );
+}
+
+fn archive_with_header(from: &str, to: &str) -> io::Cursor<Vec<u8>> {
+    let source_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(VOLTAGE_SPECTROSCOPY_FILE);
+    let mut source = zip::ZipArchive::new(fs::File::open(source_path).unwrap()).unwrap();
+
+    let mut archive = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    for idx in 0..source.len() {
+        let mut file = source.by_index(idx).unwrap();
+        let name = file.name().to_string();
+        if file.is_dir() {
+            archive.add_directory(name, options).unwrap();
+            continue;
+        }
+
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents).unwrap();
+        if name == "header.properties" {
+            contents = String::from_utf8(contents)
+                .unwrap()
+                .replace(from, to)
+                .into_bytes();
+        }
+
+        archive.start_file(name, options).unwrap();
+        archive.write_all(&contents).unwrap();
+    }
+
+    let mut buffer = archive.finish().unwrap();
+    buffer.set_position(0);
+    buffer
+}
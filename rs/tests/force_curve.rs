@@ -0,0 +1,104 @@
+use jpk_reader::force_curve::v2_0 as jpk;
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Extracted contents of a force curve, trimmed from the first pixel of the xs QI map.
+const DATA_DIR: &str = "../data/force-curve/force-curve-xs";
+
+#[test]
+fn force_curve_reader() {
+    let mut reader = jpk::Reader::new(zip::ZipArchive::new(archive()).unwrap()).unwrap();
+    assert!(reader.validate_dataset_type());
+    assert_eq!(reader.segments_count().unwrap(), 2);
+    assert_eq!(reader.segment_style(0).unwrap(), jpk::SegmentStyle::Extend);
+    assert_eq!(reader.segment_style(1).unwrap(), jpk::SegmentStyle::Retract);
+
+    let (x, y) = reader.position().unwrap();
+    assert_eq!(x, -9.388112985283302E-6);
+    assert_eq!(y, 5.654486177889925E-6);
+
+    let height = reader.channel_data(0, "height", None).unwrap();
+    assert_eq!(height.data().len(), 250);
+    assert_eq!(height.unit(), "m");
+}
+
+#[test]
+fn force_curve_load_data() {
+    let dir = data_dir("force_curve_load_data");
+    let mut reader = jpk::FileReader::new(dir.join("curve-0.jpk-force")).unwrap();
+    let df = reader.load_data_all().unwrap();
+    assert_eq!(df.height(), 2 * 250);
+
+    let names = df.get_column_names();
+    assert_eq!(names[0].as_str(), "segment");
+    assert_eq!(names[1].as_str(), "style");
+    let style = df.column("style").unwrap().str().unwrap();
+    assert_eq!(style.get(0), Some("extend"));
+    assert_eq!(style.get(250), Some("retract"));
+}
+
+#[test]
+fn force_curve_load_dir() {
+    let dir = data_dir("force_curve_load_dir");
+    let reader = jpk::DirReader::new(&dir);
+    let df = reader.load_data_all().unwrap();
+    assert_eq!(df.height(), 2 * 2 * 250);
+    assert!(df.column("x").is_ok());
+    assert!(df.column("y").is_ok());
+}
+
+/// Directory with two force curves.
+fn data_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for idx in 0..2 {
+        let path = dir.join(format!("curve-{idx}.jpk-force"));
+        fs::write(path, archive().into_inner()).unwrap();
+    }
+    fs::write(dir.join("ignored.txt"), "").unwrap();
+    dir
+}
+
+/// Archive of the extracted force curve.
+fn archive() -> io::Cursor<Vec<u8>> {
+    let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(DATA_DIR);
+    let mut archive = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+    add_dir(&mut archive, &data_path, &data_path);
+    let mut buffer = archive.finish().unwrap();
+    buffer.set_position(0);
+    buffer
+}
+
+fn add_dir<W>(archive: &mut zip::ZipWriter<W>, root: &Path, dir: &Path)
+where
+    W: io::Write + io::Seek,
+{
+    let mut entries = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            add_dir(archive, root, &path);
+            continue;
+        }
+
+        let name = path
+            .strip_prefix(root)
+            .unwrap()
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        archive.start_file(name, options).unwrap();
+        archive.write_all(&fs::read(&path).unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,78 @@
+use jpk_reader::{jpk_export, voltage_spectroscopy::v2_0 as jpk};
+use std::{fs, path::PathBuf};
+
+const DATA_FILE: &str = "../data/voltage-spectroscopy/voltage-spectroscopy.jpk-voltage-ramp";
+/// Export of [`DATA_FILE`].
+const EXPORT_FILE: &str = "../data/jpk-export/voltage-spectroscopy.txt";
+
+#[test]
+fn jpk_export_matches_archive() {
+    let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(DATA_FILE);
+    let mut reader = jpk::FileReader::new(data_path).unwrap();
+    let expected = reader.load_data_all().unwrap();
+
+    let export_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(EXPORT_FILE);
+    let export = jpk_export::FileReader::new(export_path).unwrap();
+    assert_eq!(export.position().unwrap(), reader.position().unwrap());
+    assert_eq!(export.segments().len(), 2);
+
+    let segment = &export.segments()[1];
+    assert_eq!(segment.name(), Some("Linear Ramp (2)"));
+    let column = segment.metadata().column("cafmCurrent").unwrap();
+    assert_eq!(column.fancy_name().unwrap(), "CAFM Current");
+    assert_eq!(column.unit().unwrap(), "A");
+
+    let df = export.load_data_all().unwrap();
+    assert_eq!(df.schema(), expected.schema());
+    assert!(df.equals_missing(&expected));
+}
+
+#[test]
+fn jpk_export_segment_without_data() {
+    let path =
+        PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("jpk_export_segment_without_data.txt");
+    fs::write(
+        &path,
+        "# xPosition: 1.0\n# yPosition: 2.0\n\
+        # segmentIndex: 0\n# segment: extend\n# columns: height\n\
+        # segmentIndex: 1\n# segment: retract\n# columns: height\n0.0\n0.1\n\
+        # segmentIndex: 2\n# segment: pause\n# columns: height\n",
+    )
+    .unwrap();
+    let export = jpk_export::FileReader::new(&path).unwrap();
+    assert_eq!(export.position().unwrap(), (1.0, 2.0));
+
+    let segments = export
+        .segments()
+        .iter()
+        .map(|segment| (segment.name().unwrap(), segment.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(segments, [("extend", 0), ("retract", 2), ("pause", 0)]);
+}
+
+#[test]
+fn jpk_export_invalid_row() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("jpk_export_invalid_row.txt");
+    fs::write(&path, "# columns: height vDeflection\n0.0 1.0\n0.1\n").unwrap();
+    let Err(err) = jpk_export::FileReader::new(&path) else {
+        panic!("expected an invalid row error");
+    };
+    assert!(matches!(
+        err,
+        jpk_export::error::Export::InvalidRow {
+            line: 3,
+            expected: 2,
+            found: 1
+        }
+    ));
+}
+
+#[test]
+fn jpk_export_detect() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("jpk_export_detect.tsv");
+    fs::write(&path, "# segmentIndex: 0\n# columns: height\n0.0\n0.1\n").unwrap();
+    let jpk_reader::JpkFile::Export(export) = jpk_reader::open(&path).unwrap() else {
+        panic!("expected an export");
+    };
+    assert_eq!(export.segments()[0].len(), 2);
+}
@@ -0,0 +1,106 @@
+use jpk_reader::jpk_image::{Image, Scaling, error};
+use std::path::PathBuf;
+
+const IMAGE_FILE: &str = "../data/qi_data/qi_data-2_0-xs/data-image.jpk-qi-image";
+
+#[test]
+fn jpk_image_metadata() {
+    let image = image();
+    let metadata = image.metadata();
+    assert_eq!(metadata.file_format_version(), Some(2.0));
+    assert_eq!(metadata.account_name().unwrap(), "jpkuser");
+    assert_eq!(metadata.scan_number(), Some(35));
+
+    let grid = image.grid().unwrap();
+    assert_eq!(grid.i_length(), 128);
+    assert_eq!(grid.j_length(), 128);
+    assert!((grid.u_length() - 5e-6).abs() < 1e-18);
+    assert!(!grid.reflect());
+}
+
+#[test]
+fn jpk_image_thumbnail() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(IMAGE_FILE);
+    let image = Image::open(&path).unwrap();
+    let thumbnail = image.thumbnail().unwrap();
+    assert_eq!(thumbnail.path(), &path);
+    assert_eq!(
+        thumbnail.format(),
+        jpk_reader::dataset::preview::ImageFormat::Bmp
+    );
+    assert_eq!(thumbnail.dimensions(), Some((64, 64)));
+}
+
+#[test]
+fn jpk_image_channels() {
+    let image = image();
+    let names = image
+        .channels()
+        .iter()
+        .map(|channel| channel.name().as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            "vDeflection",
+            "slope",
+            "adhesion",
+            "height",
+            "measuredHeight"
+        ]
+    );
+
+    let height = image.channel("height", false).unwrap();
+    assert!(image.channel("height", true).is_none());
+    assert_eq!(height.width(), 128);
+    assert_eq!(height.height(), 128);
+    assert_eq!(height.default_slot().unwrap(), "calibrated");
+    let slots = height
+        .slots()
+        .iter()
+        .map(|slot| slot.name().as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(slots, vec!["raw", "volts", "nominal", "calibrated"]);
+    assert_eq!(height.slot("raw").unwrap().scaling(), &Scaling::Null);
+    assert_eq!(height.slot("nominal").unwrap().parent().unwrap(), "volts");
+}
+
+#[test]
+fn jpk_image_channel_data() {
+    let image = image();
+    let slope = image.channel("slope", false).unwrap();
+    let data = slope.data(None).unwrap();
+    assert_eq!(data.unit().unwrap(), "N/m");
+    assert_eq!(data.data().len(), 128 * 128);
+    let raw = slope.raw()[128 + 2];
+    let expected = raw * 5.705800525496144e-10 + 2.292908638063887;
+    assert_eq!(data.get(2, 1), Some(expected));
+    assert_eq!(data.row(1).unwrap()[2], expected);
+    assert!(data.get(128, 0).is_none());
+    assert!(data.row(128).is_none());
+
+    let height = image.channel("height", false).unwrap();
+    let data = height.data(None).unwrap();
+    assert_eq!(data.unit().unwrap(), "m");
+    assert!(data.data().iter().all(|value| value.abs() < 1e-3));
+
+    let raw = height.data(Some("raw")).unwrap();
+    assert!(raw.unit().is_none());
+    assert_eq!(raw.data(), height.raw());
+    assert!(matches!(
+        height.data(Some("force")),
+        Err(error::Slot::NotFound(_))
+    ));
+}
+
+#[test]
+fn jpk_image_invalid_file() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../data/qi_data/qi_data-2_0-xs/header.properties");
+    let err = Image::open(path).err().unwrap();
+    assert!(matches!(err, error::Image::Tiff(_)));
+}
+
+fn image() -> Image {
+    Image::open(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(IMAGE_FILE)).unwrap()
+}
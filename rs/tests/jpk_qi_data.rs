@@ -1,9 +1,16 @@
 use jpk_reader::qi_map::{self, QIMapReader};
-use std::{fs, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
 
 const DATA_DIR: &str = "../data/qi_data";
 const DATA_FILE_LG: &str = "qi_data-2_0-lg.jpk-qi-data";
 const DATA_FILE_SM: &str = "qi_data-sm.jpk-qi-data";
+/// Extracted contents of a small QI map archive.
+const DATA_DIR_XS: &str = "qi_data-2_0-xs";
 
 #[test]
 fn qi_map_file_reader_format_version() {
@@ -29,6 +36,7 @@ fn qi_map_reader_query_data() {
         index: qi_map::IndexQuery::Pixel(pixel.clone()),
         segment: qi_map::SegmentQuery::Indices(vec![0]),
         channel: qi_map::ChannelQuery::include(vec!["measuredHeight", "smoothedMeasuredHeight"]),
+        calibration_slots: HashMap::new(),
     };
     let result = data.query_data(&query).unwrap();
     assert_eq!(result.len(), 2);
@@ -79,6 +87,7 @@ fn qi_map_file_reader_query_data() {
         index: qi_map::IndexQuery::Pixel(pixel.clone()),
         segment: qi_map::SegmentQuery::Indices(vec![0]),
         channel: qi_map::ChannelQuery::include(vec!["measuredHeight", "smoothedMeasuredHeight"]),
+        calibration_slots: HashMap::new(),
     };
     let result = data.query_data(&query).unwrap();
     assert_eq!(result.len(), 2);
@@ -116,6 +125,653 @@ fn qi_map_file_reader_query_metadata() {
     assert_eq!(result.len(), 1);
 }
 
+#[test]
+fn qi_map_reader_query_data_xs() {
+    let mut data = qi_map::Reader::new(xs::archive()).unwrap();
+
+    let query = qi_map::DataQuery {
+        index: qi_map::IndexQuery::Pixel(qi_map::Pixel::new(0, 0)),
+        segment: qi_map::SegmentQuery::All,
+        channel: qi_map::ChannelQuery::include(vec!["height", "vDeflection"]),
+        calibration_slots: HashMap::new(),
+    };
+    let result = data.query_data(&query).unwrap();
+    assert_eq!(result.len(), 4);
+    for segment in 0..2 {
+        for channel in ["height", "vDeflection"] {
+            let idx = qi_map::DataIndex::new(0, segment, channel);
+            let values = result.get(&idx).unwrap();
+            assert_eq!(values.len(), 250);
+        }
+    }
+}
+
+#[test]
+fn qi_map_reader_query_data_generated_channel_xs() {
+    let mut data = qi_map::Reader::new(xs::archive()).unwrap();
+
+    let query = qi_map::DataQuery {
+        index: qi_map::IndexQuery::Index(0),
+        segment: qi_map::SegmentQuery::Indices(vec![0]),
+        channel: qi_map::ChannelQuery::All,
+        calibration_slots: HashMap::new(),
+    };
+    let result = data.query_data(&query).unwrap();
+    assert_eq!(result.len(), 12);
+
+    let time = result.get(&qi_map::DataIndex::new(0, 0, "time")).unwrap();
+    assert_eq!(time.len(), 250);
+    let start = 6.320000000000001E-6;
+    let step = 1.2640000000000003E-5;
+    assert_eq!(time[0], start);
+    assert!((time[249] - (start + 249.0 * step)).abs() < 1e-15);
+}
+
+#[test]
+fn qi_map_reader_query_data_calibration_slot_xs() {
+    const DISTANCE_MULTIPLIER: f64 = 5.740781811552423E-8;
+    const FORCE_MULTIPLIER: f64 = 2.8307938999982456;
+
+    let mut data = qi_map::Reader::new(xs::archive()).unwrap();
+    let idx = qi_map::DataIndex::new(0, 0, "vDeflection");
+    let mut query = qi_map::DataQuery {
+        index: qi_map::IndexQuery::Index(0),
+        segment: qi_map::SegmentQuery::Indices(vec![0]),
+        channel: qi_map::ChannelQuery::include(vec!["vDeflection"]),
+        calibration_slots: HashMap::new(),
+    };
+
+    let result = data.query_data(&query).unwrap();
+    assert_eq!(result.unit(&idx).unwrap(), "N");
+    let force = result.get(&idx).unwrap().clone();
+
+    let mut results = HashMap::new();
+    for (slot, unit) in [("volts", "V"), ("distance", "m"), ("force", "N")] {
+        query
+            .calibration_slots
+            .insert("vDeflection".to_string(), slot.to_string());
+        let result = data.query_data(&query).unwrap();
+        assert_eq!(result.unit(&idx).unwrap(), unit);
+        results.insert(slot, result.get(&idx).unwrap().clone());
+    }
+
+    assert_eq!(results["force"], force);
+    for (idx, volts) in results["volts"].iter().enumerate() {
+        let distance = volts * DISTANCE_MULTIPLIER;
+        assert_eq!(results["distance"][idx], distance);
+        assert_eq!(results["force"][idx], distance * FORCE_MULTIPLIER);
+    }
+
+    query
+        .calibration_slots
+        .insert("vDeflection".to_string(), "nominal".to_string());
+    assert!(matches!(
+        data.query_data(&query),
+        Err(qi_map::QueryError::InvalidCalibrationSlot(_))
+    ));
+}
+
+#[test]
+fn qi_map_reader_query_metadata_xs() {
+    let mut data = qi_map::Reader::new(xs::archive()).unwrap();
+
+    let query = qi_map::MetadataQuery::Dataset;
+    let result = data.query_metadata(&query).unwrap();
+    assert_eq!(result.len(), 1);
+    let properties = result.get(&qi_map::MetadataIndex::Dataset).unwrap();
+    assert_eq!(properties.get("type").unwrap(), "quantitative-imaging-map");
+
+    let query = qi_map::MetadataQuery::SharedData;
+    let result = data.query_metadata(&query).unwrap();
+    assert_eq!(result.len(), 1);
+
+    let pixel = qi_map::Pixel::new(3, 0);
+    let query = qi_map::MetadataQuery::Index(qi_map::IndexQuery::Pixel(pixel));
+    let result = data.query_metadata(&query).unwrap();
+    assert_eq!(result.len(), 1);
+    assert!(result.contains_key(&qi_map::MetadataIndex::Index(3)));
+
+    let query = qi_map::MetadataQuery::Index(qi_map::IndexQuery::Index(7));
+    let result = data.query_metadata(&query).unwrap();
+    assert_eq!(result.len(), 1);
+    assert!(result.contains_key(&qi_map::MetadataIndex::Index(7)));
+
+    let rect = qi_map::PixelRect::new(qi_map::Pixel::new(1, 0), qi_map::Pixel::new(4, 0));
+    let query = qi_map::MetadataQuery::Segment {
+        index: qi_map::IndexQuery::PixelRect(rect),
+        segment: qi_map::SegmentQuery::All,
+    };
+    let result = data.query_metadata(&query).unwrap();
+    assert_eq!(result.len(), 4 * 2);
+    for index in 1..=4 {
+        for segment in 0..2 {
+            assert!(result.contains_key(&qi_map::MetadataIndex::Segment { index, segment }));
+        }
+    }
+}
+
+#[test]
+fn qi_map_file_reader_query_data_xs() {
+    let mut data = qi_map::FileReader::new(xs::file()).unwrap();
+
+    let query = qi_map::DataQuery {
+        index: qi_map::IndexQuery::Index(3),
+        segment: qi_map::SegmentQuery::All,
+        channel: qi_map::ChannelQuery::include(vec!["vDeflection"]),
+        calibration_slots: HashMap::new(),
+    };
+    let result = data.query_data(&query).unwrap();
+    assert_eq!(result.len(), 2);
+    let idx = qi_map::DataIndex::new(3, 1, "vDeflection");
+    let values = result.get(&idx).unwrap();
+    assert_eq!(values.len(), 250);
+}
+
+#[test]
+fn qi_map_file_reader_query_metadata_xs() {
+    let mut data = qi_map::FileReader::new(xs::file()).unwrap();
+
+    let query = qi_map::MetadataQuery::All;
+    let result = data.query_metadata(&query).unwrap();
+    // dataset, shared data, and 10 indices each with 2 segments
+    assert_eq!(result.len(), 2 + 10 * 3);
+    assert!(result.contains_key(&qi_map::MetadataIndex::Segment {
+        index: 9,
+        segment: 1
+    }));
+
+    let rect = qi_map::PixelRect::new(qi_map::Pixel::new(2, 0), qi_map::Pixel::new(6, 0));
+    let query = qi_map::MetadataQuery::Index(qi_map::IndexQuery::PixelRect(rect));
+    let result = data.query_metadata(&query).unwrap();
+    assert_eq!(result.len(), 5);
+    assert!(result.contains_key(&qi_map::MetadataIndex::Index(6)));
+
+    let query = qi_map::MetadataQuery::Segment {
+        index: qi_map::IndexQuery::Index(5),
+        segment: qi_map::SegmentQuery::Indices(vec![1]),
+    };
+    let result = data.query_metadata(&query).unwrap();
+    assert_eq!(result.len(), 1);
+    let properties = result
+        .get(&qi_map::MetadataIndex::Segment {
+            index: 5,
+            segment: 1,
+        })
+        .unwrap();
+    assert!(properties.get("channels.list").is_some());
+}
+
+#[test]
+fn force_map_reader_query_data_xs() {
+    let archive = zip::ZipArchive::new(xs::force_map_archive()).unwrap();
+    let mut data = qi_map::v2_0::Reader::new(archive).unwrap();
+    assert_eq!(
+        data.dataset_info().map_type(),
+        qi_map::v2_0::MapType::ForceScan
+    );
+    assert!(data.validate_dataset_type());
+
+    let query = qi_map::DataQuery {
+        index: qi_map::IndexQuery::Pixel(qi_map::Pixel::new(0, 0)),
+        segment: qi_map::SegmentQuery::All,
+        channel: qi_map::ChannelQuery::include(vec!["height"]),
+        calibration_slots: HashMap::new(),
+    };
+    let result = data.query_data(&query).unwrap();
+    assert_eq!(result.len(), 2);
+
+    let query = qi_map::MetadataQuery::Index(qi_map::IndexQuery::Pixel(qi_map::Pixel::new(0, 0)));
+    let result = data.query_metadata(&query).unwrap();
+    assert_eq!(result.len(), 1);
+    let properties = &result[&qi_map::MetadataIndex::Index(0)];
+    assert_eq!(properties.get("type"), Some("force-scan-series"));
+
+    let query = qi_map::MetadataQuery::Segment {
+        index: qi_map::IndexQuery::Pixel(qi_map::Pixel::new(0, 0)),
+        segment: qi_map::SegmentQuery::All,
+    };
+    let result = data.query_metadata(&query).unwrap();
+    assert_eq!(result.len(), 2);
+    let properties = &result[&qi_map::MetadataIndex::Segment {
+        index: 0,
+        segment: 1,
+    }];
+    assert_eq!(
+        properties.get("force-segment-header.settings.style"),
+        Some("retract")
+    );
+}
+
+#[test]
+fn qi_map_reader_thumbnail_xs() {
+    let archive = zip::ZipArchive::new(xs::archive()).unwrap();
+    let mut data = qi_map::v2_0::Reader::new(archive).unwrap();
+    let thumbnail = data.thumbnail().unwrap().unwrap();
+    assert_eq!(thumbnail.path(), Path::new("thumbnail.png"));
+    assert_eq!(
+        thumbnail.format(),
+        jpk_reader::dataset::preview::ImageFormat::Png
+    );
+    assert_eq!(thumbnail.dimensions(), Some((64, 64)));
+
+    // the thumbnail of the data image is re-encoded
+    let previews = data.previews().unwrap();
+    assert_eq!(previews.len(), 2);
+    assert_eq!(previews[0].path(), Path::new("data-image.jpk-qi-image"));
+    assert_eq!(
+        previews[0].format(),
+        jpk_reader::dataset::preview::ImageFormat::Bmp
+    );
+    assert_eq!(previews[0].dimensions(), Some((64, 64)));
+}
+
+#[test]
+fn qi_map_versioned_reader_thumbnail_xs() {
+    let mut data = qi_map::Reader::new(xs::archive()).unwrap();
+    let thumbnail = data.thumbnail().unwrap().unwrap();
+    assert_eq!(thumbnail.path(), Path::new("thumbnail.png"));
+    assert_eq!(data.previews().unwrap().len(), 2);
+}
+
+#[test]
+fn qi_map_file_reader_missing_file_error() {
+    let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join(DATA_DIR)
+        .join("missing.jpk-qi-data");
+
+    let err = qi_map::FileReader::format_version(&data_path).unwrap_err();
+    assert!(matches!(err, qi_map::Error::OpenFile { .. }));
+    assert!(err.to_string().contains("missing.jpk-qi-data"));
+}
+
+#[test]
+fn qi_map_reader_missing_property_error() {
+    use std::error::Error as _;
+
+    let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join(DATA_DIR)
+        .join(DATA_DIR_XS);
+    let shared_data = fs::read(data_path.join("shared-data/header.properties")).unwrap();
+
+    let mut archive = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    archive.start_file("header.properties", options).unwrap();
+    archive
+        .write_all(b"file-format-version=2.0\ntype=quantitative-imaging-map\n")
+        .unwrap();
+    archive
+        .start_file("shared-data/header.properties", options)
+        .unwrap();
+    archive.write_all(&shared_data).unwrap();
+    let mut archive = archive.finish().unwrap();
+    archive.set_position(0);
+
+    let Err(err) = qi_map::Reader::new(archive) else {
+        panic!("expected missing property error");
+    };
+    assert!(err.to_string().contains("header.properties"));
+    let source = err.source().unwrap().to_string();
+    assert!(source.contains("quantitative-imaging-map.indexes.type"));
+}
+
+#[test]
+fn qi_map_file_reader_removed_file_error_xs() {
+    use std::error::Error as _;
+
+    let path = xs::file_with("-removed", &|_name, contents| contents);
+    let mut data = qi_map::FileReader::new(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let query = qi_map::DataQuery {
+        index: qi_map::IndexQuery::Index(0),
+        segment: qi_map::SegmentQuery::All,
+        channel: qi_map::ChannelQuery::include(vec!["vDeflection"]),
+        calibration_slots: HashMap::new(),
+    };
+    let Err(err @ qi_map::QueryError::OpenArchive { .. }) = data.query_data(&query) else {
+        panic!("expected open archive error");
+    };
+    let source = err.source().unwrap().downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn qi_map_file_reader_error_paths_xs() {
+    const SEGMENT_HEADER: &str = "index/3/segments/0/segment-header.properties";
+
+    let path = xs::file_with("-invalid-property", &|name, contents| {
+        if name != SEGMENT_HEADER {
+            return contents;
+        }
+
+        String::from_utf8(contents)
+            .unwrap()
+            .replace(
+                "channel.vDeflection.lcd-info.*=1",
+                "channel.vDeflection.lcd-info.*=x",
+            )
+            .into_bytes()
+    });
+    let mut data = qi_map::FileReader::new(&path).unwrap();
+
+    let query = qi_map::DataQuery {
+        index: qi_map::IndexQuery::Index(3),
+        segment: qi_map::SegmentQuery::All,
+        channel: qi_map::ChannelQuery::include(vec!["vDeflection"]),
+        calibration_slots: HashMap::new(),
+    };
+    let Err(qi_map::QueryError::Property {
+        archive,
+        path: entry,
+        ..
+    }) = data.query_data(&query)
+    else {
+        panic!("expected property error");
+    };
+    assert_eq!(archive.as_ref(), Some(&path));
+    assert_eq!(entry, PathBuf::from(SEGMENT_HEADER));
+}
+
+#[test]
+fn qi_map_reader_override_calibration_xs() {
+    use jpk_reader::dataset::v2_0::error::OverrideCalibration;
+    const DISTANCE_MULTIPLIER: f64 = 5.740781811552423E-8;
+    const SENSITIVITY: f64 = 6E-8;
+    const KEY: &str = "lcd-info.1.conversion-set.conversion.distance.scaling";
+
+    let qi_map::VersionedReader::V2_0(mut data) =
+        qi_map::Reader::new_versioned(xs::archive()).unwrap();
+    let query = qi_map::DataQuery {
+        index: qi_map::IndexQuery::Index(0),
+        segment: qi_map::SegmentQuery::Indices(vec![0]),
+        channel: qi_map::ChannelQuery::include(vec!["vDeflection"]),
+        calibration_slots: HashMap::from([("vDeflection".to_string(), "distance".to_string())]),
+    };
+    let index = qi_map::DataIndex::new(0, 0, "vDeflection");
+    let original = data
+        .query_data(&query)
+        .unwrap()
+        .get(&index)
+        .unwrap()
+        .clone();
+
+    data.override_calibration("vDeflection", "distance", SENSITIVITY, 0.0)
+        .unwrap();
+    let calibrated = data
+        .query_data(&query)
+        .unwrap()
+        .get(&index)
+        .unwrap()
+        .clone();
+    for (original, calibrated) in original.iter().zip(calibrated) {
+        let expected = original / DISTANCE_MULTIPLIER * SENSITIVITY;
+        assert!((calibrated - expected).abs() <= expected.abs() * 1e-12);
+    }
+
+    for query in [
+        qi_map::MetadataQuery::SharedData,
+        qi_map::MetadataQuery::All,
+    ] {
+        let metadata = data.query_metadata(&query).unwrap();
+        let properties = &metadata[&qi_map::MetadataIndex::SharedData];
+        let scaling = properties.subtree(KEY);
+        assert_eq!(scaling.get_f64("multiplier").unwrap(), SENSITIVITY);
+        assert_eq!(
+            scaling.get_f64("original-multiplier").unwrap(),
+            DISTANCE_MULTIPLIER
+        );
+        assert_eq!(scaling.get_f64("original-offset").unwrap(), 0.0);
+    }
+
+    assert!(matches!(
+        data.override_calibration("unknown", "distance", 1.0, 0.0),
+        Err(OverrideCalibration::ChannelNotFound(_))
+    ));
+    assert!(matches!(
+        data.override_calibration("vDeflection", "nominal", 1.0, 0.0),
+        Err(OverrideCalibration::InvalidCalibrationSlot(_))
+    ));
+}
+
+#[test]
+fn force_curve_analysis_xs() {
+    use jpk_reader::analysis::force_curve::{self, FitRange, ForceCurve};
+
+    let mut data = qi_map::Reader::new(xs::archive()).unwrap();
+    let query = force_curve::data_query(qi_map::IndexQuery::Index(0));
+    let result = data.query_data(&query).unwrap();
+    let shared = data
+        .query_metadata(&qi_map::MetadataQuery::SharedData)
+        .unwrap();
+    let shared = &shared[&qi_map::MetadataIndex::SharedData];
+    let curve = ForceCurve::from_data(&result, 0, shared, None).unwrap();
+    assert_eq!(curve.spring_constant(), 2.8307938999982456);
+    assert_eq!(curve.extend().len(), 250);
+    assert_eq!(curve.retract().len(), 250);
+
+    let processed = curve.process(&FitRange::default()).unwrap();
+    let contact = processed.contact_point();
+    assert!(contact.index() > 200 && contact.index() < 249);
+    let indentation = processed.indentation(processed.extend());
+    assert!(indentation[contact.index()].abs() < 1e-15);
+    assert!(indentation[249] > 0.0);
+    assert!(processed.force(processed.extend())[249] > 0.0);
+}
+
+#[test]
+fn elastic_fit_map_xs() {
+    use jpk_reader::analysis::elastic::{self, FitSettings, Model, Tip};
+
+    let (mut data, result, shared) = xs::first_line_force_curves();
+    let metadata = data
+        .query_metadata(&qi_map::MetadataQuery::Dataset)
+        .unwrap();
+    let properties = &metadata[&qi_map::MetadataIndex::Dataset];
+    let tip =
+        Tip::from_properties(&properties.subtree(format!("quantitative-imaging-map.{}", Tip::KEY)))
+            .unwrap();
+    assert_eq!(
+        tip,
+        Tip::Paraboloid {
+            radius: 3.0000000000000004E-8
+        }
+    );
+
+    let pattern = data.dataset_info().position_pattern();
+    let settings = FitSettings::new(Model::Hertz, tip);
+    let map = elastic::fit_map(&result, pattern, &shared, None, &settings).unwrap();
+    assert_eq!((map.i_length(), map.j_length()), (128, 128));
+    assert!(map.get(&qi_map::Pixel::new(10, 0)).is_none());
+
+    let fit = map
+        .get(&qi_map::Pixel::new(0, 0))
+        .unwrap()
+        .as_ref()
+        .unwrap();
+    assert!(fit.modulus() > 0.0);
+    assert!(fit.uncertainty() < fit.modulus());
+    assert!(fit.residual() > 0.0);
+
+    let modulus = map.modulus();
+    assert_eq!(modulus.len(), 128);
+    assert_eq!(modulus[0][0], fit.modulus());
+    assert!(modulus[1][0].is_nan());
+}
+
+#[test]
+fn mechanics_map_xs() {
+    use jpk_reader::analysis::mechanics;
+
+    let (data, result, shared) = xs::first_line_force_curves();
+    let pattern = data.dataset_info().position_pattern();
+    let df = mechanics::mechanics_map(
+        &result,
+        pattern,
+        &shared,
+        None,
+        &mechanics::Settings::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        df.get_column_names(),
+        [
+            "i",
+            "j",
+            "adhesion",
+            "work_of_adhesion",
+            "dissipation",
+            "slope",
+            "error"
+        ]
+    );
+    assert_eq!(df.height(), 10);
+    let i = df.column("i").unwrap().u32().unwrap();
+    assert_eq!(
+        i.into_no_null_iter().collect::<Vec<_>>(),
+        (0..10).collect::<Vec<_>>()
+    );
+    let adhesion = df.column("adhesion").unwrap().f64().unwrap();
+    assert!(
+        adhesion
+            .into_iter()
+            .flatten()
+            .all(|adhesion| adhesion >= 0.0)
+    );
+    let slope = df.column("slope").unwrap().f64().unwrap();
+    assert!(slope.get(0).unwrap() > 0.0);
+    let error = df.column("error").unwrap().str().unwrap();
+    assert!(std::iter::zip(error, slope).all(|(error, slope)| error.is_some() != slope.is_some()));
+}
+
+/// Archive the extracted xs data set for testing.
+mod xs {
+    use super::*;
+    use jpk_reader::{analysis::force_curve, dataset::properties::Properties};
+
+    /// Reader of the in memory archive, the force curve data of the first line of pixels,
+    /// and the shared properties.
+    pub fn first_line_force_curves() -> (
+        qi_map::v2_0::Reader<io::Cursor<Vec<u8>>>,
+        qi_map::Data,
+        Properties,
+    ) {
+        let qi_map::VersionedReader::V2_0(mut data) =
+            qi_map::Reader::new_versioned(archive()).unwrap();
+        let rect = qi_map::PixelRect::new(qi_map::Pixel::new(0, 0), qi_map::Pixel::new(9, 0));
+        let query = force_curve::data_query(qi_map::IndexQuery::PixelRect(rect));
+        let result = data.query_data(&query).unwrap();
+        let shared = data
+            .query_metadata(&qi_map::MetadataQuery::SharedData)
+            .unwrap();
+        let shared = shared[&qi_map::MetadataIndex::SharedData].clone();
+        (data, result, shared)
+    }
+
+    /// In memory archive.
+    pub fn archive() -> io::Cursor<Vec<u8>> {
+        archive_with(&|_name, contents| contents)
+    }
+
+    /// In memory archive relabeled as a force map.
+    /// The dataset, index and segment headers follow the layout of a force map.
+    pub fn force_map_archive() -> io::Cursor<Vec<u8>> {
+        archive_with(&|name, contents| {
+            if !name.ends_with("header.properties") || name.starts_with("shared-data/") {
+                return contents;
+            }
+
+            let contents = String::from_utf8(contents)
+                .unwrap()
+                .replace("quantitative-imaging-map", "force-scan-map")
+                .replace("spm-quantitative-image-data-file", "spm-forcefile")
+                .replace("quantitative-imaging-series", "force-scan-series")
+                .replace("quantitative-imaging-settings", "force-settings");
+            let Some(segment) = name
+                .strip_suffix("/segment-header.properties")
+                .and_then(|name| name.rsplit('/').next())
+            else {
+                return contents.into_bytes();
+            };
+
+            // force map segments record the settings of their motion
+            let style = if segment == "0" { "extend" } else { "retract" };
+            contents
+                .replace(
+                    "force-segment-header.type=",
+                    &format!(
+                        "force-segment-header.settings.style={style}\nforce-segment-header.type="
+                    ),
+                )
+                .into_bytes()
+        })
+    }
+
+    /// In memory archive with the contents of each file passed through `transform`.
+    fn archive_with(transform: &dyn Fn(&str, Vec<u8>) -> Vec<u8>) -> io::Cursor<Vec<u8>> {
+        let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join(DATA_DIR)
+            .join(DATA_DIR_XS);
+
+        let mut archive = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+        add_dir(&mut archive, &data_path, &data_path, transform);
+        let mut buffer = archive.finish().unwrap();
+        buffer.set_position(0);
+        buffer
+    }
+
+    /// Archive written to the test directory.
+    pub fn file() -> PathBuf {
+        file_with("", &|_name, contents| contents)
+    }
+
+    /// Archive with the contents of each file passed through `transform`,
+    /// written to the test directory.
+    pub fn file_with(label: &str, transform: &dyn Fn(&str, Vec<u8>) -> Vec<u8>) -> PathBuf {
+        let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!(
+            "{DATA_DIR_XS}{label}-{:?}.jpk-qi-data",
+            std::thread::current().id()
+        ));
+        fs::write(&path, archive_with(transform).into_inner()).unwrap();
+        path
+    }
+
+    fn add_dir<W>(
+        archive: &mut zip::ZipWriter<W>,
+        root: &Path,
+        dir: &Path,
+        transform: &dyn Fn(&str, Vec<u8>) -> Vec<u8>,
+    ) where
+        W: io::Write + io::Seek,
+    {
+        let mut entries = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        entries.sort();
+
+        for path in entries {
+            if path.is_dir() {
+                add_dir(archive, root, &path, transform);
+                continue;
+            }
+
+            let name = path
+                .strip_prefix(root)
+                .unwrap()
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let options = zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Stored);
+            let contents = transform(&name, fs::read(&path).unwrap());
+            archive.start_file(name, options).unwrap();
+            archive.write_all(&contents).unwrap();
+        }
+    }
+}
+
 pub mod tmp {
     use std::{fs, io, path::Path, sync::Arc};
 
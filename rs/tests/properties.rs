@@ -0,0 +1,387 @@
+use jpk_reader::dataset::properties::{KeyInterner, Properties, error};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const DATA_DIR: &str = "../data/qi_data/qi_data-2_0-xs";
+
+#[test]
+fn properties_parse_separators() {
+    let input = "a=1\nb:2\nc 3\nd = 4\ne\t:\t5\nf\ng=\nh:=6\n";
+    let properties = Properties::parse(input).unwrap();
+    assert_eq!(properties.len(), 8);
+    assert_eq!(properties.get("a").unwrap(), "1");
+    assert_eq!(properties.get("b").unwrap(), "2");
+    assert_eq!(properties.get("c").unwrap(), "3");
+    assert_eq!(properties.get("d").unwrap(), "4");
+    assert_eq!(properties.get("e").unwrap(), "5");
+    assert_eq!(properties.get("f").unwrap(), "");
+    assert_eq!(properties.get("g").unwrap(), "");
+    assert_eq!(properties.get("h").unwrap(), "=6");
+}
+
+#[test]
+fn properties_parse_escapes() {
+    let input = r"time=16\:35\:28.549
+unit=15\u00B5m
+sqrt=nV/\u221AHz
+emoji=\uD83D\uDE00
+key\ with\:sep\=chars=value
+tab=a\tb\nc\rd\fe
+other=\q\\
+";
+    let properties = Properties::parse(input).unwrap();
+    assert_eq!(properties.get("time").unwrap(), "16:35:28.549");
+    assert_eq!(properties.get("unit").unwrap(), "15µm");
+    assert_eq!(properties.get("sqrt").unwrap(), "nV/√Hz");
+    assert_eq!(properties.get("emoji").unwrap(), "😀");
+    assert_eq!(properties.get("key with:sep=chars").unwrap(), "value");
+    assert_eq!(properties.get("tab").unwrap(), "a\tb\nc\rd\x0ce");
+    assert_eq!(properties.get("other").unwrap(), "q\\");
+}
+
+#[test]
+fn properties_parse_lines() {
+    let input = "# comment\r\n! other comment\r\n\r\n   \t\r\nlist=a \\\r\n    b \\\n\tc\r\n# comment \\\nnext=1\rescaped=a\\\\\nlast=end";
+    let properties = Properties::parse(input).unwrap();
+    assert_eq!(properties.len(), 4);
+    assert_eq!(properties.get("list").unwrap(), "a b c");
+    assert_eq!(properties.get("next").unwrap(), "1");
+    assert_eq!(properties.get("escaped").unwrap(), "a\\");
+    assert_eq!(properties.get("last").unwrap(), "end");
+    assert!(properties.timestamp().is_none());
+}
+
+#[test]
+fn properties_parse_continued_key_and_separator() {
+    let input = "ke\\\n  y=1\nsep \\\r\n\t= v\\\n  alue\nescaped=a\\:\\\n  b\n";
+    let properties = Properties::parse(input).unwrap();
+    assert_eq!(properties.len(), 3);
+    assert_eq!(properties.get("key").unwrap(), "1");
+    assert_eq!(properties.get("sep").unwrap(), "value");
+    assert_eq!(properties.get("escaped").unwrap(), "a:b");
+}
+
+#[test]
+fn properties_extract() {
+    let input = "a=1\nb=x\\:y\nc=3\n";
+    let properties = Properties::extract(&mut input.as_bytes(), &["b", "c", "d"]).unwrap();
+    assert_eq!(properties.len(), 2);
+    assert!(properties.get("a").is_none());
+    assert_eq!(properties.get("b").unwrap(), "x:y");
+    assert_eq!(properties.get("c").unwrap(), "3");
+}
+
+#[test]
+fn properties_parse_duplicate_keys() {
+    let properties = Properties::parse("a=1\nb=2\na=3\n").unwrap();
+    assert_eq!(properties.len(), 2);
+    assert_eq!(properties.get("a").unwrap(), "3");
+}
+
+#[test]
+fn properties_parse_invalid_unicode_escape() {
+    for input in [
+        "a=1\nb=\\u00\n",
+        "a=1\nb=\\u00G0\n",
+        "a=1\nb=\\uD83D\n",
+        "a=1\nb=\\uD83D\\u0041\n",
+    ] {
+        let result = Properties::parse(input);
+        assert!(
+            matches!(result, Err(error::Parse::InvalidUnicodeEscape { line: 2 })),
+            "{input:?}"
+        );
+    }
+}
+
+#[test]
+fn properties_parse_timestamp() {
+    let input = "#Thu Jan 08 16:39:16 CET 2026\na=1\n";
+    let properties = Properties::parse(input).unwrap();
+    let timestamp = properties.timestamp().unwrap();
+    assert_eq!(timestamp.year(), 2026);
+    assert_eq!(timestamp.month(), 1);
+    assert_eq!(timestamp.day(), 8);
+    assert_eq!(timestamp.hour(), 16);
+    assert_eq!(timestamp.minute(), 39);
+    assert_eq!(timestamp.second(), 16);
+    assert_eq!(timestamp.time_zone(), "CET");
+    assert_eq!(timestamp.weekday(), 4);
+    assert_eq!(timestamp.to_string(), "Thu Jan 08 16:39:16 CET 2026");
+
+    let properties = Properties::parse("#not a date\na=1\n").unwrap();
+    assert!(properties.timestamp().is_none());
+}
+
+#[test]
+fn properties_with_values() {
+    let input = "#Thu Jan 08 16:39:16 CET 2026\na=1\nb=x\\:y\n";
+    let properties = Properties::parse(input).unwrap();
+    let updated = properties.with_values([("a", "2"), ("c d", "µ=")]);
+    assert_eq!(updated.len(), 3);
+    assert_eq!(updated.get("a").unwrap(), "2");
+    assert_eq!(updated.get("b").unwrap(), "x:y");
+    assert_eq!(updated.get("c d").unwrap(), "µ=");
+    assert_eq!(updated.timestamp(), properties.timestamp());
+    assert_eq!(properties.get("a").unwrap(), "1");
+}
+
+#[test]
+fn properties_interned_keys() {
+    let keys = KeyInterner::new();
+    let a = Properties::new_interned(&mut "x=1\ny=2\n".as_bytes(), &keys).unwrap();
+    let b = Properties::new_interned(&mut "x=3\n".as_bytes(), &keys).unwrap();
+    let c = Properties::new(&mut "x=4\n".as_bytes()).unwrap();
+    assert_eq!(keys.len(), 2);
+
+    let key = |properties: &Properties| properties.iter().next().unwrap().0.as_ptr();
+    assert_eq!(key(&a), key(&b));
+    assert_ne!(key(&a), key(&c));
+    assert_eq!(b.get("x").unwrap(), "3");
+}
+
+#[test]
+fn property_tree_navigation() {
+    let input = "a.count=2
+a.list=x y
+a.0.name=first
+a.1.name=second
+a.1.sub.value=3
+a-b=other
+a=root
+b.0=zero
+";
+    let properties = Properties::parse(input).unwrap();
+    let root = properties.tree();
+    assert_eq!(root.path(), "");
+    assert_eq!(root.children(), vec!["a", "a-b", "b"]);
+
+    let a = properties.subtree("a");
+    assert_eq!(a.path(), "a");
+    assert_eq!(a.value().unwrap(), "root");
+    assert_eq!(a.len(), 5);
+    assert_eq!(a.children(), vec!["0", "1", "count", "list"]);
+    assert_eq!(a.get("0.name").unwrap(), "first");
+    assert_eq!(a.get("").unwrap(), "root");
+    assert!(a.get("name").is_none());
+
+    let one = a.subtree("1");
+    assert_eq!(one.path(), "a.1");
+    assert_eq!(one.children(), vec!["name", "sub"]);
+    assert_eq!(one.subtree("sub").get("value").unwrap(), "3");
+    assert_eq!(properties.subtree("a.1.sub").get("value").unwrap(), "3");
+    assert_eq!(
+        one.iter().collect::<Vec<_>>(),
+        vec![("name", "second"), ("sub.value", "3")]
+    );
+
+    assert_eq!(root.count("a").unwrap(), 2);
+    assert_eq!(root.list("a").unwrap(), vec!["x", "y"]);
+    let names = a
+        .child_trees()
+        .filter_map(|child| child.get("name"))
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["first", "second"]);
+
+    let missing = properties.subtree("c.d");
+    assert!(missing.is_empty());
+    assert!(missing.children().is_empty());
+}
+
+#[test]
+fn property_tree_typed_getters() {
+    let input = "h.f=1.5E-3
+h.nan=NaN
+h.inf=-Infinity
+h.b=true
+h.i=-42
+h.t=2026-01-08 16:35:28.552 +0100
+h.bad=x
+";
+    let properties = Properties::parse(input).unwrap();
+    let tree = properties.subtree("h");
+    assert_eq!(tree.get_f64("f").unwrap(), 1.5e-3);
+    assert!(tree.get_f64("nan").unwrap().is_nan());
+    assert_eq!(tree.get_f64_non_nan("nan").unwrap(), None);
+    assert_eq!(tree.get_f64_non_nan("f").unwrap(), Some(1.5e-3));
+    assert_eq!(tree.get_f64("inf").unwrap(), f64::NEG_INFINITY);
+    assert!(tree.get_bool("b").unwrap());
+    assert_eq!(tree.get_i64("i").unwrap(), -42);
+    assert_eq!(tree.parse::<u8>("i").ok(), None);
+
+    let timestamp = tree.get_timestamp("t").unwrap();
+    assert_eq!(timestamp.year(), 2026);
+    assert_eq!(timestamp.month(), 1);
+    assert_eq!(timestamp.day(), 8);
+    assert_eq!(timestamp.hour(), 16);
+    assert_eq!(timestamp.minute(), 35);
+    assert_eq!(timestamp.second(), 28);
+    assert_eq!(timestamp.nanosecond(), 552_000_000);
+    assert_eq!(timestamp.time_zone(), "+0100");
+
+    assert!(matches!(
+        tree.get_f64("bad"),
+        Err(error::Property::InvalidValue(key)) if key == "h.bad"
+    ));
+    assert!(matches!(
+        tree.get_bool("missing"),
+        Err(error::Property::NotFound(key)) if key == "h.missing"
+    ));
+    assert!(matches!(
+        tree.subtree("sub").count("items"),
+        Err(error::Property::NotFound(key)) if key == "h.sub.items.count"
+    ));
+    assert!(matches!(
+        tree.get_timestamp("bad"),
+        Err(error::Property::InvalidValue(key)) if key == "h.bad"
+    ));
+}
+
+#[test]
+fn properties_fixtures_round_trip() {
+    let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(DATA_DIR);
+    let mut files = Vec::new();
+    properties_files(&data_path, &mut files);
+    assert!(!files.is_empty());
+
+    for path in files {
+        let mut file = fs::File::open(&path).unwrap();
+        let properties = Properties::new(&mut file).unwrap();
+        assert!(properties.timestamp().is_some(), "{path:?}");
+        assert_round_trip(&properties);
+    }
+
+    let mut file = fs::File::open(data_path.join("header.properties")).unwrap();
+    let properties = Properties::new(&mut file).unwrap();
+    assert_eq!(
+        properties
+            .get("quantitative-imaging-map.start-time")
+            .unwrap(),
+        "2026-01-08 16:35:28.552 +0100"
+    );
+}
+
+#[test]
+fn properties_fuzz_parse() {
+    const ALPHABET: &[char] = &[
+        'a', 'b', 'u', 'D', '0', '8', 'F', ' ', '\t', '\x0c', '\n', '\r', '\\', '=', ':', '#', '!',
+        'µ', '√', '😀',
+    ];
+
+    let mut rng = XorShift::new(0x2545_f491_4f6c_dd1d);
+    for _ in 0..5_000 {
+        let len = rng.next_below(64);
+        let input = (0..len)
+            .map(|_| ALPHABET[rng.next_below(ALPHABET.len())])
+            .collect::<String>();
+
+        // must not panic
+        if let Ok(properties) = Properties::parse(&input) {
+            assert_round_trip(&properties);
+        }
+    }
+}
+
+#[test]
+fn properties_fuzz_round_trip() {
+    const ALPHABET: &[char] = &[
+        'a', 'z', '0', '.', ' ', '\t', '\x0c', '\n', '\r', '\\', '=', ':', '#', '!', 'µ', '√',
+        '😀', '\u{7f}', '\u{0}',
+    ];
+
+    let mut rng = XorShift::new(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..1_000 {
+        let count = rng.next_below(8);
+        let mut input = String::new();
+        let mut expected = Vec::new();
+        for _ in 0..count {
+            let key = (0..rng.next_below(8) + 1)
+                .map(|_| ALPHABET[rng.next_below(ALPHABET.len())])
+                .collect::<String>();
+            let value = (0..rng.next_below(16))
+                .map(|_| ALPHABET[rng.next_below(ALPHABET.len())])
+                .collect::<String>();
+
+            // every character escaped, the reader must recover the exact pair
+            let pair = Properties::parse(&format!(
+                "{}={}",
+                escape_for_test(&key),
+                escape_for_test(&value)
+            ))
+            .unwrap();
+            assert_eq!(pair.get(&key), Some(value.as_str()), "{key:?}={value:?}");
+
+            input.push_str(&format!(
+                "{}={}\n",
+                escape_for_test(&key),
+                escape_for_test(&value)
+            ));
+            expected.retain(|(k, _): &(String, String)| *k != key);
+            expected.push((key, value));
+        }
+
+        let properties = Properties::parse(&input).unwrap();
+        assert_eq!(properties.len(), expected.len());
+        for (key, value) in expected.iter() {
+            assert_eq!(properties.get(key), Some(value.as_str()));
+        }
+        assert_round_trip(&properties);
+    }
+}
+
+fn assert_round_trip(properties: &Properties) {
+    let mut output = Vec::new();
+    properties.write(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.is_ascii());
+    let parsed = Properties::parse(&output).unwrap();
+    assert_eq!(&parsed, properties, "{output}");
+}
+
+/// Escape using the `\uXXXX` form for every character,
+/// independent of the writer's escaping.
+fn escape_for_test(input: &str) -> String {
+    let mut buf = [0; 2];
+    input
+        .chars()
+        .flat_map(|ch| {
+            ch.encode_utf16(&mut buf)
+                .iter()
+                .map(|unit| format!("\\u{unit:04x}"))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn properties_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            properties_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "properties") {
+            files.push(path);
+        }
+    }
+}
+
+/// Deterministic pseudo-random number generator.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn next_below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
@@ -0,0 +1,85 @@
+use jpk_reader::scope::{ScopeFile, error};
+use polars::prelude::*;
+use std::{fs, path::PathBuf};
+
+const HEADER: &str = "# date: 2026-01-08 16:35:28 CET
+# columns: t current vDeflection
+# fancyNames: \"Time\" \"Current\" \"Vertical Deflection\"
+# units: s A V
+";
+
+#[test]
+fn scope_load_data_pushdown() {
+    for (name, rows) in [
+        (
+            "delimited",
+            "0.0 1e-9 0.5\n0.001 2e-9 0.6\n0.002 3e-9 0.7\n",
+        ),
+        (
+            "whitespace",
+            "0.0  1e-9 0.5\n 0.001 2e-9 0.6\n0.002\t3e-9 0.7\n",
+        ),
+    ] {
+        let path = temp_path(&format!("scope_load_data_pushdown-{name}"));
+        fs::write(&path, format!("{HEADER}{rows}")).unwrap();
+
+        let file = ScopeFile::open(&path).unwrap();
+        let df = file
+            .load_data()
+            .unwrap()
+            .select([col("vDeflection")])
+            .slice(1, 2)
+            .collect()
+            .unwrap();
+        assert_eq!(df.shape(), (2, 1), "{name}");
+        let values = df.column("vDeflection").unwrap().f64().unwrap();
+        assert_eq!(values.get(0), Some(0.6), "{name}");
+        assert_eq!(values.get(1), Some(0.7), "{name}");
+
+        let df = file.load_data().unwrap().collect().unwrap();
+        assert_eq!(df.shape(), (3, 3), "{name}");
+    }
+}
+
+#[test]
+fn scope_load_data_ragged_row() {
+    for (name, rows) in [
+        ("delimited", "0.0 1e-9 0.5\n0.001 2e-9\n0.002 3e-9 0.7\n"),
+        ("whitespace", "0.0  1e-9 0.5\n0.001  2e-9\n"),
+    ] {
+        let path = temp_path(&format!("scope_load_data_ragged_row-{name}"));
+        fs::write(&path, format!("{HEADER}{rows}")).unwrap();
+
+        let file = ScopeFile::open(&path).unwrap();
+        let err = file.load_data().unwrap().collect().unwrap_err();
+        let expected = error::Scope::InvalidRow {
+            line: 6,
+            expected: 3,
+            found: 2,
+        };
+        assert!(
+            err.to_string().contains(&expected.to_string()),
+            "{name}: {err}"
+        );
+    }
+}
+
+#[test]
+fn scope_load_data_column_names_row() {
+    let path = temp_path("scope_load_data_column_names_row");
+    fs::write(
+        &path,
+        "# date: 2026-01-08\nt current\n0.0 1e-9\n0.001 2e-9\n",
+    )
+    .unwrap();
+
+    let file = ScopeFile::open(&path).unwrap();
+    let df = file.load_data().unwrap().collect().unwrap();
+    assert_eq!(df.get_column_names(), ["t", "current"]);
+    assert_eq!(df.height(), 2);
+}
+
+/// Path of a scope file unique to the test, in the target's temporary directory.
+fn temp_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{name}.out"))
+}
@@ -12,6 +12,14 @@ fn voltage_spectroscopy_load_data() {
     eprintln!("{:?}", df.head(Some(10)));
 }
 
+#[test]
+fn voltage_spectroscopy_thumbnail() {
+    let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(DATA_FILE);
+    let mut reader = jpk::FileReader::new(data_path).unwrap();
+    assert!(reader.thumbnail().unwrap().is_none());
+    assert!(reader.previews().unwrap().is_empty());
+}
+
 #[test]
 fn voltage_spectroscopy_load_dir() {
     let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(COLLECTION_DIR);
@@ -19,3 +27,90 @@ fn voltage_spectroscopy_load_dir() {
     let df = reader.load_data_all().unwrap();
     eprintln!("{:?}", df.head(Some(10)));
 }
+
+#[test]
+fn voltage_spectroscopy_map() {
+    use jpk_reader::dataset::index::{IndexQuery, Pixel, PixelRect};
+
+    let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(COLLECTION_DIR);
+    let map = jpk::VoltageSpectroscopyMap::new(data_path).unwrap();
+    assert_eq!(map.len(), 64);
+    assert_eq!(map.indices().collect::<Vec<_>>(), (0..64).collect::<Vec<_>>());
+
+    let grid = map.grid();
+    assert_eq!((grid.i_length(), grid.j_length()), (8, 8));
+    assert!((grid.x_step() - 1.25e-6).abs() < 1e-9);
+    assert!((grid.u_length() - 1e-5).abs() < 1e-9);
+    assert!(grid.x_center().abs() < 1e-9);
+
+    // rows are scanned in alternating directions
+    assert_eq!(map.index_to_pixel(7), Some(Pixel::new(7, 0)));
+    assert_eq!(map.index_to_pixel(8), Some(Pixel::new(7, 1)));
+    assert_eq!(map.pixel_to_index(&Pixel::new(0, 1)), Some(15));
+    assert_eq!(map.pixel_to_index(&Pixel::new(8, 0)), None);
+
+    let rect = PixelRect::new(Pixel::new(0, 0), Pixel::new(1, 1));
+    let query = IndexQuery::PixelRect(rect);
+    assert_eq!(map.query_indices(&query).unwrap(), vec![0, 1, 15, 14]);
+    assert!(matches!(
+        map.query_indices(&IndexQuery::Pixel(Pixel::new(0, 8))),
+        Err(jpk::error::Map::OutOfBounds(_))
+    ));
+
+    let df = map.load_data(&query).unwrap();
+    let pixels = df
+        .select(["i", "j"])
+        .unwrap()
+        .unique_stable(None, polars::prelude::UniqueKeepStrategy::First, None)
+        .unwrap();
+    assert_eq!(pixels.height(), 4);
+    for column in ["segment", "index", "x", "y"] {
+        assert!(df.column(column).is_ok());
+    }
+}
+
+#[test]
+fn voltage_spectroscopy_transport() {
+    use jpk_reader::analysis::voltage_spectroscopy::{self as iv, RampPair, Settings, Sweep};
+
+    let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(DATA_FILE);
+    let mut reader = jpk::FileReader::new(data_path).unwrap();
+    let sweeps = Sweep::from_reader(&mut reader).unwrap();
+    assert_eq!(sweeps.len(), 1);
+    let sweep = &sweeps[0];
+    assert_eq!(sweep.pair(), &RampPair::new(1, 0));
+    assert_eq!(sweep.forward().len(), 2048);
+    assert!(sweep.forward().bias()[0] < sweep.forward().bias()[2047]);
+
+    let transport = sweep.analyze(&Settings::default()).unwrap();
+    assert!(transport.zero_bias_conductance().is_finite());
+    assert!(transport.rectification_ratio() > 0.0);
+    assert!(transport.hysteresis_area().is_finite());
+
+    let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(COLLECTION_DIR);
+    let df = iv::analyze_dir(&jpk::DirReader::new(data_path), &Settings::default()).unwrap();
+    assert_eq!(df.height(), 65);
+    assert_eq!(df.column("zero_bias_conductance").unwrap().null_count(), 0);
+    assert_eq!(df.column("error").unwrap().null_count(), 65);
+}
+
+#[test]
+fn voltage_spectroscopy_transport_file_error() {
+    use jpk_reader::analysis::voltage_spectroscopy::{self as iv, Settings};
+
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("voltage_spectroscopy_transport_file_error");
+    std::fs::create_dir_all(&dir).unwrap();
+    let data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(DATA_FILE);
+    std::fs::copy(&data_path, dir.join("a.jpk-voltage-ramp")).unwrap();
+    std::fs::write(dir.join("b.jpk-voltage-ramp"), "not an archive").unwrap();
+
+    let df = iv::analyze_dir(&jpk::DirReader::new(&dir), &Settings::default()).unwrap();
+    assert_eq!(df.height(), 2);
+    let errors = df.column("error").unwrap().str().unwrap();
+    assert!(errors.get(0).is_none());
+    assert!(errors.get(1).unwrap().contains("b.jpk-voltage-ramp"));
+    for column in ["x", "forward_segment", "zero_bias_conductance"] {
+        assert_eq!(df.column(column).unwrap().null_count(), 1, "{column}");
+    }
+}